@@ -0,0 +1,53 @@
+//! Compare parsing WKT serially with read_wkt_geometries, and in parallel with
+//! read_wkt_geometries_parallel
+//!
+//!     cargo bench --no-default-features --features parallel --bench read_wkt
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use generative::io::{read_wkt_geometries, read_wkt_geometries_parallel};
+
+/// Generate a WKT input of LINESTRINGs with the given number of vertices each
+fn generate_input(lines: usize, vertices: usize) -> String {
+    let mut input = String::new();
+    for i in 0..lines {
+        input += "LINESTRING(";
+        for j in 0..vertices {
+            if j > 0 {
+                input += ",";
+            }
+            input += &format!("{}.{:03} {}.{:03}", i, j, j, (i * j) % 1000);
+        }
+        input += ")\n";
+    }
+    input
+}
+
+/// The fastest of the given number of runs
+fn fastest(runs: usize, mut f: impl FnMut() -> usize) -> Duration {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn main() {
+    let runs = 5;
+    let vertices = 20;
+    println!("LINESTRINGs with {vertices} vertices, fastest of {runs} runs");
+    for lines in [1_000, 10_000, 100_000] {
+        let input = generate_input(lines, vertices);
+        let serial = fastest(runs, || read_wkt_geometries(input.as_bytes()).count());
+        let parallel = fastest(runs, || {
+            read_wkt_geometries_parallel(input.as_bytes()).len()
+        });
+        println!(
+            "{lines:>7} lines: serial {serial:>10.2?}  parallel {parallel:>10.2?}  speedup {:.2}x",
+            serial.as_secs_f64() / parallel.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,12 @@
+#!/bin/bash
+set -o errexit
+set -o pipefail
+set -o nounset
+set -o noclobber
+
+# BEGIN HATCH_SNIPPET1
+echo "POLYGON((0 0, 4 0, 4 4, 0 4, 0 0), (1 1, 3 1, 3 3, 1 3, 1 1))" |
+    hatch --spacing=0.2 --angle=30 --cross=-30 --outline |
+    wkt2svg --scale=50 --output=examples/hatch/cross.svg
+# END HATCH_SNIPPET1
+extract_snippet HATCH_SNIPPET1
@@ -31,6 +31,8 @@ pub mod ffi {
     struct PolygonizationResult {
         polygons: Vec<LineStringShim>,
         dangles: Vec<LineStringShim>,
+        cut_edges: Vec<LineStringShim>,
+        invalid_ring_lines: Vec<LineStringShim>,
     }
 }
 
@@ -7,6 +7,7 @@
 #include <generative/noding/geometry-noder.h>
 
 #include <geos/geom/Geometry.h>
+#include <geos/geom/LineString.h>
 #include <geos/noding/snap/SnappingNoder.h>
 #include <geos/operation/overlay/snap/GeometrySnapper.h>
 #include <geos/operation/polygonize/Polygonizer.h>
@@ -39,6 +40,19 @@ node(const GeometryCollectionShim& rust_geoms, double tolerance) noexcept
     return graph_shim;
 }
 
+inline LineStringShim copy_geos_linestring_to_rust(const geos::geom::LineString& linestring)
+{
+    LineStringShim result;
+    const auto* coords = linestring.getCoordinatesRO();
+    result.vec.reserve(coords->size());
+    for (size_t i = 0; i < coords->size(); i++)
+    {
+        const auto coord = coords->getAt(i);
+        result.vec.push_back(CoordShim{coord.x, coord.y});
+    }
+    return result;
+}
+
 [[nodiscard]] inline PolygonizationResult polygonize(const GeometryGraphShim& graph) noexcept
 {
     const auto owned_edges = graph.inner().get_edges();
@@ -58,38 +72,35 @@ node(const GeometryCollectionShim& rust_geoms, double tolerance) noexcept
     polygonizer.add(&non_owned_edges);
 
     const auto polys = polygonizer.getPolygons();
-    // The dangles are just pointers back to the LineString pointers we passed in?
+    // The dangles and cut edges are just pointers back to the LineString pointers we passed in?
     const auto dangles = polygonizer.getDangles();
+    const auto cut_edges = polygonizer.getCutEdges();
+    const auto& invalid_ring_lines = polygonizer.getInvalidRingLines();
 
     PolygonizationResult retval;
     retval.polygons.reserve(polys.size());
     retval.dangles.reserve(dangles.size());
+    retval.cut_edges.reserve(cut_edges.size());
+    retval.invalid_ring_lines.reserve(invalid_ring_lines.size());
 
     for (const auto& poly : polys)
     {
-        LineStringShim result;
-        const auto* shell = poly->getExteriorRing();
-        result.vec.reserve(shell->getNumPoints());
-        const auto* coords = shell->getCoordinatesRO();
-        for (size_t i = 0; i < coords->size(); i++)
-        {
-            const auto coord = coords->getAt(i);
-            result.vec.push_back(CoordShim{coord.x, coord.y});
-        }
-        retval.polygons.push_back(result);
+        retval.polygons.push_back(copy_geos_linestring_to_rust(*poly->getExteriorRing()));
     }
 
     for (const auto* dangle : dangles)
     {
-        LineStringShim result;
-        const auto* coords = dangle->getCoordinatesRO();
-        result.vec.reserve(coords->size());
-        for (size_t i = 0; i < coords->size(); i++)
-        {
-            const auto coord = coords->getAt(i);
-            result.vec.push_back(CoordShim{coord.x, coord.y});
-        }
-        retval.dangles.push_back(result);
+        retval.dangles.push_back(copy_geos_linestring_to_rust(*dangle));
+    }
+
+    for (const auto* cut_edge : cut_edges)
+    {
+        retval.cut_edges.push_back(copy_geos_linestring_to_rust(*cut_edge));
+    }
+
+    for (const auto& invalid_ring_line : invalid_ring_lines)
+    {
+        retval.invalid_ring_lines.push_back(copy_geos_linestring_to_rust(*invalid_ring_line));
     }
 
     return retval;
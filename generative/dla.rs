@@ -1,5 +1,8 @@
+use std::collections::{HashSet, VecDeque};
+use std::f64::consts::TAU;
 use std::io::{BufWriter, Write};
 
+use geo::{coord, ConvexHull, Line, MultiPoint, Point, Polygon, Rect};
 use kdtree::distance::squared_euclidean;
 use kdtree::KdTree;
 use log::{debug, info, trace, warn};
@@ -17,6 +20,8 @@ pub struct Particle {
     pub coordinates: [f64; 2],
     /// The number of times this particle has been attempted to be joined to.
     pub join_attempts: usize,
+    /// The seed cluster this particle descends from.
+    pub colony: usize,
 }
 
 /// Data associated with the nodes.
@@ -25,6 +30,95 @@ type NodeDataType = Particle;
 type EdgeDataType = ();
 pub type GraphType = Graph<NodeDataType, EdgeDataType, Undirected>;
 
+/// How to lay out the initial seed particles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeedPattern {
+    /// A single seed at the origin, or multiple seeds scattered randomly around it
+    Random,
+    /// Seeds evenly spaced along a horizontal line with the given length, centered on the origin
+    Line(f64),
+    /// Seeds evenly spaced around a circle with the given radius, centered on the origin
+    Circle(f64),
+    /// Seeds on a square grid with the given spacing, centered on the origin
+    ///
+    /// The grid is filled row by row, so it may have a partial top row.
+    Grid(f64),
+}
+
+/// Structural metrics of a grown model, for comparing the results of different parameters
+///
+/// A branch is a chain of edges between two particles that are seeds, leaves, or junctions. The
+/// angles are in degrees, and are NaN if there's nothing to measure them on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DlaStats {
+    /// The number of particles, including the seeds
+    pub particles: usize,
+    /// The number of branches
+    pub branches: usize,
+    /// The mean length of the branches, measured along their edges
+    pub mean_branch_length: f64,
+    /// The mean angle between each branch and the edge leading into the junction it grows from
+    pub mean_branch_angle: f64,
+    /// The mean angle each branch turns by at each particle along it
+    pub mean_curl: f64,
+    /// The box-counting estimate of the fractal dimension of the particles
+    pub fractal_dimension: f64,
+}
+
+/// The tunable parameters of a [Model]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelParams {
+    /// Particle dimensionality. Only 2D is supported.
+    pub dimensions: u8,
+    /// The number of seed particles
+    pub seeds: usize,
+    /// How to lay out the seed particles
+    pub seed_pattern: SeedPattern,
+    /// The number of colonies to split the seed particles into
+    pub colonies: usize,
+    /// Whether particles may join one colony while touching another
+    pub allow_merge: bool,
+    /// The random seed, or zero to pick one
+    pub seed: u64,
+    /// The spacing between joined particles
+    pub particle_spacing: f64,
+    /// The distance threshold for joining two particles
+    pub attraction_distance: f64,
+    /// The minimum random walk step
+    pub min_move_distance: f64,
+    /// The number of join attempts before a particle sticks
+    pub stubbornness: usize,
+    /// The probability that a particle sticks, after the stubbornness
+    pub stickiness: f64,
+    /// The (width, height) of the periodic domain centered on the origin, if any
+    pub periodic: Option<[f64; 2]>,
+    /// The point the random walk is pulled toward, and the strength of the pull, if any
+    pub attractor: Option<([f64; 2], f64)>,
+    /// Whether to keep a copy of the indexed coordinates, for [Model::index_regions]
+    pub record_index: bool,
+}
+
+impl Default for ModelParams {
+    fn default() -> Self {
+        Self {
+            dimensions: 2,
+            seeds: 1,
+            seed_pattern: SeedPattern::Random,
+            colonies: 1,
+            allow_merge: false,
+            seed: 0,
+            particle_spacing: 1.0,
+            attraction_distance: 3.0,
+            min_move_distance: 1.0,
+            stubbornness: 0,
+            stickiness: 1.0,
+            periodic: None,
+            attractor: None,
+            record_index: false,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Model {
     /// The particles and their parent associations.
@@ -36,6 +130,9 @@ pub struct Model {
     /// TODO: Variable dimensionality
     /// TODO: Figure out how to store a reference to an ndarray::Array1 in the index
     index: KdTree<DimensionType, NodeIndex, [DimensionType; 2]>,
+    /// The coordinates added to the spatial index, in order, so that its partitioning can be
+    /// reconstructed by [Model::index_regions]. Only recorded if [ModelParams::record_index] is set.
+    indexed: Option<Vec<[DimensionType; 2]>>,
     rng: StdRng,
 
     /// Particle dimensionality
@@ -43,6 +140,14 @@ pub struct Model {
     dimensions: u8,
 
     // Tunable parameters
+    /// The (width, height) of the periodic domain centered on the origin, if any
+    periodic: Option<[f64; 2]>,
+    /// The point the random walk is pulled toward, and the strength of the pull, if any
+    attractor: Option<([f64; 2], f64)>,
+    seeds: usize,
+    seed_pattern: SeedPattern,
+    colonies: usize,
+    allow_merge: bool,
     bounding_radius: f64,
     particle_spacing: f64,
     attraction_distance: f64,
@@ -53,17 +158,23 @@ pub struct Model {
 
 impl Model {
     /// Create a new model with the given tunable parameters.
-    #[allow(clippy::too_many_arguments)]
-    pub fn new(
-        dimensions: u8,
-        seeds: usize,
-        seed: u64,
-        particle_spacing: f64,
-        attraction_distance: f64,
-        min_move_distance: f64,
-        stubbornness: usize,
-        stickiness: f64,
-    ) -> Model {
+    pub fn new(params: ModelParams) -> Model {
+        let ModelParams {
+            dimensions,
+            seeds,
+            seed_pattern,
+            colonies,
+            allow_merge,
+            seed,
+            particle_spacing,
+            attraction_distance,
+            min_move_distance,
+            stubbornness,
+            stickiness,
+            periodic,
+            attractor,
+            record_index,
+        } = params;
         let seed = Model::generate_random_seed_if_not_specified(seed);
         info!("Intializing rng with seed {}", seed);
 
@@ -71,22 +182,53 @@ impl Model {
             warn!("{} dimensions not supported (yet?). Using 2D.", dimensions);
         }
 
-        debug!("Initializing model with parameters <seeds={}, seed={}, particle_spacing={}, attraction_distance={}, min_move_distance={}, stubbornness={}, stickiness={}>",
+        let seeds = if seeds == 0 {
+            warn!("Cannot run DLA model with no initial seed particles. Using one seed.");
+            1
+        } else {
+            seeds
+        };
+        let colonies = if colonies == 0 {
+            warn!("Cannot run DLA model with no colonies. Using one colony.");
+            1
+        } else if colonies > seeds {
+            warn!(
+                "Cannot have more colonies than seed particles. Using {} colonies.",
+                seeds
+            );
+            seeds
+        } else {
+            colonies
+        };
+
+        debug!("Initializing model with parameters <seeds={}, seed_pattern={:?}, colonies={}, allow_merge={}, seed={}, particle_spacing={}, attraction_distance={}, min_move_distance={}, stubbornness={}, stickiness={}, periodic={:?}, attractor={:?}>",
                seeds,
+               seed_pattern,
+               colonies,
+               allow_merge,
                seed,
                particle_spacing,
                attraction_distance,
                min_move_distance,
                stubbornness,
                stickiness,
+               periodic,
+               attractor,
         );
 
         let mut model = Model {
             particle_graph: Graph::new_undirected(),
             // TODO: Variable dimensionality
             index: KdTree::new(2),
+            indexed: record_index.then(Vec::new),
             rng: StdRng::seed_from_u64(seed),
             dimensions: 2,
+            periodic,
+            attractor,
+            seeds,
+            seed_pattern,
+            colonies,
+            allow_merge,
             bounding_radius: 5.0,
             particle_spacing,
             attraction_distance,
@@ -95,19 +237,258 @@ impl Model {
             stickiness,
         };
 
-        if seeds == 0 {
-            warn!("Cannot run DLA model with no initial seed particles. Using one seed.");
-        }
-        model.add_seeds(if seeds == 0 { 1 } else { seeds });
+        model.add_seeds(seeds);
 
         model
     }
 
+    /// Thicken the particle graph into polygons, for rendering as solid forms
+    ///
+    /// Each edge is buffered into a capsule, whose radius tapers linearly with the number of edges
+    /// between a particle and the closest seed particle; from `thickness / 2` at the seeds to
+    /// `(1 - taper) * thickness / 2` at the particles furthest from any seed. The capsules overlap,
+    /// rather than being unioned together.
+    pub fn thicken(&self, thickness: f64, taper: f64) -> Vec<Polygon> {
+        let depths = self.depths();
+        let max_depth = depths.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let radius = |idx: NodeIndex| {
+            thickness / 2.0 * (1.0 - taper * depths[idx.index()] as f64 / max_depth)
+        };
+
+        let mut polygons = Vec::new();
+        for idx in self.particle_graph.node_indices() {
+            if self.particle_graph.neighbors(idx).next().is_none() {
+                let coords = self.particle_graph[idx].coordinates;
+                polygons.push(capsule(coords, radius(idx), coords, radius(idx)));
+            }
+        }
+        for edge in self.particle_graph.edge_references() {
+            let a = self.particle_graph[edge.source()].coordinates;
+            // Thicken edges across the periodic boundary on the same side as their source
+            let b = self.nearest_image(&self.particle_graph[edge.target()].coordinates, &a);
+            polygons.push(capsule(a, radius(edge.source()), b, radius(edge.target())));
+        }
+        polygons
+    }
+
+    /// Draw each particle as a circle polygon with the given radius, for rendering as bubbles
+    ///
+    /// The render radius is independent of the particle spacing the model grew with, so the
+    /// circles can overlap or leave gaps between neighboring particles.
+    pub fn circles(&self, radius: f64) -> Vec<Polygon> {
+        self.particle_graph
+            .node_weights()
+            .map(|p| capsule(p.coordinates, radius, p.coordinates, radius))
+            .collect()
+    }
+
+    /// Measure the structure of the particle graph
+    pub fn statistics(&self) -> DlaStats {
+        let graph = &self.particle_graph;
+        let is_seed = |idx: NodeIndex| idx.index() < self.seeds;
+        let is_branch_end = |idx: NodeIndex| is_seed(idx) || graph.neighbors(idx).count() != 2;
+        let other_end = |edge: petgraph::graph::EdgeReference<()>, idx: NodeIndex| {
+            if edge.source() == idx {
+                edge.target()
+            } else {
+                edge.source()
+            }
+        };
+
+        // Walk each branch from one of its ends, measuring its length and how much it turns
+        let mut visited = vec![false; graph.edge_count()];
+        let (mut branches, mut total_length) = (0, 0.0);
+        let (mut turns, mut total_turn) = (0, 0.0);
+        for start in graph.node_indices().filter(|idx| is_branch_end(*idx)) {
+            for first in graph.edges(start) {
+                if visited[first.id().index()] {
+                    continue;
+                }
+                visited[first.id().index()] = true;
+                branches += 1;
+
+                let mut idx = other_end(first, start);
+                let mut curr = graph[start].coordinates;
+                let mut next = self.nearest_image(&graph[idx].coordinates, &curr);
+                let mut direction = [next[0] - curr[0], next[1] - curr[1]];
+                total_length += Model::length(&direction);
+                while !is_branch_end(idx) {
+                    let Some(edge) = graph.edges(idx).find(|e| !visited[e.id().index()]) else {
+                        break;
+                    };
+                    visited[edge.id().index()] = true;
+                    idx = other_end(edge, idx);
+                    curr = next;
+                    next = self.nearest_image(&graph[idx].coordinates, &curr);
+                    let next_direction = [next[0] - curr[0], next[1] - curr[1]];
+                    total_turn += Model::angle_between(&direction, &next_direction);
+                    turns += 1;
+                    total_length += Model::length(&next_direction);
+                    direction = next_direction;
+                }
+            }
+        }
+
+        // The angle between each particle growing off a junction, and the junction's parent edge
+        let depths = self.depths();
+        let parent = |idx: NodeIndex| {
+            graph
+                .neighbors(idx)
+                .find(|n| depths[n.index()] + 1 == depths[idx.index()])
+        };
+        let (mut junction_children, mut total_angle) = (0, 0.0);
+        for idx in graph.node_indices().filter(|idx| !is_seed(*idx)) {
+            let Some(junction) = parent(idx) else {
+                continue;
+            };
+            if is_seed(junction) || graph.neighbors(junction).count() < 3 {
+                continue;
+            }
+            let Some(grandparent) = parent(junction) else {
+                continue;
+            };
+            let a = graph[junction].coordinates;
+            let b = self.nearest_image(&graph[idx].coordinates, &a);
+            let c = self.nearest_image(&graph[grandparent].coordinates, &a);
+            total_angle +=
+                Model::angle_between(&[a[0] - c[0], a[1] - c[1]], &[b[0] - a[0], b[1] - a[1]]);
+            junction_children += 1;
+        }
+
+        let mean = |total: f64, count: usize| {
+            if count == 0 {
+                f64::NAN
+            } else {
+                total / count as f64
+            }
+        };
+        DlaStats {
+            particles: graph.node_count(),
+            branches,
+            mean_branch_length: if branches == 0 {
+                0.0
+            } else {
+                total_length / branches as f64
+            },
+            mean_branch_angle: mean(total_angle, junction_children),
+            mean_curl: mean(total_turn, turns),
+            fractal_dimension: self.box_counting_dimension(),
+        }
+    }
+
+    /// Estimate the fractal dimension of the particles by box-counting
+    ///
+    /// The particles' bounding square is halved repeatedly, down to the particle spacing, and the
+    /// dimension is the slope of log(occupied boxes) against log(1 / box size). Returns NaN if the
+    /// particles don't span at least two box sizes.
+    fn box_counting_dimension(&self) -> f64 {
+        let coords: Vec<_> = self
+            .particle_graph
+            .node_weights()
+            .map(|p| p.coordinates)
+            .collect();
+        let mut min = [f64::INFINITY; 2];
+        let mut max = [f64::NEG_INFINITY; 2];
+        for c in &coords {
+            for i in 0..2 {
+                min[i] = min[i].min(c[i]);
+                max[i] = max[i].max(c[i]);
+            }
+        }
+        let side = (max[0] - min[0]).max(max[1] - min[1]);
+
+        const MAX_SAMPLES: usize = 16;
+        let mut samples = Vec::new();
+        let mut occupied = HashSet::new();
+        let mut size = side / 2.0;
+        while size >= self.particle_spacing && samples.len() < MAX_SAMPLES {
+            // The particles on the far sides of the bounding square belong to the last box
+            let boxes = (side / size).round() as i64;
+            let to_box = |v: f64, min: f64| (((v - min) / size) as i64).min(boxes - 1);
+            occupied.clear();
+            occupied.extend(
+                coords
+                    .iter()
+                    .map(|c| (to_box(c[0], min[0]), to_box(c[1], min[1]))),
+            );
+            samples.push(((1.0 / size).ln(), (occupied.len() as f64).ln()));
+            size /= 2.0;
+        }
+        if samples.len() < 2 {
+            return f64::NAN;
+        }
+
+        // The least squares slope
+        let n = samples.len() as f64;
+        let (sx, sy) = samples
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+        let sxx: f64 = samples.iter().map(|(x, _)| x * x).sum();
+        let sxy: f64 = samples.iter().map(|(x, y)| x * y).sum();
+        (n * sxy - sx * sy) / (n * sxx - sx * sx)
+    }
+
+    /// The seed particles, which are the first particles in the graph
+    pub fn seed_points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.particle_graph
+            .node_weights()
+            .take(self.seeds)
+            .map(|p| Point::from(p.coordinates))
+    }
+
+    /// The edge from the most recently added particle to the particle it joined
+    ///
+    /// Returns `None` if no particles have been added beyond the seeds. Streaming this edge after
+    /// each particle is added writes the whole structure without waiting for the model to finish.
+    pub fn newest_edge(&self) -> Option<Line> {
+        let edge = self.particle_graph.edge_references().next_back()?;
+        let particle = self.particle_graph[edge.source()].coordinates;
+        let parent = self.particle_graph[edge.target()].coordinates;
+        Some(Line::new(parent, particle))
+    }
+
+    /// The number of edges between each particle and the closest seed particle
+    fn depths(&self) -> Vec<usize> {
+        let mut depths = vec![0; self.particle_graph.node_count()];
+        let mut visited = vec![false; self.particle_graph.node_count()];
+        let mut queue = VecDeque::new();
+
+        // The seed particles are the first particles added to the graph, and every other particle
+        // is joined to an existing particle, so every particle is reachable from a seed.
+        for idx in self.particle_graph.node_indices().take(self.seeds) {
+            visited[idx.index()] = true;
+            queue.push_back(idx);
+        }
+        while let Some(idx) = queue.pop_front() {
+            for neighbor in self.particle_graph.neighbors(idx) {
+                if !visited[neighbor.index()] {
+                    visited[neighbor.index()] = true;
+                    depths[neighbor.index()] = depths[idx.index()] + 1;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        depths
+    }
+
     /// Add the specified number of particles to the model.
     pub fn run(&mut self, particles: usize) {
+        self.run_with_callback(particles, |_, _| {});
+    }
+
+    /// Add the specified number of particles to the model, calling the given callback after each
+    /// particle is added.
+    ///
+    /// The callback is passed the model, and the 0-based index of the particle that was just added
+    /// (not counting the seed particles).
+    pub fn run_with_callback<F>(&mut self, particles: usize, mut callback: F)
+    where
+        F: FnMut(&Model, usize),
+    {
         debug!("Adding {} particles", particles);
-        for _ in 0..particles {
+        for i in 0..particles {
             self.add_particle();
+            callback(self, i);
         }
     }
 
@@ -131,6 +512,11 @@ impl Model {
             }
 
             if distance < self.attraction_distance {
+                if self.is_contested(&coords, nearest_index) {
+                    // Keep the colonies apart by starting the walk over somewhere else
+                    coords = self.generate_random_coord();
+                    continue;
+                }
                 if self.attempt_to_join(&mut coords, nearest_index) {
                     trace!("Added particle POINT({} {})", coords[0], coords[1]);
                     return;
@@ -140,39 +526,239 @@ impl Model {
                 let v = &coords;
                 let m = f64::max(self.min_move_distance, distance - self.attraction_distance);
                 let u = Model::norm(&[self.rng.gen_range(0.0..1.0), self.rng.gen_range(0.0..1.0)]);
-                coords = [v[0] + u[0] * m, v[1] + u[1] * m];
+                // Bias the direction of the step toward (or away from) the attractor
+                let p = self.attractor_pull(v);
+                let biased = [u[0] + p[0], u[1] + p[1]];
+                let u = if Model::length(&biased) > f64::EPSILON {
+                    Model::norm(&biased)
+                } else {
+                    u
+                };
 
-                if Model::length(&coords) > self.bounding_radius * 2.0 {
-                    coords = self.generate_random_coord();
+                if self.periodic.is_some() {
+                    // Particles farther away than the ghost margin might be missed, so don't step
+                    // far enough to jump past them
+                    let m = m.min(self.ghost_margin() / 2.0);
+                    coords = self.wrap(&[v[0] + u[0] * m, v[1] + u[1] * m]);
+                } else {
+                    coords = [v[0] + u[0] * m, v[1] + u[1] * m];
+                    if Model::length(&coords) > self.bounding_radius * 2.0 {
+                        coords = self.generate_random_coord();
+                    }
                 }
             }
         }
     }
 
+    /// The pull toward the attractor point, relative to the unit length random walk direction
+    ///
+    /// The pull is inversely proportional to the distance to the attractor, so particles close to
+    /// the attractor walk nearly straight toward it, and far away particles walk nearly randomly.
+    /// A negative strength pushes the particles away from the attractor instead.
+    fn attractor_pull(&self, coords: &[f64; 2]) -> [f64; 2] {
+        let Some((attractor, strength)) = self.attractor else {
+            return [0.0, 0.0];
+        };
+        // Pull toward whichever copy of the attractor is closest in a periodic domain
+        let attractor = self.nearest_image(&attractor, coords);
+        let delta = [attractor[0] - coords[0], attractor[1] - coords[1]];
+        let distance = Model::length(&delta);
+        if distance < f64::EPSILON {
+            return [0.0, 0.0];
+        }
+        let pull = strength / distance;
+        [delta[0] / distance * pull, delta[1] / distance * pull]
+    }
+
+    /// Whether joining the given particle would let two colonies grow into each other
+    ///
+    /// Checks where the joining particle would be placed, rather than where the walker is, so that
+    /// no particle is ever within the attraction distance of another colony. Always false if the
+    /// colonies are allowed to merge.
+    fn is_contested(&self, coords: &[f64; 2], parent_index: NodeIndex) -> bool {
+        if self.allow_merge || self.colonies == 1 {
+            return false;
+        }
+
+        let parent = &self.particle_graph[parent_index];
+        let colony = parent.colony;
+        let parent_coords = self.nearest_image(&parent.coordinates, coords);
+        let placed = self.wrap(&Model::lerp(&parent_coords, coords, self.particle_spacing));
+        self.index
+            .within(&placed, self.attraction_distance, &squared_euclidean)
+            .expect("Failed to find nearby particles")
+            .iter()
+            .any(|(_, index)| self.particle_graph[**index].colony != colony)
+    }
+
     /// Add starting seeds to the DLA model.
+    ///
+    /// The seeds are assigned to the colonies round-robin.
     fn add_seeds(&mut self, particles: usize) {
-        debug!("Adding {} seed particles", particles);
+        debug!(
+            "Adding {} seed particles in a {:?} pattern",
+            particles, self.seed_pattern
+        );
 
-        for _ in 0..particles {
-            let coords = self.generate_random_coord();
+        for i in 0..particles {
+            let coordinates = match self.seed_pattern {
+                SeedPattern::Random => self.random_seed_coord(particles),
+                pattern => {
+                    let coords = Model::pattern_seed_coord(pattern, i, particles);
+                    // Spawn the random walkers far enough out to reach every seed
+                    self.bounding_radius = self
+                        .bounding_radius
+                        .max(Model::length(&coords) + self.attraction_distance);
+                    match self.periodic {
+                        Some(_) => self.wrap(&coords),
+                        None => coords,
+                    }
+                }
+            };
             let particle = Particle {
                 // TODO: Variable dimensionality
-                coordinates: if particles == 1 {
-                    [0.0, 0.0]
-                } else {
-                    // TODO: Maybe there's other seed patterns that could be neat.
-                    [
-                        coords[0] * (5.0 + particles as f64 / 10.0),
-                        coords[1] * (5.0 + particles as f64 / 10.0),
-                    ]
-                },
+                coordinates,
                 join_attempts: 0,
+                colony: i % self.colonies,
             };
             let particle_index = self.particle_graph.add_node(particle);
             // TODO: How to avoid copying the coordinates?
-            self.index
-                .add(particle.coordinates, particle_index)
-                .expect("Failed to add seed to spatial index");
+            self.add_to_index(particle.coordinates, particle_index);
+        }
+    }
+
+    fn random_seed_coord(&mut self, particles: usize) -> [f64; 2] {
+        let coords = self.generate_random_coord();
+        if particles == 1 {
+            [0.0, 0.0]
+        } else if self.periodic.is_some() {
+            coords
+        } else {
+            [
+                coords[0] * (5.0 + particles as f64 / 10.0),
+                coords[1] * (5.0 + particles as f64 / 10.0),
+            ]
+        }
+    }
+
+    /// The coordinates of the i'th of the given number of seeds in a non-random pattern
+    fn pattern_seed_coord(pattern: SeedPattern, i: usize, particles: usize) -> [f64; 2] {
+        match pattern {
+            SeedPattern::Random => unreachable!("Random seeds aren't in a pattern"),
+            SeedPattern::Line(length) => {
+                if particles == 1 {
+                    return [0.0, 0.0];
+                }
+                let t = i as f64 / (particles - 1) as f64;
+                [length * (t - 0.5), 0.0]
+            }
+            SeedPattern::Circle(radius) => {
+                let theta = TAU * i as f64 / particles as f64;
+                [radius * theta.cos(), radius * theta.sin()]
+            }
+            SeedPattern::Grid(spacing) => {
+                let columns = (particles as f64).sqrt().ceil() as usize;
+                let rows = particles.div_ceil(columns);
+                let (row, column) = (i / columns, i % columns);
+                [
+                    spacing * (column as f64 - (columns - 1) as f64 / 2.0),
+                    spacing * (row as f64 - (rows - 1) as f64 / 2.0),
+                ]
+            }
+        }
+    }
+
+    /// Add the given particle to the spatial index
+    ///
+    /// For periodic domains, particles near the boundary also get ghost copies on the opposite
+    /// side of the domain, so that nearest neighbor lookups find particles across the boundary.
+    fn add_to_index(&mut self, coords: [f64; 2], particle_index: NodeIndex) {
+        self.index
+            .add(coords, particle_index)
+            .expect("Failed to add particle to spatial index");
+        if let Some(indexed) = &mut self.indexed {
+            indexed.push(coords);
+        }
+
+        let Some([width, height]) = self.periodic else {
+            return;
+        };
+        let margin = self.ghost_margin();
+        for dx in [-width, 0.0, width] {
+            for dy in [-height, 0.0, height] {
+                if dx == 0.0 && dy == 0.0 {
+                    continue;
+                }
+                let ghost = [coords[0] + dx, coords[1] + dy];
+                if ghost[0].abs() <= width / 2.0 + margin && ghost[1].abs() <= height / 2.0 + margin
+                {
+                    self.index
+                        .add(ghost, particle_index)
+                        .expect("Failed to add ghost particle to spatial index");
+                    if let Some(indexed) = &mut self.indexed {
+                        indexed.push(ghost);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The bounding box of each node in the spatial index, showing how it subdivides space
+    ///
+    /// The kdtree crate doesn't expose its nodes, so this replays the indexed coordinates through
+    /// the same splitting rule to rebuild the partitioning. The regions are in depth-first order,
+    /// starting with the root, so each region contains the regions after it up until its sibling.
+    ///
+    /// Empty unless the model was created with [ModelParams::record_index] set.
+    pub fn index_regions(&self) -> Vec<Rect> {
+        let mut root = IndexRegion::default();
+        for coords in self.indexed.iter().flatten() {
+            root.add(*coords);
+        }
+
+        let mut regions = Vec::new();
+        let mut stack = vec![&root];
+        while let Some(region) = stack.pop() {
+            if region.size == 0 {
+                continue;
+            }
+            regions.push(Rect::new(
+                coord! {x: region.min[0], y: region.min[1]},
+                coord! {x: region.max[0], y: region.max[1]},
+            ));
+            if let Some(children) = &region.children {
+                stack.push(&children.1);
+                stack.push(&children.0);
+            }
+        }
+        regions
+    }
+
+    /// How far across the boundary of a periodic domain to add ghost particles
+    fn ghost_margin(&self) -> f64 {
+        4.0 * (self.attraction_distance + self.min_move_distance + self.particle_spacing)
+    }
+
+    /// Wrap the given coordinates into the periodic domain, if any
+    fn wrap(&self, coords: &[f64; 2]) -> [f64; 2] {
+        match self.periodic {
+            Some([width, height]) => [
+                (coords[0] + width / 2.0).rem_euclid(width) - width / 2.0,
+                (coords[1] + height / 2.0).rem_euclid(height) - height / 2.0,
+            ],
+            None => *coords,
+        }
+    }
+
+    /// The copy of the given coordinates, possibly across the periodic domain's boundary, that's
+    /// closest to the target
+    fn nearest_image(&self, coords: &[f64; 2], target: &[f64; 2]) -> [f64; 2] {
+        match self.periodic {
+            Some([width, height]) => [
+                coords[0] + ((target[0] - coords[0]) / width).round() * width,
+                coords[1] + ((target[1] - coords[1]) / height).round() * height,
+            ],
+            None => *coords,
         }
     }
 
@@ -188,12 +774,16 @@ impl Model {
                 )
             });
         parent.join_attempts += 1;
+        let join_attempts = parent.join_attempts;
+        let colony = parent.colony;
+        let parent_coords = parent.coordinates;
+        // The parent might have been found through one of its ghosts across the periodic boundary
+        let parent_coords = self.nearest_image(&parent_coords, new_coords);
 
-        if parent.join_attempts >= self.stubbornness
-            && self.rng.gen_range(0.0..1.0) <= self.stickiness
-        {
+        if join_attempts >= self.stubbornness && self.rng.gen_range(0.0..1.0) <= self.stickiness {
             // Bump the new particle away from the parent by the particle spacing
-            *new_coords = Model::lerp(&parent.coordinates, new_coords, self.particle_spacing);
+            *new_coords = Model::lerp(&parent_coords, new_coords, self.particle_spacing);
+            *new_coords = self.wrap(new_coords);
             self.bounding_radius = self
                 .bounding_radius
                 .max(Model::length(new_coords) + self.attraction_distance);
@@ -201,24 +791,24 @@ impl Model {
             let new_particle = Particle {
                 coordinates: *new_coords,
                 join_attempts: 0,
+                colony,
             };
 
             // Place the particle in the graph and the spatial index
             let graph_index = self.particle_graph.add_node(new_particle);
             self.particle_graph.add_edge(graph_index, parent_index, ());
-            self.index
-                .add(new_particle.coordinates, graph_index)
-                .expect("Failed to add new particle to index");
+            self.add_to_index(new_particle.coordinates, graph_index);
 
             return true;
         }
 
         // Nudge the new particle
         *new_coords = Model::lerp(
-            &parent.coordinates,
+            &parent_coords,
             new_coords,
             self.attraction_distance + self.min_move_distance,
         );
+        *new_coords = self.wrap(new_coords);
 
         false
     }
@@ -230,7 +820,13 @@ impl Model {
 
     // TODO: ndarray::Array1
     fn generate_random_coord(&mut self) -> [f64; 2] {
-        [self.generate_random(), self.generate_random()]
+        match self.periodic {
+            Some([width, height]) => [
+                self.rng.gen_range(-width / 2.0..width / 2.0),
+                self.rng.gen_range(-height / 2.0..height / 2.0),
+            ],
+            None => [self.generate_random(), self.generate_random()],
+        }
     }
 
     fn length(v: &[f64; 2]) -> f64 {
@@ -241,6 +837,13 @@ impl Model {
         [v[0] / l, v[1] / l]
     }
 
+    /// The unsigned angle between the two vectors, in degrees
+    fn angle_between(u: &[f64; 2], v: &[f64; 2]) -> f64 {
+        let cross = u[0] * v[1] - u[1] * v[0];
+        let dot = u[0] * v[0] + u[1] * v[1];
+        cross.abs().atan2(dot).to_degrees()
+    }
+
     fn lerp(a: &[f64; 2], b: &[f64; 2], d: f64) -> [f64; 2] {
         // a + unit(b - a) * d
         let u = [b[0] - a[0], b[1] - a[1]];
@@ -259,18 +862,30 @@ impl Model {
     }
 }
 
-pub fn format_tgf(writer: &mut BufWriter<Box<dyn Write>>, graph: GraphType) {
+/// Write the particle graph in TGF
+///
+/// If there are multiple colonies, each node label is followed by the particle's colony id.
+pub fn format_tgf(writer: &mut BufWriter<Box<dyn Write>>, graph: &GraphType) {
+    let label_colonies = graph.node_weights().any(|p| p.colony != 0);
     // let (nodes, edges) = graph.into_nodes_edges();
     for idx in graph.node_indices() {
         let particle = graph
             .node_weight(idx)
             .expect("Got index to nonexistent node.");
         let label = idx.index();
-        writeln!(
-            writer,
-            "{}\tPOINT({} {})",
-            label, particle.coordinates[0], particle.coordinates[1]
-        )
+        if label_colonies {
+            writeln!(
+                writer,
+                "{}\tPOINT({} {})\t{}",
+                label, particle.coordinates[0], particle.coordinates[1], particle.colony
+            )
+        } else {
+            writeln!(
+                writer,
+                "{}\tPOINT({} {})",
+                label, particle.coordinates[0], particle.coordinates[1]
+            )
+        }
         .expect("Failed to write node label");
     }
     writeln!(writer, "#").expect("Failed to write node/edge separator");
@@ -285,7 +900,22 @@ pub fn format_tgf(writer: &mut BufWriter<Box<dyn Write>>, graph: GraphType) {
     }
 }
 
-pub fn format_wkt(writer: &mut BufWriter<Box<dyn Write>>, graph: GraphType) {
+/// The convex hull of the circles with the given radii around the given centers
+fn capsule(a: [f64; 2], radius_a: f64, b: [f64; 2], radius_b: f64) -> Polygon {
+    const SEGMENTS: usize = 16;
+    let points: Vec<Point> = (0..SEGMENTS)
+        .flat_map(|i| {
+            let (sin, cos) = (TAU * i as f64 / SEGMENTS as f64).sin_cos();
+            [
+                Point::new(a[0] + radius_a * cos, a[1] + radius_a * sin),
+                Point::new(b[0] + radius_b * cos, b[1] + radius_b * sin),
+            ]
+        })
+        .collect();
+    MultiPoint::new(points).convex_hull()
+}
+
+pub fn format_wkt(writer: &mut BufWriter<Box<dyn Write>>, graph: &GraphType) {
     for idx in graph.node_indices() {
         let particle = graph
             .node_weight(idx)
@@ -298,3 +928,450 @@ pub fn format_wkt(writer: &mut BufWriter<Box<dyn Write>>, graph: GraphType) {
         .expect("Failed to write node WKT.");
     }
 }
+
+/// The number of points a leaf of the spatial index holds before it's split; the kdtree default
+const INDEX_CAPACITY: usize = 16;
+
+/// A node of the spatial index, mirroring how the kdtree crate partitions its points
+///
+/// Each leaf holds up to [INDEX_CAPACITY] points. When it overflows, it's split in half across the
+/// middle of its longest side.
+struct IndexRegion {
+    min: [DimensionType; 2],
+    max: [DimensionType; 2],
+    size: usize,
+    points: Vec<[DimensionType; 2]>,
+    split: Option<(usize, DimensionType)>,
+    children: Option<Box<(IndexRegion, IndexRegion)>>,
+}
+
+impl Default for IndexRegion {
+    fn default() -> Self {
+        Self {
+            min: [DimensionType::INFINITY; 2],
+            max: [DimensionType::NEG_INFINITY; 2],
+            size: 0,
+            points: Vec::new(),
+            split: None,
+            children: None,
+        }
+    }
+}
+
+impl IndexRegion {
+    fn add(&mut self, point: [DimensionType; 2]) {
+        for ((min, max), value) in self.min.iter_mut().zip(&mut self.max).zip(point) {
+            *min = min.min(value);
+            *max = max.max(value);
+        }
+        self.size += 1;
+
+        match (self.split, &mut self.children) {
+            (Some(split), Some(children)) => {
+                if Self::belongs_in_left(split, self.min, point) {
+                    children.0.add(point);
+                } else {
+                    children.1.add(point);
+                }
+            }
+            _ => {
+                self.points.push(point);
+                if self.size > INDEX_CAPACITY {
+                    self.split();
+                }
+            }
+        }
+    }
+
+    fn split(&mut self) {
+        let mut longest = 0.0;
+        for dim in 0..2 {
+            let extent = self.max[dim] - self.min[dim];
+            if extent > longest {
+                longest = extent;
+                self.split = Some((dim, self.min[dim] + extent / 2.0));
+            }
+        }
+        // Every point is identical, so there's nothing to split
+        let Some(split) = self.split else {
+            return;
+        };
+
+        let mut left = IndexRegion::default();
+        let mut right = IndexRegion::default();
+        // Redistribute the points in the same order as the kdtree crate
+        let mut points = std::mem::take(&mut self.points);
+        while !points.is_empty() {
+            let point = points.swap_remove(0);
+            if Self::belongs_in_left(split, self.min, point) {
+                left.add(point);
+            } else {
+                right.add(point);
+            }
+        }
+        self.children = Some(Box::new((left, right)));
+    }
+
+    fn belongs_in_left(
+        (dim, value): (usize, DimensionType),
+        min: [DimensionType; 2],
+        point: [DimensionType; 2],
+    ) -> bool {
+        if min[dim] == value {
+            point[dim] <= value
+        } else {
+            point[dim] < value
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{BoundingRect, Intersects};
+
+    use super::*;
+
+    /// The default parameters, with a fixed random seed
+    fn params() -> ModelParams {
+        ModelParams {
+            seed: 42,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_index_regions_partition_the_particles() {
+        let mut unrecorded = Model::new(params());
+        unrecorded.run(10);
+        assert!(unrecorded.index_regions().is_empty());
+
+        let mut model = Model::new(ModelParams {
+            record_index: true,
+            ..params()
+        });
+        model.run(200);
+
+        let regions = model.index_regions();
+        assert!(regions.len() > 1);
+        assert_eq!(regions.len() % 2, 1, "each split adds two regions");
+
+        // The root region is the bounding box of every particle
+        let root = regions[0];
+        for particle in model.particle_graph.node_weights() {
+            let point = Point::from(particle.coordinates);
+            assert!(root.intersects(&point));
+        }
+        for region in &regions[1..] {
+            assert!(root.intersects(region));
+        }
+    }
+
+    #[test]
+    fn test_thicken_covers_every_particle() {
+        let mut model = Model::new(params());
+        model.run(100);
+
+        let polygons = model.thicken(0.5, 0.5);
+        assert_eq!(polygons.len(), model.particle_graph.edge_count());
+        for particle in model.particle_graph.node_weights() {
+            let point = Point::from(particle.coordinates);
+            assert!(polygons.iter().any(|p| p.intersects(&point)));
+        }
+
+        let depths = model.depths();
+        assert_eq!(depths[0], 0);
+        assert!(depths[1..].iter().all(|d| *d > 0));
+    }
+
+    #[test]
+    fn test_circles() {
+        let mut model = Model::new(params());
+        model.run(20);
+
+        let circles = model.circles(2.0);
+        assert_eq!(circles.len(), model.particle_graph.node_count());
+        for (circle, particle) in circles.iter().zip(model.particle_graph.node_weights()) {
+            let center = Point::from(particle.coordinates);
+            assert!(circle.intersects(&center));
+            let rect = circle.bounding_rect().unwrap();
+            assert!((rect.width() - 4.0).abs() < 1e-9);
+            assert!((rect.center().x - center.x()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_statistics_of_a_fork() {
+        let mut model = Model::new(params());
+        // A trunk growing straight up from the seed at the origin, that forks at (0, 2)
+        let graph = &mut model.particle_graph;
+        let particle = |x, y| Particle {
+            coordinates: [x, y],
+            join_attempts: 0,
+            colony: 0,
+        };
+        let seed = NodeIndex::new(0);
+        let a = graph.add_node(particle(0.0, 1.0));
+        let fork = graph.add_node(particle(0.0, 2.0));
+        let left = graph.add_node(particle(-1.0, 3.0));
+        let right = graph.add_node(particle(1.0, 3.0));
+        graph.add_edge(a, seed, ());
+        graph.add_edge(fork, a, ());
+        graph.add_edge(left, fork, ());
+        graph.add_edge(right, fork, ());
+
+        let stats = model.statistics();
+        assert_eq!(stats.particles, 5);
+        assert_eq!(stats.branches, 3);
+        let expected = (2.0 + 2.0 * 2.0_f64.sqrt()) / 3.0;
+        assert!((stats.mean_branch_length - expected).abs() < 1e-9);
+        assert!((stats.mean_branch_angle - 45.0).abs() < 1e-9);
+        assert!(stats.mean_curl.abs() < 1e-9);
+        // Too small to count boxes at more than one size
+        assert!(stats.fractal_dimension.is_nan());
+    }
+
+    #[test]
+    fn test_statistics_of_a_grown_model() {
+        let mut model = Model::new(params());
+        let empty = model.statistics();
+        assert_eq!(empty.particles, 1);
+        assert_eq!(empty.branches, 0);
+        assert!(empty.mean_branch_angle.is_nan());
+        assert!(empty.fractal_dimension.is_nan());
+
+        model.run(500);
+        let stats = model.statistics();
+        assert_eq!(stats.particles, 501);
+        assert!(stats.branches > 1);
+        assert!(stats.mean_branch_length >= 1.0 - 1e-9);
+        assert!((0.0..=180.0).contains(&stats.mean_branch_angle));
+        assert!((0.0..=180.0).contains(&stats.mean_curl));
+        assert!(
+            (1.0..2.0).contains(&stats.fractal_dimension),
+            "{}",
+            stats.fractal_dimension
+        );
+    }
+
+    #[test]
+    fn test_periodic_structure_is_continuous_across_the_boundary() {
+        let (width, height) = (20.0, 10.0);
+        let mut model = Model::new(ModelParams {
+            periodic: Some([width, height]),
+            ..params()
+        });
+        model.run(400);
+
+        let mut wrapped_edges = 0;
+        for edge in model.particle_graph.edge_references() {
+            let a = model.particle_graph[edge.source()].coordinates;
+            let b = model.particle_graph[edge.target()].coordinates;
+            for p in [a, b] {
+                assert!(p[0].abs() <= width / 2.0 && p[1].abs() <= height / 2.0);
+            }
+
+            // Every edge has the particle spacing, once it's unwrapped across the boundary
+            let b_image = model.nearest_image(&b, &a);
+            let length = Model::length(&[b_image[0] - a[0], b_image[1] - a[1]]);
+            assert!((length - 1.0).abs() < 1e-6, "length: {length}");
+            if b_image != b {
+                wrapped_edges += 1;
+            }
+        }
+        assert!(wrapped_edges > 0);
+    }
+
+    #[test]
+    fn test_seed_patterns() {
+        let seeds = |pattern, particles| -> Vec<[f64; 2]> {
+            let model = Model::new(ModelParams {
+                seeds: particles,
+                seed_pattern: pattern,
+                ..params()
+            });
+            model
+                .particle_graph
+                .node_weights()
+                .map(|p| p.coordinates)
+                .collect()
+        };
+
+        assert_eq!(
+            seeds(SeedPattern::Line(4.0), 3),
+            [[-2.0, 0.0], [0.0, 0.0], [2.0, 0.0]]
+        );
+
+        for [x, y] in seeds(SeedPattern::Circle(3.0), 7) {
+            assert!((x.hypot(y) - 3.0).abs() < 1e-9);
+        }
+
+        // A 3x2 grid, centered on the origin
+        assert_eq!(
+            seeds(SeedPattern::Grid(2.0), 5),
+            [
+                [-2.0, -1.0],
+                [0.0, -1.0],
+                [2.0, -1.0],
+                [-2.0, 1.0],
+                [0.0, 1.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grows_from_circle_seeds() {
+        let mut model = Model::new(ModelParams {
+            seeds: 8,
+            seed_pattern: SeedPattern::Circle(20.0),
+            ..params()
+        });
+        model.run(100);
+        assert_eq!(model.particle_graph.node_count(), 108);
+        assert_eq!(model.particle_graph.edge_count(), 100);
+    }
+
+    #[test]
+    fn test_stream_newest_edges() {
+        let mut model = Model::new(ModelParams {
+            seeds: 3,
+            seed_pattern: SeedPattern::Line(10.0),
+            ..params()
+        });
+        assert_eq!(model.seed_points().count(), 3);
+        assert!(model.newest_edge().is_none());
+
+        let mut edges = Vec::new();
+        model.run_with_callback(20, |model, _| edges.push(model.newest_edge().unwrap()));
+        assert_eq!(edges.len(), 20);
+
+        // Each edge ends at a new particle, and starts at a particle that was already there
+        let points: Vec<_> = model
+            .particle_graph
+            .node_weights()
+            .map(|p| Point::from(p.coordinates))
+            .collect();
+        for (i, edge) in edges.iter().enumerate() {
+            assert_eq!(Point(edge.end), points[3 + i]);
+            assert!(points[..3 + i].contains(&Point(edge.start)));
+        }
+    }
+
+    /// The shortest distance between two particles of different colonies
+    fn min_inter_colony_distance(model: &Model) -> f64 {
+        let particles: Vec<_> = model.particle_graph.node_weights().collect();
+        let mut min = f64::INFINITY;
+        for (i, a) in particles.iter().enumerate() {
+            for b in particles[i + 1..].iter().filter(|b| b.colony != a.colony) {
+                let delta = [
+                    b.coordinates[0] - a.coordinates[0],
+                    b.coordinates[1] - a.coordinates[1],
+                ];
+                min = min.min(Model::length(&delta));
+            }
+        }
+        min
+    }
+
+    #[test]
+    fn test_colonies_stay_apart() {
+        let colonies = |allow_merge| {
+            let mut model = Model::new(ModelParams {
+                seeds: 2,
+                seed_pattern: SeedPattern::Line(10.0),
+                colonies: 2,
+                allow_merge,
+                ..params()
+            });
+            model.run(300);
+            model
+        };
+        // The nearest neighbor distances are squared
+        let join_radius = params().attraction_distance.sqrt();
+
+        let apart = colonies(false);
+        for edge in apart.particle_graph.edge_references() {
+            let a = apart.particle_graph[edge.source()].colony;
+            let b = apart.particle_graph[edge.target()].colony;
+            assert_eq!(a, b);
+        }
+        let distance = min_inter_colony_distance(&apart);
+        assert!(distance > join_radius, "distance: {distance}");
+
+        let merged = colonies(true);
+        let distance = min_inter_colony_distance(&merged);
+        assert!(distance < join_radius, "distance: {distance}");
+    }
+
+    #[test]
+    fn test_seeds_are_assigned_to_colonies_round_robin() {
+        let model = Model::new(ModelParams {
+            seeds: 5,
+            seed_pattern: SeedPattern::Line(10.0),
+            colonies: 2,
+            ..params()
+        });
+        let colonies: Vec<_> = model
+            .particle_graph
+            .node_weights()
+            .map(|p| p.colony)
+            .collect();
+        assert_eq!(colonies, [0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_format_tgf_labels_colonies() {
+        let tgf = |colonies| {
+            let model = Model::new(ModelParams {
+                seeds: 2,
+                seed_pattern: SeedPattern::Line(2.0),
+                colonies,
+                ..params()
+            });
+            let path = std::env::temp_dir().join(format!(
+                "generative-dla-{colonies}-{}.tgf",
+                std::process::id()
+            ));
+            let file: Box<dyn Write> = Box::new(std::fs::File::create(&path).unwrap());
+            let mut writer = BufWriter::new(file);
+            format_tgf(&mut writer, &model.particle_graph);
+            drop(writer);
+            let tgf = std::fs::read_to_string(&path).unwrap();
+            std::fs::remove_file(path).unwrap();
+            tgf
+        };
+
+        assert_eq!(tgf(1), "0\tPOINT(-1 0)\n1\tPOINT(1 0)\n#\n");
+        assert_eq!(tgf(2), "0\tPOINT(-1 0)\t0\n1\tPOINT(1 0)\t1\n#\n");
+    }
+
+    #[test]
+    fn test_attractor_pull() {
+        let model = |periodic, attractor| {
+            Model::new(ModelParams {
+                periodic,
+                attractor,
+                ..params()
+            })
+        };
+
+        let none = model(None, None);
+        assert_eq!(none.attractor_pull(&[3.0, 4.0]), [0.0, 0.0]);
+
+        // The pull points at the attractor, and is inversely proportional to the distance
+        let pulled = model(None, Some(([0.0, 0.0], 10.0)));
+        assert_eq!(pulled.attractor_pull(&[3.0, 4.0]), [-1.2, -1.6]);
+        assert_eq!(pulled.attractor_pull(&[6.0, 8.0]), [-0.6, -0.8]);
+        assert_eq!(pulled.attractor_pull(&[0.0, 0.0]), [0.0, 0.0]);
+
+        let pushed = model(None, Some(([0.0, 0.0], -10.0)));
+        assert_eq!(pushed.attractor_pull(&[3.0, 4.0]), [1.2, 1.6]);
+
+        // Pulled toward the closest copy of the attractor across the periodic boundary
+        let periodic = model(Some([20.0, 20.0]), Some(([-9.0, 0.0], 2.0)));
+        assert_eq!(periodic.attractor_pull(&[9.0, 0.0]), [1.0, 0.0]);
+
+        let mut grown = model(None, Some(([0.0, 0.0], 10.0)));
+        grown.run(100);
+        assert_eq!(grown.particle_graph.node_count(), 101);
+    }
+}
@@ -19,15 +19,16 @@ where
     type Item = Geometry;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let current_geometry = self.current_geometry_iterator.next();
-        if current_geometry.is_some() {
-            return current_geometry;
+        loop {
+            let current_geometry = self.current_geometry_iterator.next();
+            if current_geometry.is_some() {
+                return current_geometry;
+            }
+            // We've exhausted the iterator for the current geometry, move on to the next one.
+            // EMPTY geometries don't yield anything, so keep going until there's a non-empty one.
+            let next_geometry = self.geometries.next()?;
+            self.current_geometry_iterator = geometry_to_outer_iterator(Some(next_geometry));
         }
-        // We've exhausted the iterator for the current geometry, move on to the next one
-        let next_geometry = self.geometries.next();
-        self.current_geometry_iterator = geometry_to_outer_iterator(next_geometry);
-
-        self.current_geometry_iterator.next()
     }
 }
 
@@ -168,4 +169,13 @@ mod tests {
         ];
         assert_eq!(flattened, expected);
     }
+
+    #[test]
+    fn test_flatten_skips_empty_geometries() {
+        let wkt = b"MULTIPOINT EMPTY\nGEOMETRYCOLLECTION(GEOMETRYCOLLECTION EMPTY, POINT(1 1))\nMULTIPOLYGON EMPTY\nPOINT(2 2)";
+        let geometries = read_wkt_geometries(&wkt[..]);
+        let flattened: Vec<_> = flatten_nested_geometries(geometries).collect();
+        let expected = [Point::new(1.0, 1.0).into(), Point::new(2.0, 2.0).into()];
+        assert_eq!(flattened, expected);
+    }
 }
@@ -2,4 +2,7 @@ mod geometries;
 mod points;
 
 pub use geometries::flatten_nested_geometries;
-pub use points::{flatten_geometries_into_points, flatten_geometries_into_points_ref};
+pub use points::{
+    flatten_geometries_into_points, flatten_geometries_into_points_ref,
+    flatten_geometries_into_points_ref_with, flatten_geometries_into_points_with, FlattenOptions,
+};
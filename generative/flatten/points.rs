@@ -2,8 +2,31 @@ use geo::{Coord, CoordsIter, Geometry, LineString, Point};
 
 use crate::flatten::flatten_nested_geometries;
 
-fn implicitly_open_linestring(ls: LineString) -> impl Iterator<Item = Coord> {
-    let length = if ls.is_closed() {
+/// Options controlling which vertices [`flatten_geometries_into_points_with`] emits
+///
+/// Regardless of the options, every vertex of a POINT, LINE, RECT, or TRIANGLE is emitted exactly
+/// once, and repeated vertices other than the closing vertex of a ring are always emitted.
+/// MULTI-geometries and GEOMETRYCOLLECTIONs emit the vertices of each of their parts in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlattenOptions {
+    /// Emit the vertices of POLYGON interior rings after the exterior ring's vertices
+    pub interiors: bool,
+    /// Emit the last vertex of a closed LINESTRING or POLYGON ring, duplicating the first vertex
+    pub closing_vertices: bool,
+}
+
+impl Default for FlattenOptions {
+    /// Emit the interior rings, but implicitly open each closed ring
+    fn default() -> Self {
+        Self {
+            interiors: true,
+            closing_vertices: false,
+        }
+    }
+}
+
+fn linestring_vertices(ls: LineString, options: FlattenOptions) -> impl Iterator<Item = Coord> {
+    let length = if ls.is_closed() && !options.closing_vertices {
         ls.0.len() - 1
     } else {
         ls.0.len()
@@ -11,23 +34,28 @@ fn implicitly_open_linestring(ls: LineString) -> impl Iterator<Item = Coord> {
     ls.0.into_iter().take(length)
 }
 
-fn implicitly_open(geometry: Geometry) -> Box<dyn Iterator<Item = Coord>> {
+fn vertices(geometry: Geometry, options: FlattenOptions) -> Box<dyn Iterator<Item = Coord>> {
     match geometry {
         Geometry::Point(_) | Geometry::Line(_) | Geometry::Rect(_) | Geometry::Triangle(_) => {
             // This collect _is_ actually needed :(
             //
             // .coords_iter() doesn't consume the geometry, but the function signature for
-            // implicitly_open() requires that we do, so that's what we'll do then.
+            // vertices() requires that we do, so that's what we'll do then.
             #[allow(clippy::needless_collect)]
             let coords: Vec<_> = geometry.coords_iter().collect();
             Box::new(coords.into_iter())
         }
-        Geometry::LineString(ls) => Box::new(implicitly_open_linestring(ls)),
+        Geometry::LineString(ls) => Box::new(linestring_vertices(ls, options)),
         Geometry::Polygon(p) => {
-            let (exterior, interiors) = p.into_inner();
-
-            let exterior = implicitly_open_linestring(exterior);
-            let interiors = interiors.into_iter().flat_map(implicitly_open_linestring);
+            let (exterior, mut interiors) = p.into_inner();
+            if !options.interiors {
+                interiors.clear();
+            }
+
+            let exterior = linestring_vertices(exterior, options);
+            let interiors = interiors
+                .into_iter()
+                .flat_map(move |ring| linestring_vertices(ring, options));
             let all_points = exterior.chain(interiors);
             Box::new(all_points)
         }
@@ -35,24 +63,15 @@ fn implicitly_open(geometry: Geometry) -> Box<dyn Iterator<Item = Coord>> {
     }
 }
 
-fn implicitly_open_ref(geometry: &Geometry) -> Box<dyn Iterator<Item = Coord> + '_> {
+fn vertices_ref(
+    geometry: &Geometry,
+    options: FlattenOptions,
+) -> Box<dyn Iterator<Item = Coord> + '_> {
     match geometry {
         Geometry::Point(_) | Geometry::Line(_) | Geometry::Rect(_) | Geometry::Triangle(_) => {
             Box::new(geometry.coords_iter())
         }
-        Geometry::LineString(ls) => {
-            let ls = ls.clone();
-            Box::new(implicitly_open_linestring(ls))
-        }
-        Geometry::Polygon(p) => {
-            let p = p.clone();
-            let (exterior, interiors) = p.into_inner();
-
-            let exterior = implicitly_open_linestring(exterior);
-            let interiors = interiors.into_iter().flat_map(implicitly_open_linestring);
-            let all_points = exterior.chain(interiors);
-            Box::new(all_points)
-        }
+        Geometry::LineString(_) | Geometry::Polygon(_) => vertices(geometry.clone(), options),
         // TODO: Is there an alternative implementation of flatten_nested_geometries that could
         // operate on an 'impl Iterator<Item = &Geometry>'?
         _ => unimplemented!(
@@ -63,26 +82,43 @@ fn implicitly_open_ref(geometry: &Geometry) -> Box<dyn Iterator<Item = Coord> +
 
 /// Flatten the given geometries into a point cloud
 ///
-/// NOTE: Closed rings are implicitly opened.
+/// NOTE: Closed rings are implicitly opened, and POLYGON holes are included. See
+/// [`flatten_geometries_into_points_with`] to control which vertices are emitted.
 pub fn flatten_geometries_into_points(
     geometries: impl Iterator<Item = Geometry>,
+) -> impl Iterator<Item = Point> {
+    flatten_geometries_into_points_with(geometries, FlattenOptions::default())
+}
+
+/// Flatten the given geometries into a point cloud, using the given [`FlattenOptions`]
+pub fn flatten_geometries_into_points_with(
+    geometries: impl Iterator<Item = Geometry>,
+    options: FlattenOptions,
 ) -> impl Iterator<Item = Point> {
     let geometries = flatten_nested_geometries(geometries);
 
     geometries
-        .flat_map(implicitly_open)
+        .flat_map(move |geometry| vertices(geometry, options))
         .map(|coord| coord.into())
 }
 
 /// A variant of [`flatten_geometries_into_points`](flatten_geometries_into_points) that doesn't
 /// consume the geometries
 ///
-/// NOTE: Closed rings are implicitly opened.
+/// NOTE: Closed rings are implicitly opened, and POLYGON holes are included.
 pub fn flatten_geometries_into_points_ref<'geom>(
     geometries: impl Iterator<Item = &'geom Geometry> + 'geom,
+) -> impl Iterator<Item = Point> + 'geom {
+    flatten_geometries_into_points_ref_with(geometries, FlattenOptions::default())
+}
+
+/// A variant of [`flatten_geometries_into_points_with`] that doesn't consume the geometries
+pub fn flatten_geometries_into_points_ref_with<'geom>(
+    geometries: impl Iterator<Item = &'geom Geometry> + 'geom,
+    options: FlattenOptions,
 ) -> impl Iterator<Item = Point> + 'geom {
     geometries
-        .flat_map(implicitly_open_ref)
+        .flat_map(move |geometry| vertices_ref(geometry, options))
         .map(|coord| coord.into())
 }
 
@@ -140,4 +176,46 @@ mod tests {
         ];
         assert_eq!(points, expected);
     }
+
+    #[test]
+    fn test_flatten_polygon_exterior_only() {
+        let wkt = b"POLYGON((0 0, 1 0, 1 1, 0 1, 0 0), (0.25 0.25, 0.75 0.25, 0.75 0.75, 0.25 0.75, 0.25 0.25))";
+        let geometries = read_wkt_geometries(&wkt[..]);
+        let options = FlattenOptions {
+            interiors: false,
+            ..Default::default()
+        };
+        let points: Vec<Point<f64>> =
+            flatten_geometries_into_points_with(geometries, options).collect();
+
+        let expected = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(0.0, 1.0),
+        ];
+        assert_eq!(points, expected);
+    }
+
+    #[test]
+    fn test_flatten_keeps_closing_vertices() {
+        let wkt = b"POLYGON((0 0, 1 0, 1 1, 0 0), (0.5 0.25, 0.75 0.5, 0.5 0.5, 0.5 0.25))\nLINESTRING(2 2, 3 3, 3 3)";
+        let geometries: Vec<_> = read_wkt_geometries(&wkt[..]).collect();
+        let options = FlattenOptions {
+            closing_vertices: true,
+            ..Default::default()
+        };
+
+        let points: Vec<Point<f64>> =
+            flatten_geometries_into_points_ref_with(geometries.iter(), options).collect();
+        assert_eq!(points.len(), 4 + 4 + 3);
+        assert_eq!(points[3], points[0]);
+        assert_eq!(points[7], points[4]);
+        // Repeated vertices other than the closing vertex are always emitted
+        assert_eq!(points[9], points[10]);
+
+        let points: Vec<Point<f64>> =
+            flatten_geometries_into_points(geometries.into_iter()).collect();
+        assert_eq!(points.len(), 3 + 3 + 3);
+    }
 }
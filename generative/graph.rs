@@ -1,4 +1,9 @@
-use geo::Point;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use geo::{Area, Centroid, Coord, LineString, Point, Polygon, Winding};
+use petgraph::visit::EdgeRef;
+use petgraph::{EdgeType, Undirected};
+use rand::Rng;
 
 pub type NodeData = Point;
 pub type EdgeWeight = ();
@@ -37,3 +42,661 @@ impl<Direction: petgraph::EdgeType> From<&crate::cxxbridge::GeometryGraphShim>
         graph
     }
 }
+
+/// The faces of a planar graph, found by walking its half-edges
+struct FaceWalk {
+    /// The node indices around each face, CCW for bounded faces, and CW for the unbounded outside
+    /// of each connected component
+    rings: Vec<Vec<usize>>,
+    /// The face to the left of each (src, dst) half-edge
+    half_edge_faces: HashMap<(usize, usize), usize>,
+}
+
+/// Walk each half-edge of the graph exactly once, assigning it to the face on its left
+///
+/// At each node, the walk always turns as far right as possible, which traces out each bounded face
+/// counter-clockwise.
+fn walk_faces(graph: &GeometryGraph<Undirected>) -> FaceWalk {
+    let points: Vec<Point> = graph.node_weights().copied().collect();
+
+    // Sort each node's neighbors counter-clockwise by angle
+    let mut neighbors: Vec<Vec<usize>> = Vec::with_capacity(graph.node_count());
+    for node in graph.node_indices() {
+        let center = points[node.index()];
+        let mut adjacent: Vec<_> = graph.neighbors(node).map(|n| n.index()).collect();
+        adjacent.sort_by(|a, b| {
+            let a = points[*a] - center;
+            let b = points[*b] - center;
+            a.y().atan2(a.x()).total_cmp(&b.y().atan2(b.x()))
+        });
+        adjacent.dedup();
+        neighbors.push(adjacent);
+    }
+
+    let mut half_edge_faces: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut rings: Vec<Vec<usize>> = Vec::new();
+    for (start, adjacent) in neighbors.iter().enumerate() {
+        for &next in adjacent {
+            if half_edge_faces.contains_key(&(start, next)) {
+                continue;
+            }
+
+            let face = rings.len();
+            let mut ring = Vec::new();
+            let (mut u, mut v) = (start, next);
+            while !half_edge_faces.contains_key(&(u, v)) {
+                half_edge_faces.insert((u, v), face);
+                ring.push(u);
+
+                // The next half-edge leaves v towards the neighbor clockwise from u
+                let around_v = &neighbors[v];
+                let position = around_v.iter().position(|n| *n == u).unwrap();
+                let w = around_v[(position + around_v.len() - 1) % around_v.len()];
+                (u, v) = (v, w);
+            }
+            rings.push(ring);
+        }
+    }
+
+    FaceWalk {
+        rings,
+        half_edge_faces,
+    }
+}
+
+/// Build a polygon for each bounded face, or `None` for the unbounded faces
+fn face_polygons(graph: &GeometryGraph<Undirected>, walk: &FaceWalk) -> Vec<Option<Polygon>> {
+    walk.rings
+        .iter()
+        .map(|ring| {
+            let coords: Vec<Coord> = ring
+                .iter()
+                .map(|n| graph[petgraph::graph::NodeIndex::new(*n)].0)
+                .collect();
+            let polygon = Polygon::new(LineString::new(coords), Vec::new());
+            (polygon.signed_area() > 0.0).then_some(polygon)
+        })
+        .collect()
+}
+
+/// Find the faces of the given planar graph as polygons, without GEOS
+///
+/// The graph is assumed to be planar, with nodes at every edge intersection, like a grid or a
+/// noded graph. The unbounded face outside the graph isn't returned. Each polygon has a closed,
+/// counter-clockwise exterior ring. Dangling edges inside a face are included in its ring, and
+/// faces surrounding a disconnected part of the graph aren't given a hole.
+pub fn faces(graph: &GeometryGraph<Undirected>) -> Vec<Polygon> {
+    let walk = walk_faces(graph);
+    face_polygons(graph, &walk).into_iter().flatten().collect()
+}
+
+/// Build the dual of the given planar graph
+///
+/// Each of the [faces] of the graph becomes a node located at the face's centroid, and each pair of
+/// faces that share an edge are connected.
+pub fn dual_graph(graph: &GeometryGraph<Undirected>) -> GeometryGraph<Undirected> {
+    let walk = walk_faces(graph);
+
+    let mut dual = GeometryGraph::<Undirected>::default();
+    let face_to_node: Vec<_> = face_polygons(graph, &walk)
+        .into_iter()
+        .map(|polygon| polygon.and_then(|p| p.centroid()).map(|c| dual.add_node(c)))
+        .collect();
+
+    // Connect the faces on either side of each edge
+    for edge in graph.raw_edges() {
+        let (u, v) = (edge.source().index(), edge.target().index());
+        let left = face_to_node[walk.half_edge_faces[&(u, v)]];
+        let right = face_to_node[walk.half_edge_faces[&(v, u)]];
+        if let (Some(left), Some(right)) = (left, right) {
+            if left != right {
+                dual.update_edge(left, right, ());
+            }
+        }
+    }
+
+    dual
+}
+
+/// Find a cycle basis of the graph, and build a polygon from each cycle
+///
+/// Builds a breadth first spanning tree of each connected component, and then each edge not in the
+/// tree closes a fundamental cycle through the tree. Unlike [faces], the graph doesn't have to be
+/// planar, but the basis isn't guaranteed to be minimal, so for a planar graph a cycle may enclose
+/// more than one face. Each polygon has a closed, counter-clockwise exterior ring. Cycles through
+/// parallel edges have fewer than three nodes, and are skipped.
+pub fn cycle_basis(graph: &GeometryGraph<Undirected>) -> Vec<Polygon> {
+    let nodes = graph.node_count();
+    let mut parent: Vec<Option<usize>> = vec![None; nodes];
+    let mut depth: Vec<Option<usize>> = vec![None; nodes];
+    let mut tree_edges = HashSet::new();
+    let mut queue = VecDeque::new();
+    for start in graph.node_indices() {
+        if depth[start.index()].is_some() {
+            continue;
+        }
+
+        depth[start.index()] = Some(0);
+        queue.push_back(start);
+        while let Some(node) = queue.pop_front() {
+            let next_depth = depth[node.index()].unwrap() + 1;
+            for edge in graph.edges(node) {
+                let neighbor = edge.target();
+                if depth[neighbor.index()].is_none() {
+                    depth[neighbor.index()] = Some(next_depth);
+                    parent[neighbor.index()] = Some(node.index());
+                    tree_edges.insert(edge.id());
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    let mut cycles = Vec::new();
+    for edge in graph.edge_references() {
+        if tree_edges.contains(&edge.id()) {
+            continue;
+        }
+
+        // Walk up the tree from both ends of the edge until the paths meet
+        let (mut u, mut v) = (edge.source().index(), edge.target().index());
+        let mut up_from_u = vec![u];
+        let mut up_from_v = vec![v];
+        while u != v {
+            if depth[u] >= depth[v] {
+                u = parent[u].unwrap();
+                up_from_u.push(u);
+            } else {
+                v = parent[v].unwrap();
+                up_from_v.push(v);
+            }
+        }
+        // The common ancestor is at the end of both paths
+        up_from_v.pop();
+        let ring: Vec<usize> = up_from_u
+            .into_iter()
+            .chain(up_from_v.into_iter().rev())
+            .collect();
+        if ring.len() < 3 {
+            continue;
+        }
+
+        let mut exterior: LineString = ring
+            .iter()
+            .chain(ring.first())
+            .map(|n| graph[petgraph::graph::NodeIndex::new(*n)].0)
+            .collect();
+        exterior.make_ccw_winding();
+        cycles.push(Polygon::new(exterior, Vec::new()));
+    }
+
+    cycles
+}
+
+/// Remove self-loops, and collapse parallel edges between the same pair of nodes into one edge
+///
+/// Petgraph's Graph allows multi-edges, and snapping and noding can leave them behind. For directed
+/// graphs, edges in opposite directions between the same nodes aren't parallel, and are both kept.
+pub fn simplify_multigraph<D: EdgeType>(graph: &mut GeometryGraph<D>) {
+    let mut seen = HashSet::new();
+    graph.retain_edges(|graph, edge| {
+        let (src, dst) = graph.edge_endpoints(edge).unwrap();
+        if src == dst {
+            return false;
+        }
+        let key = if graph.is_directed() || src < dst {
+            (src, dst)
+        } else {
+            (dst, src)
+        };
+        seen.insert(key)
+    });
+}
+
+/// Label each node with the index of the connected component it belongs to
+///
+/// Edge directions are ignored, so the components of a directed graph are its weakly connected
+/// components. Components are numbered in the order of their lowest node index.
+pub fn connected_components<D: EdgeType>(graph: &GeometryGraph<D>) -> Vec<usize> {
+    let mut labels: Vec<Option<usize>> = vec![None; graph.node_count()];
+    let mut components = 0;
+    let mut queue = VecDeque::new();
+    for start in graph.node_indices() {
+        if labels[start.index()].is_some() {
+            continue;
+        }
+
+        labels[start.index()] = Some(components);
+        queue.push_back(start);
+        while let Some(node) = queue.pop_front() {
+            for neighbor in graph.neighbors_undirected(node) {
+                if labels[neighbor.index()].is_none() {
+                    labels[neighbor.index()] = Some(components);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        components += 1;
+    }
+
+    labels.into_iter().map(Option::unwrap).collect()
+}
+
+/// Extract the connected component with the most nodes, discarding the rest of the graph
+///
+/// Ties go to the component with the lowest node index. The nodes are renumbered contiguously,
+/// preserving their relative order.
+pub fn largest_component<D: EdgeType>(graph: &GeometryGraph<D>) -> GeometryGraph<D> {
+    let labels = connected_components(graph);
+    let mut sizes = Vec::new();
+    for &label in &labels {
+        if label >= sizes.len() {
+            sizes.resize(label + 1, 0);
+        }
+        sizes[label] += 1;
+    }
+    // max_by_key returns the last maximum, so reverse to break ties with the first component
+    let largest = sizes
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|(_, size)| **size)
+        .map(|(label, _)| label);
+
+    graph.filter_map(
+        |node, point| (Some(labels[node.index()]) == largest).then_some(*point),
+        |_, _| Some(()),
+    )
+}
+
+/// Find the bridges of the graph; the edges whose removal would disconnect their component
+///
+/// Each bridge is given as the (source, target) pair of nodes it connects, in edge order. Parallel
+/// edges between the same pair of nodes are never bridges, and neither are self-loops.
+pub fn bridges(graph: &GeometryGraph<Undirected>) -> Vec<(NodeIndex, NodeIndex)> {
+    let (bridges, _) = low_link(graph);
+    let mut bridges: Vec<_> = bridges
+        .into_iter()
+        .map(|e| {
+            let (src, dst) = graph.edge_endpoints(e).unwrap();
+            (e.index(), (src.index(), dst.index()))
+        })
+        .collect();
+    bridges.sort_unstable_by_key(|(e, _)| *e);
+    bridges.into_iter().map(|(_, nodes)| nodes).collect()
+}
+
+/// Find the articulation points of the graph; the nodes whose removal would disconnect their
+/// component
+///
+/// The nodes are returned in increasing order.
+pub fn articulation_points(graph: &GeometryGraph<Undirected>) -> Vec<NodeIndex> {
+    let (_, is_articulation) = low_link(graph);
+    is_articulation
+        .into_iter()
+        .enumerate()
+        .filter_map(|(node, is_articulation)| is_articulation.then_some(node))
+        .collect()
+}
+
+/// Find the bridges and articulation points with Tarjan's depth first search low-link algorithm
+///
+/// The low-link of a node is the earliest discovered node reachable from its DFS subtree through at
+/// most one back edge. An edge to a child is a bridge if the child's subtree can't reach above the
+/// child, and a non-root node is an articulation point if some child's subtree can't reach above
+/// the node. The root is an articulation point if it has more than one child in the DFS tree. The
+/// DFS uses an explicit stack, so deep graphs don't overflow the call stack.
+fn low_link(
+    graph: &GeometryGraph<Undirected>,
+) -> (Vec<petgraph::graph::EdgeIndex<NodeIndex>>, Vec<bool>) {
+    let nodes = graph.node_count();
+    let mut discovered: Vec<Option<usize>> = vec![None; nodes];
+    let mut low = vec![0; nodes];
+    let mut is_articulation = vec![false; nodes];
+    let mut bridges = Vec::new();
+    let mut time = 0;
+
+    for root in graph.node_indices() {
+        if discovered[root.index()].is_some() {
+            continue;
+        }
+
+        discovered[root.index()] = Some(time);
+        low[root.index()] = time;
+        time += 1;
+        let mut root_children = 0;
+        // Each entry is a node, the edge it was discovered through, and its unvisited edges
+        let mut stack = vec![(root.index(), None, graph.edges(root))];
+        while let Some((node, parent_edge, edges)) = stack.last_mut() {
+            let (node, parent_edge) = (*node, *parent_edge);
+            if let Some(edge) = edges.next() {
+                // Skip the edge back to the parent, but not any edges parallel to it
+                if Some(edge.id()) == parent_edge {
+                    continue;
+                }
+                let next = edge.target();
+                match discovered[next.index()] {
+                    Some(d) => low[node] = low[node].min(d),
+                    None => {
+                        discovered[next.index()] = Some(time);
+                        low[next.index()] = time;
+                        time += 1;
+                        stack.push((next.index(), Some(edge.id()), graph.edges(next)));
+                    }
+                }
+                continue;
+            }
+
+            // Done with this node, so propagate its low-link up to its parent
+            stack.pop();
+            let Some((parent, _, _)) = stack.last() else {
+                continue;
+            };
+            let parent = *parent;
+            low[parent] = low[parent].min(low[node]);
+            let parent_discovered = discovered[parent].unwrap();
+            if low[node] > parent_discovered {
+                bridges.push(parent_edge.unwrap());
+            }
+            if parent == root.index() {
+                root_children += 1;
+            } else if low[node] >= parent_discovered {
+                is_articulation[parent] = true;
+            }
+        }
+        if root_children > 1 {
+            is_articulation[root.index()] = true;
+        }
+    }
+
+    (bridges, is_articulation)
+}
+
+/// The centrality of a single node in a graph
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Centrality {
+    /// The number of edges incident to the node
+    pub degree: usize,
+    /// The number of shortest paths between other pairs of nodes that pass through the node
+    ///
+    /// Paths that are tied for the shortest each contribute a fraction of a path.
+    pub betweenness: f64,
+}
+
+/// Calculate the degree and betweenness centrality of each node in the graph
+///
+/// Uses Brandes' algorithm, with the shortest paths measured by the number of edges rather than by
+/// their length. Takes O(VE) time, so see [sampled_centrality] for large graphs.
+pub fn centrality<D: EdgeType>(graph: &GeometryGraph<D>) -> Vec<(NodeIndex, Centrality)> {
+    let sources = graph.node_indices().map(|n| n.index());
+    centrality_from_sources(graph, sources, 1.0)
+}
+
+/// Approximate the betweenness centrality by only finding the shortest paths from a random sample
+/// of the nodes
+///
+/// The betweenness is scaled up by the fraction of nodes sampled, so that it's comparable to the
+/// exact [centrality]. The degree is always exact.
+pub fn sampled_centrality<D: EdgeType, R: Rng>(
+    graph: &GeometryGraph<D>,
+    samples: usize,
+    rng: &mut R,
+) -> Vec<(NodeIndex, Centrality)> {
+    let nodes = graph.node_count();
+    let samples = samples.min(nodes);
+    if samples == 0 {
+        return centrality_from_sources(graph, std::iter::empty(), 0.0);
+    }
+    let sources = rand::seq::index::sample(rng, nodes, samples);
+    let scale = nodes as f64 / samples as f64;
+    centrality_from_sources(graph, sources.into_iter(), scale)
+}
+
+fn centrality_from_sources<D: EdgeType>(
+    graph: &GeometryGraph<D>,
+    sources: impl Iterator<Item = usize>,
+    scale: f64,
+) -> Vec<(NodeIndex, Centrality)> {
+    let nodes = graph.node_count();
+    let mut betweenness = vec![0.0; nodes];
+
+    // Reused between each source to avoid reallocating
+    let mut stack = Vec::with_capacity(nodes);
+    let mut queue = VecDeque::with_capacity(nodes);
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); nodes];
+    let mut num_paths = vec![0.0; nodes];
+    let mut distance: Vec<Option<usize>> = vec![None; nodes];
+    let mut dependency = vec![0.0; nodes];
+
+    for source in sources {
+        for p in predecessors.iter_mut() {
+            p.clear();
+        }
+        num_paths.fill(0.0);
+        distance.fill(None);
+        dependency.fill(0.0);
+
+        // Count the shortest paths from the source to each node with a breadth first search
+        num_paths[source] = 1.0;
+        distance[source] = Some(0);
+        queue.push_back(source);
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            let next_distance = distance[v].unwrap() + 1;
+            for w in graph.neighbors(petgraph::graph::NodeIndex::new(v)) {
+                let w = w.index();
+                if distance[w].is_none() {
+                    distance[w] = Some(next_distance);
+                    queue.push_back(w);
+                }
+                if distance[w] == Some(next_distance) {
+                    num_paths[w] += num_paths[v];
+                    predecessors[w].push(v);
+                }
+            }
+        }
+
+        // Accumulate the dependencies back from the farthest nodes
+        while let Some(w) = stack.pop() {
+            for &v in &predecessors[w] {
+                dependency[v] += num_paths[v] / num_paths[w] * (1.0 + dependency[w]);
+            }
+            if w != source {
+                betweenness[w] += dependency[w];
+            }
+        }
+    }
+
+    // Each path in an undirected graph is found from both of its ends
+    let scale = if graph.is_directed() {
+        scale
+    } else {
+        scale / 2.0
+    };
+    graph
+        .node_indices()
+        .map(|node| {
+            let centrality = Centrality {
+                degree: graph.neighbors_undirected(node).count(),
+                betweenness: betweenness[node.index()] * scale,
+            };
+            (node.index(), centrality)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::read_tgf_graph;
+
+    #[test]
+    fn test_faces_of_a_quad_grid() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(2 0)\n\
+                    3\tPOINT(0 1)\n4\tPOINT(1 1)\n5\tPOINT(2 1)\n\
+                    #\n0 1\n1 2\n3 4\n4 5\n0 3\n1 4\n2 5\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+
+        let faces = faces(&graph);
+        assert_eq!(faces.len(), 2);
+        for face in &faces {
+            assert_eq!(face.unsigned_area(), 1.0);
+            assert!(face.exterior().is_closed());
+            assert!(face.exterior().is_ccw());
+        }
+
+        let dual = dual_graph(&graph);
+        assert_eq!(dual.node_count(), 2);
+        assert_eq!(dual.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_no_faces_in_a_tree() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(0 1)\n#\n0 1\n0 2\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+
+        assert!(faces(&graph).is_empty());
+        assert_eq!(dual_graph(&graph).node_count(), 0);
+    }
+
+    #[test]
+    fn test_cycle_basis_of_a_quad_grid() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(2 0)\n\
+                    3\tPOINT(0 1)\n4\tPOINT(1 1)\n5\tPOINT(2 1)\n\
+                    #\n0 1\n1 2\n3 4\n4 5\n0 3\n1 4\n2 5\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+
+        // 7 edges - 6 nodes + 1 component
+        let cycles = cycle_basis(&graph);
+        assert_eq!(cycles.len(), 2);
+        let mut areas: Vec<_> = cycles.iter().map(|c| c.unsigned_area()).collect();
+        areas.sort_by(f64::total_cmp);
+        // The BFS tree from node 0 closes one cell, and the whole grid
+        assert_eq!(areas, [1.0, 2.0]);
+        for cycle in &cycles {
+            assert!(cycle.exterior().is_closed());
+            assert!(cycle.exterior().is_ccw());
+        }
+    }
+
+    #[test]
+    fn test_cycle_basis_skips_trees_and_parallel_edges() {
+        // A tree, with a parallel edge, next to a disconnected triangle
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(0 1)\n\
+                    3\tPOINT(5 0)\n4\tPOINT(6 0)\n5\tPOINT(5 1)\n\
+                    #\n0 1\n0 2\n1 0\n3 4\n4 5\n5 3\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+
+        let cycles = cycle_basis(&graph);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].unsigned_area(), 0.5);
+        assert_eq!(cycles[0].exterior().0.len(), 4);
+        assert!(cycles[0].exterior().is_ccw());
+    }
+
+    #[test]
+    fn test_simplify_multigraph() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(0 1)\n#\n0 1\n1 0\n0 1\n1 1\n1 2\n2 2\n";
+        let mut graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+        assert_eq!(graph.edge_count(), 6);
+
+        simplify_multigraph(&mut graph);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+        let n = |i| petgraph::graph::NodeIndex::new(i);
+        assert!(graph.find_edge(n(0), n(1)).is_some());
+        assert!(graph.find_edge(n(1), n(2)).is_some());
+
+        let mut digraph: GeometryGraph<petgraph::Directed> = read_tgf_graph(&tgf[..]);
+        simplify_multigraph(&mut digraph);
+        // 0->1 and 1->0 aren't parallel
+        assert_eq!(digraph.edge_count(), 3);
+    }
+
+    #[test]
+    fn test_largest_component() {
+        // A triangle 1-3-4, an edge 0-2, and an isolated node 5
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(2 0)\n\
+                    3\tPOINT(3 0)\n4\tPOINT(4 0)\n5\tPOINT(5 0)\n\
+                    #\n0 2\n1 3\n3 4\n4 1\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+        assert_eq!(connected_components(&graph), [0, 1, 0, 1, 1, 2]);
+
+        let largest = largest_component(&graph);
+        assert_eq!(largest.node_count(), 3);
+        assert_eq!(largest.edge_count(), 3);
+        let points: Vec<_> = largest.node_weights().map(|p| p.x()).collect();
+        assert_eq!(points, [1.0, 3.0, 4.0]);
+        let n = |i| petgraph::graph::NodeIndex::new(i);
+        assert!(largest.find_edge(n(0), n(1)).is_some());
+        assert!(largest.find_edge(n(1), n(2)).is_some());
+        assert!(largest.find_edge(n(2), n(0)).is_some());
+
+        let empty = GeometryGraph::<Undirected>::default();
+        assert_eq!(largest_component(&empty).node_count(), 0);
+    }
+
+    #[test]
+    fn test_bridges_and_articulation_points() {
+        // Two triangles 0-1-2 and 3-4-5 joined by the bridge 2-3, with a tail 5-6, and an isolated
+        // node 7
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(1 1)\n3\tPOINT(2 1)\n\
+                    4\tPOINT(3 1)\n5\tPOINT(3 2)\n6\tPOINT(4 2)\n7\tPOINT(9 9)\n\
+                    #\n0 1\n1 2\n2 0\n2 3\n3 4\n4 5\n5 3\n5 6\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+        assert_eq!(bridges(&graph), [(2, 3), (5, 6)]);
+        assert_eq!(articulation_points(&graph), [2, 3, 5]);
+    }
+
+    #[test]
+    fn test_parallel_edges_are_not_bridges() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(2 0)\n#\n0 1\n1 0\n1 2\n2 2\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+        assert_eq!(bridges(&graph), [(1, 2)]);
+        assert_eq!(articulation_points(&graph), [1]);
+
+        // The root of the DFS is only an articulation point if it has multiple children
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(2 0)\n#\n1 0\n1 2\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+        assert_eq!(bridges(&graph), [(1, 0), (1, 2)]);
+        assert_eq!(articulation_points(&graph), [1]);
+    }
+
+    #[test]
+    fn test_centrality_of_a_path() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(2 0)\n3\tPOINT(3 0)\n#\n0 1\n1 2\n2 3\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+
+        let centrality = centrality(&graph);
+        let degrees: Vec<_> = centrality.iter().map(|(_, c)| c.degree).collect();
+        let betweenness: Vec<_> = centrality.iter().map(|(_, c)| c.betweenness).collect();
+        assert_eq!(degrees, [1, 2, 2, 1]);
+        // Node 1 is on the paths 0-2 and 0-3
+        assert_eq!(betweenness, [0.0, 2.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_centrality_splits_tied_paths() {
+        // A square, where there are two shortest paths between opposite corners
+        let tgf =
+            b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(1 1)\n3\tPOINT(0 1)\n#\n0 1\n1 2\n2 3\n3 0\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+
+        for (_, c) in centrality(&graph) {
+            assert_eq!(c.degree, 2);
+            assert_eq!(c.betweenness, 0.5);
+        }
+    }
+
+    #[test]
+    fn test_sampling_every_node_is_exact() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0)\n2\tPOINT(2 0)\n3\tPOINT(1 1)\n#\n0 1\n1 2\n1 3\n";
+        let graph: GeometryGraph = read_tgf_graph(&tgf[..]);
+        let mut rng = rand::rngs::mock::StepRng::new(0, 1);
+
+        let exact = centrality(&graph);
+        let sampled = sampled_centrality(&graph, 100, &mut rng);
+        assert_eq!(exact, sampled);
+        assert_eq!(exact[1].1.betweenness, 3.0);
+    }
+}
@@ -0,0 +1,125 @@
+use geo::{coord, Coord, LineString, MultiLineString, Polygon};
+
+/// Fill the given polygon with parallel hatch lines
+///
+/// The lines are at the given angle (in degrees, counter clockwise from the x-axis), and are
+/// `spacing` apart. The lines are clipped to the interior of the polygon, so lines crossing a hole
+/// are split in two.
+///
+/// The hatch lines lie on a global grid through the origin, so that the hatching of adjacent
+/// polygons lines up.
+pub fn hatch(polygon: &Polygon, angle: f64, spacing: f64) -> MultiLineString {
+    if spacing <= 0.0 {
+        return MultiLineString::new(Vec::new());
+    }
+
+    // Rotate the polygon so that the hatch lines are horizontal, and then rotate the hatch lines
+    // back
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let to_hatch = |c: Coord| coord! {x: c.x * cos + c.y * sin, y: -c.x * sin + c.y * cos};
+    let from_hatch = |c: Coord| coord! {x: c.x * cos - c.y * sin, y: c.x * sin + c.y * cos};
+
+    let edges: Vec<(Coord, Coord)> = std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .flat_map(|ring| ring.lines())
+        .map(|line| (to_hatch(line.start), to_hatch(line.end)))
+        .collect();
+    if edges.is_empty() {
+        return MultiLineString::new(Vec::new());
+    }
+
+    let (min_y, max_y) = edges
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), (s, _)| {
+            (min.min(s.y), max.max(s.y))
+        });
+
+    let first = (min_y / spacing).ceil() as i64;
+    let last = (max_y / spacing).floor() as i64;
+
+    let mut lines = Vec::new();
+    let mut crossings = Vec::new();
+    for k in first..=last {
+        let y = k as f64 * spacing;
+
+        // Each edge is half-open, so that a scanline through a vertex crosses exactly one of its
+        // edges, and horizontal edges are never crossed.
+        crossings.clear();
+        for (start, end) in &edges {
+            if (start.y <= y) != (end.y <= y) {
+                let t = (y - start.y) / (end.y - start.y);
+                crossings.push(start.x + t * (end.x - start.x));
+            }
+        }
+        crossings.sort_by(f64::total_cmp);
+
+        // Using the even-odd rule, the scanline alternates between entering and leaving the
+        // polygon at each crossing. Skip the slivers from scanlines that graze a corner.
+        for pair in crossings.chunks_exact(2) {
+            if pair[1] - pair[0] > spacing * 1e-9 {
+                lines.push(LineString::new(vec![
+                    from_hatch(coord! {x: pair[0], y: y}),
+                    from_hatch(coord! {x: pair[1], y: y}),
+                ]));
+            }
+        }
+    }
+
+    MultiLineString::new(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Geometry, Point};
+
+    use super::*;
+    use crate::io::read_wkt_geometries;
+
+    fn read_polygon(wkt: &[u8]) -> Polygon {
+        match read_wkt_geometries(wkt).next() {
+            Some(Geometry::Polygon(p)) => p,
+            _ => panic!("Expected a POLYGON"),
+        }
+    }
+
+    #[test]
+    fn test_hatch_unit_square() {
+        let square = read_polygon(b"POLYGON((0 0, 1 0, 1 1, 0 1, 0 0))");
+        let hatching = hatch(&square, 0.0, 0.25);
+
+        let expected = vec![
+            LineString::from(vec![(0.0, 0.0), (1.0, 0.0)]),
+            LineString::from(vec![(0.0, 0.25), (1.0, 0.25)]),
+            LineString::from(vec![(0.0, 0.5), (1.0, 0.5)]),
+            LineString::from(vec![(0.0, 0.75), (1.0, 0.75)]),
+        ];
+        assert_eq!(hatching.0, expected);
+    }
+
+    #[test]
+    fn test_hatch_vertical() {
+        let square = read_polygon(b"POLYGON((0 0, 1 0, 1 1, 0 1, 0 0))");
+        let hatching = hatch(&square, 90.0, 0.5);
+
+        assert_eq!(hatching.0.len(), 2);
+        for line in hatching.0 {
+            let start: Point = line.0[0].into();
+            let end: Point = line.0[1].into();
+            assert!((start.x() - end.x()).abs() < 1e-9);
+            assert!((start.y() - end.y()).abs() > 1.0 - 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_hatch_honors_holes() {
+        let square = read_polygon(b"POLYGON((0 0, 4 0, 4 4, 0 4, 0 0), (1 1, 3 1, 3 3, 1 3, 1 1))");
+        let hatching = hatch(&square, 0.0, 2.0);
+
+        let expected = vec![
+            LineString::from(vec![(0.0, 0.0), (4.0, 0.0)]),
+            LineString::from(vec![(0.0, 2.0), (1.0, 2.0)]),
+            LineString::from(vec![(3.0, 2.0), (4.0, 2.0)]),
+        ];
+        assert_eq!(hatching.0, expected);
+    }
+}
@@ -25,6 +25,9 @@ public:
         std::unique_ptr<geos::geom::Point> point;
         //! @brief All of the Node is's adjacent to this node.
         std::unordered_set<std::size_t> adjacencies;
+        //! @brief The adjacencies whose edge was first added in the direction from this node.
+        //! @details Preserves the orientation of the edges built from the input linestrings.
+        std::unordered_set<std::size_t> successors;
 
         Node(std::size_t _id, std::unique_ptr<geos::geom::Point> _point) :
             index(_id), point(std::move(_point))
@@ -74,6 +77,9 @@ public:
     //! @returns the index of the created node.
     [[nodiscard]] std::size_t add_node(geos::geom::CoordinateXY coord) noexcept;
 
+    //! @brief Get each edge once, as a (src, dst) pair.
+    //! @details Edges are oriented in the direction they were first added. Edges from nodes given
+    //! to set_nodes() with no recorded orientation are oriented from the lower to the higher index.
     [[nodiscard]] std::vector<std::pair<const Node&, const Node&>> get_edge_pairs() const;
 
     //! @brief Get the graph edges.
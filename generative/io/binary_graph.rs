@@ -0,0 +1,203 @@
+use std::io::{BufRead, BufReader, Read, Write};
+
+use geo::Point;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::EdgeType;
+
+use crate::graph::GeometryGraph;
+use crate::io::read_tgf_graph;
+
+/// The magic bytes at the start of every binary graph
+const MAGIC: &[u8; 4] = b"GGB1";
+
+/// Write the graph in a compact binary format
+///
+/// All values are little-endian. After the [MAGIC] bytes, the header has the `u64` node and edge
+/// counts. Then each node's `f64` x and y coordinates are packed one after the other, followed by
+/// the `u64` source and target node index of each edge.
+pub fn write_binary_graph<Direction, W>(writer: &mut W, graph: &GeometryGraph<Direction>)
+where
+    W: Write,
+    Direction: EdgeType,
+{
+    let mut write = |bytes: &[u8]| {
+        writer
+            .write_all(bytes)
+            .expect("Failed to write binary graph")
+    };
+
+    write(MAGIC);
+    write(&(graph.node_count() as u64).to_le_bytes());
+    write(&(graph.edge_count() as u64).to_le_bytes());
+    for point in graph.node_weights() {
+        write(&point.x().to_le_bytes());
+        write(&point.y().to_le_bytes());
+    }
+    for edge in graph.edge_references() {
+        write(&(edge.source().index() as u64).to_le_bytes());
+        write(&(edge.target().index() as u64).to_le_bytes());
+    }
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> std::io::Result<u64> {
+    let mut buffer = [0; 8];
+    reader.read_exact(&mut buffer)?;
+    Ok(u64::from_le_bytes(buffer))
+}
+
+fn read_f64<R: Read>(reader: &mut R) -> std::io::Result<f64> {
+    let mut buffer = [0; 8];
+    reader.read_exact(&mut buffer)?;
+    Ok(f64::from_le_bytes(buffer))
+}
+
+/// Read a graph written by [write_binary_graph]
+///
+/// A truncated graph is read up until the point it was cut off.
+pub fn read_binary_graph<Direction, R>(mut reader: R) -> GeometryGraph<Direction>
+where
+    R: Read,
+    Direction: EdgeType,
+{
+    let mut graph = GeometryGraph::<Direction>::default();
+
+    let mut magic = [0; 4];
+    if let Err(e) = reader.read_exact(&mut magic) {
+        log::warn!("Failed to read binary graph header: {e:?}");
+        return graph;
+    }
+    if &magic != MAGIC {
+        log::warn!("Binary graph has bad magic bytes: {magic:?}");
+        return graph;
+    }
+    let (nodes, edges) = match (read_u64(&mut reader), read_u64(&mut reader)) {
+        (Ok(nodes), Ok(edges)) => (nodes as usize, edges as usize),
+        _ => {
+            log::warn!("Failed to read binary graph node and edge counts");
+            return graph;
+        }
+    };
+    // Don't trust the counts enough to allocate for a corrupted header
+    graph.reserve_nodes(nodes.min(1 << 20));
+    graph.reserve_edges(edges.min(1 << 20));
+
+    for _ in 0..nodes {
+        match (read_f64(&mut reader), read_f64(&mut reader)) {
+            (Ok(x), Ok(y)) => {
+                graph.add_node(Point::new(x, y));
+            }
+            _ => {
+                log::warn!(
+                    "Binary graph truncated after {} of {nodes} nodes",
+                    graph.node_count()
+                );
+                return graph;
+            }
+        }
+    }
+
+    for i in 0..edges {
+        let (source, target) = match (read_u64(&mut reader), read_u64(&mut reader)) {
+            (Ok(source), Ok(target)) => (source as usize, target as usize),
+            _ => {
+                log::warn!("Binary graph truncated after {i} of {edges} edges");
+                return graph;
+            }
+        };
+        if source >= nodes || target >= nodes {
+            log::warn!("Failed to find node from edge {source} -> {target} in graph");
+            continue;
+        }
+        graph.add_edge(NodeIndex::new(source), NodeIndex::new(target), ());
+    }
+
+    graph
+}
+
+/// Read a graph in either Trivial Graph Format, or the binary format from [write_binary_graph]
+///
+/// The format is detected from the binary graph's magic bytes.
+pub fn read_graph<Direction, R>(reader: R) -> GeometryGraph<Direction>
+where
+    R: Read,
+    Direction: EdgeType,
+{
+    let mut reader = BufReader::new(reader);
+    let is_binary = match reader.fill_buf() {
+        Ok(buffer) => buffer.starts_with(MAGIC),
+        Err(e) => {
+            log::warn!("Failed to read graph: {e:?}");
+            return GeometryGraph::default();
+        }
+    };
+
+    if is_binary {
+        read_binary_graph(reader)
+    } else {
+        read_tgf_graph(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::{Directed, Undirected};
+
+    use super::*;
+
+    #[test]
+    fn test_binary_graph_round_trip() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1.5 -2)\n2\tPOINT(3 4)\n#\n0\t1\n2\t1\n";
+        let graph = read_tgf_graph::<Directed, _>(&tgf[..]);
+
+        let mut buffer = Vec::new();
+        write_binary_graph(&mut buffer, &graph);
+        assert_eq!(buffer.len(), 4 + 2 * 8 + 3 * 16 + 2 * 16);
+
+        let round_trip: GeometryGraph<Directed> = read_binary_graph(&buffer[..]);
+        let points: Vec<_> = round_trip.node_weights().copied().collect();
+        assert_eq!(
+            points,
+            [
+                Point::new(0.0, 0.0),
+                Point::new(1.5, -2.0),
+                Point::new(3.0, 4.0)
+            ]
+        );
+        assert_eq!(round_trip.edge_count(), 2);
+        assert!(round_trip.contains_edge(0.into(), 1.into()));
+        assert!(round_trip.contains_edge(2.into(), 1.into()));
+        assert!(!round_trip.contains_edge(1.into(), 2.into()));
+    }
+
+    #[test]
+    fn test_read_graph_detects_format() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 1)\n#\n0\t1\n";
+        let graph = read_graph::<Undirected, _>(&tgf[..]);
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+
+        let mut buffer = Vec::new();
+        write_binary_graph(&mut buffer, &graph);
+        let graph = read_graph::<Undirected, _>(&buffer[..]);
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_read_truncated_binary_graph() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 1)\n#\n0\t1\n";
+        let graph = read_tgf_graph::<Undirected, _>(&tgf[..]);
+        let mut buffer = Vec::new();
+        write_binary_graph(&mut buffer, &graph);
+
+        // Cut off the edge
+        buffer.truncate(buffer.len() - 4);
+        let truncated = read_binary_graph::<Undirected, _>(&buffer[..]);
+        assert_eq!(truncated.node_count(), 2);
+        assert_eq!(truncated.edge_count(), 0);
+
+        let garbage = read_binary_graph::<Undirected, _>(&b"garbage"[..]);
+        assert_eq!(garbage.node_count(), 0);
+    }
+}
@@ -0,0 +1,120 @@
+use std::io::{ErrorKind, Read, Write};
+
+use geo::Geometry;
+
+use super::wkt::{read_wkbraw_geometries, write_wkbraw_geometries};
+
+/// Read the payload of the next frame, or `None` at the end of the stream
+///
+/// Each frame is a little-endian `u32` byte count, followed by that many bytes of payload. The
+/// stream ends cleanly when the reader reaches EOF on a frame boundary.
+pub fn read_frame<R: Read>(reader: &mut R) -> std::io::Result<Option<Vec<u8>>> {
+    let mut length = [0u8; 4];
+    match reader.read_exact(&mut length) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let mut payload = vec![0u8; u32::from_le_bytes(length) as usize];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(payload))
+}
+
+/// Write the given payload as a single frame, and flush it so the other end can respond
+pub fn write_frame<W: Write>(writer: &mut W, payload: &[u8]) -> std::io::Result<()> {
+    let length = u32::try_from(payload.len())
+        .map_err(|_| std::io::Error::new(ErrorKind::InvalidInput, "Frame too large"))?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// Read the next batch of geometries, or `None` at the end of the stream
+///
+/// Geometries that fail to parse are logged, and end the batch early.
+pub fn read_geometry_batch<R: Read>(reader: &mut R) -> std::io::Result<Option<Vec<Geometry>>> {
+    let payload = read_frame(reader)?;
+    Ok(payload.map(|payload| read_wkbraw_geometries(payload.as_slice()).collect()))
+}
+
+/// Write the given geometries as a single frame
+pub fn write_geometry_batch<W, G>(writer: &mut W, geometries: G) -> std::io::Result<()>
+where
+    W: Write,
+    G: IntoIterator<Item = Geometry>,
+{
+    let mut payload = Vec::new();
+    write_wkbraw_geometries(&mut payload, geometries);
+    write_frame(writer, &payload)
+}
+
+/// Respond to each batch of geometries read from the reader until the end of the stream
+///
+/// Each batch is a frame of concatenated raw WKB geometries. Each request batch gets exactly one
+/// response batch, even if the response is empty. This lets a tool stay running between requests,
+/// avoiding the startup cost of a new process for each one.
+pub fn serve<R, W, F>(mut reader: R, mut writer: W, mut handler: F) -> std::io::Result<()>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(Vec<Geometry>) -> Vec<Geometry>,
+{
+    while let Some(batch) = read_geometry_batch(&mut reader)? {
+        log::debug!("Received batch of {} geometries", batch.len());
+        let response = handler(batch);
+        write_geometry_batch(&mut writer, response)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Point;
+
+    use super::*;
+
+    #[test]
+    fn test_frame_round_trip() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, b"hello").unwrap();
+        write_frame(&mut buffer, b"").unwrap();
+        assert_eq!(buffer.len(), 4 + 5 + 4);
+
+        let mut reader = buffer.as_slice();
+        assert_eq!(read_frame(&mut reader).unwrap().unwrap(), b"hello");
+        assert_eq!(read_frame(&mut reader).unwrap().unwrap(), b"");
+        assert!(read_frame(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_truncated_frame_is_an_error() {
+        let buffer = [10u8, 0, 0, 0, 1, 2, 3];
+        let mut reader = &buffer[..];
+        assert!(read_frame(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_serve() {
+        let mut requests = Vec::new();
+        let batch = vec![
+            Geometry::Point(Point::new(1.0, 2.0)),
+            Geometry::Point(Point::new(3.0, 4.0)),
+        ];
+        write_geometry_batch(&mut requests, batch.clone()).unwrap();
+        write_geometry_batch(&mut requests, Vec::new()).unwrap();
+
+        let mut responses = Vec::new();
+        serve(requests.as_slice(), &mut responses, |geoms| {
+            geoms.into_iter().rev().collect()
+        })
+        .unwrap();
+
+        let mut reader = responses.as_slice();
+        let first = read_geometry_batch(&mut reader).unwrap().unwrap();
+        assert_eq!(first, batch.into_iter().rev().collect::<Vec<_>>());
+        let second = read_geometry_batch(&mut reader).unwrap().unwrap();
+        assert!(second.is_empty());
+        assert!(read_geometry_batch(&mut reader).unwrap().is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use std::io::Write;
+
+use petgraph::visit::EdgeRef;
+use petgraph::EdgeType;
+
+use crate::graph::GeometryGraph;
+
+/// Write the graph in Graphviz DOT format
+///
+/// Each node has its coordinates as `x` and `y` attributes, and as a pinned `pos` so that `neato
+/// -n` draws the graph where it is, rather than laying it out again.
+pub fn write_dot_graph<Direction, W>(writer: &mut W, graph: &GeometryGraph<Direction>)
+where
+    W: Write,
+    Direction: EdgeType,
+{
+    let (kind, connector) = if graph.is_directed() {
+        ("digraph", "->")
+    } else {
+        ("graph", "--")
+    };
+
+    writeln!(writer, "{kind} {{").expect("Failed to write DOT header");
+    for idx in graph.node_indices() {
+        let point = graph[idx];
+        let (x, y) = (point.x(), point.y());
+        writeln!(
+            writer,
+            "    {} [x={x}, y={y}, pos=\"{x},{y}!\"];",
+            idx.index()
+        )
+        .expect("Failed to write DOT node");
+    }
+    for edge in graph.edge_references() {
+        writeln!(
+            writer,
+            "    {} {connector} {};",
+            edge.source().index(),
+            edge.target().index()
+        )
+        .expect("Failed to write DOT edge");
+    }
+    writeln!(writer, "}}").expect("Failed to write DOT footer");
+}
+
+/// Write the graph in GraphML format
+///
+/// Each node has its coordinates as `x` and `y` attributes of type double.
+pub fn write_graphml_graph<Direction, W>(writer: &mut W, graph: &GeometryGraph<Direction>)
+where
+    W: Write,
+    Direction: EdgeType,
+{
+    let edgedefault = if graph.is_directed() {
+        "directed"
+    } else {
+        "undirected"
+    };
+
+    let header = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="x" for="node" attr.name="x" attr.type="double"/>
+  <key id="y" for="node" attr.name="y" attr.type="double"/>
+  <graph id="G" edgedefault="{edgedefault}">"#
+    );
+    writeln!(writer, "{header}").expect("Failed to write GraphML header");
+    for idx in graph.node_indices() {
+        let point = graph[idx];
+        writeln!(
+            writer,
+            r#"    <node id="n{}"><data key="x">{}</data><data key="y">{}</data></node>"#,
+            idx.index(),
+            point.x(),
+            point.y()
+        )
+        .expect("Failed to write GraphML node");
+    }
+    for edge in graph.edge_references() {
+        writeln!(
+            writer,
+            r#"    <edge source="n{}" target="n{}"/>"#,
+            edge.source().index(),
+            edge.target().index()
+        )
+        .expect("Failed to write GraphML edge");
+    }
+    writeln!(writer, "  </graph>\n</graphml>").expect("Failed to write GraphML footer");
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::{Directed, Undirected};
+
+    use super::*;
+    use crate::io::read_tgf_graph;
+
+    #[test]
+    fn test_write_dot_graph() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1.5 -2)\n#\n0\t1\n";
+
+        let graph = read_tgf_graph::<Undirected, _>(&tgf[..]);
+        let mut buffer = Vec::new();
+        write_dot_graph(&mut buffer, &graph);
+        let expected = "graph {\n    0 [x=0, y=0, pos=\"0,0!\"];\n    1 [x=1.5, y=-2, pos=\"1.5,-2!\"];\n    0 -- 1;\n}\n";
+        assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+
+        let graph = read_tgf_graph::<Directed, _>(&tgf[..]);
+        let mut buffer = Vec::new();
+        write_dot_graph(&mut buffer, &graph);
+        let dot = String::from_utf8(buffer).unwrap();
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.contains("    0 -> 1;\n"));
+    }
+
+    #[test]
+    fn test_write_graphml_graph() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1.5 -2)\n#\n0\t1\n";
+        let graph = read_tgf_graph::<Directed, _>(&tgf[..]);
+
+        let mut buffer = Vec::new();
+        write_graphml_graph(&mut buffer, &graph);
+        let graphml = String::from_utf8(buffer).unwrap();
+        assert!(graphml.contains(r#"<graph id="G" edgedefault="directed">"#));
+        assert!(graphml
+            .contains(r#"<node id="n1"><data key="x">1.5</data><data key="y">-2</data></node>"#));
+        assert!(graphml.contains(r#"<edge source="n0" target="n1"/>"#));
+        assert!(graphml.ends_with("</graph>\n</graphml>\n"));
+    }
+}
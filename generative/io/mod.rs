@@ -1,13 +1,32 @@
+mod binary_graph;
+pub mod framed;
+mod graph_export;
+#[cfg(feature = "gdal")]
+mod ogr;
+mod progress;
 mod stdio;
 mod tgf;
 mod wkt;
 
+pub use binary_graph::{read_binary_graph, read_graph, write_binary_graph};
+pub use graph_export::{write_dot_graph, write_graphml_graph};
+#[cfg(feature = "gdal")]
+pub use ogr::read_ogr_geometries;
+pub use progress::Progress;
 pub use stdio::{get_input_reader, get_output_writer};
-// TODO: A read_graph method that takes in the GraphFormat (the same as read_geometries) would
-// require FFI bindings to geom2graph. See: https://github.com/Notgnoshi/generative/issues/130
-pub use tgf::{read_tgf_graph, write_graph, write_tgf_graph, GraphFormat};
+// TODO: Reading a GraphFormat::Wkt graph (the same as read_geometries) would require FFI bindings
+// to geom2graph. See: https://github.com/Notgnoshi/generative/issues/130
+pub use tgf::{
+    read_tgf_graph, write_graph, write_graph_with_options, write_tgf_graph,
+    write_tgf_graph_with_node_labels, write_tgf_graph_with_options, GraphFormat, TgfOptions,
+};
 
 pub use self::wkt::{
-    read_geometries, read_wkt_geometries, read_wkt_geometries_and_styles, write_geometries,
-    write_wkt_geometries, GeometryAndStyle, GeometryFormat, SvgStyle,
+    parse_crs_header, read_geometries, read_labeled_wkt_geometries, read_polygons,
+    read_wkt_geometries, read_wkt_geometries_and_styles, read_wkt_geometries_styles_and_z,
+    write_crs_header, write_geometries, write_labeled_wkt_geometries, write_wkt_geometries,
+    write_wkt_geometries_with_precision, GeometryAndStyle, GeometryFormat, GeometryInputFormat,
+    SvgStyle,
 };
+#[cfg(feature = "parallel")]
+pub use self::wkt::{read_wkt_geometries_and_styles_parallel, read_wkt_geometries_parallel};
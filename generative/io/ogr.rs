@@ -0,0 +1,109 @@
+use std::io::Read;
+
+use gdal::vector::LayerAccess;
+use gdal::Dataset;
+use geo::Geometry;
+use log::{error, warn};
+
+/// The in-memory GDAL file that the input is copied into
+const MEM_FILE: &str = "/vsimem/generative-ogr-input";
+
+/// Read the geometry of each feature in each layer of a GDAL vector dataset
+///
+/// GDAL needs random access to the dataset, so the whole input is read into memory first. Zipped
+/// and gzipped inputs are decompressed, which is how to read a Shapefile along with its sidecar
+/// files from a single input. MULTI-geometries and GEOMETRYCOLLECTIONs are yielded as-is, and Z
+/// coordinates are dropped.
+///
+/// TODO: The feature attributes are ignored. They could be passed through as labels.
+pub fn read_ogr_geometries<R>(mut reader: R) -> std::vec::IntoIter<Geometry<f64>>
+where
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    if let Err(e) = reader.read_to_end(&mut bytes) {
+        error!("Failed to read OGR dataset: {e:?}");
+        return Vec::new().into_iter();
+    }
+
+    // GDAL identifies some formats, and where an archive's path ends, by the file extension
+    let (file, path) = match bytes.as_slice() {
+        [b'P', b'K', 3, 4, ..] => (format!("{MEM_FILE}.zip"), format!("/vsizip/{MEM_FILE}.zip")),
+        [0x1f, 0x8b, ..] => (format!("{MEM_FILE}.gz"), format!("/vsigzip/{MEM_FILE}.gz")),
+        [b'S', b'Q', b'L', b'i', b't', b'e', ..] => {
+            (format!("{MEM_FILE}.gpkg"), format!("{MEM_FILE}.gpkg"))
+        }
+        _ => (MEM_FILE.to_string(), MEM_FILE.to_string()),
+    };
+    if let Err(e) = gdal::vsi::create_mem_file(&file, bytes) {
+        error!("Failed to load OGR dataset: {e}");
+        return Vec::new().into_iter();
+    }
+
+    let geometries = read_dataset(&path);
+
+    if let Err(e) = gdal::vsi::unlink_mem_file(&file) {
+        warn!("Failed to clean up in-memory OGR dataset: {e}");
+    }
+    geometries.into_iter()
+}
+
+fn read_dataset(path: &str) -> Vec<Geometry<f64>> {
+    let dataset = match Dataset::open(path) {
+        Ok(dataset) => dataset,
+        Err(e) => {
+            error!("Failed to open OGR dataset: {e}");
+            return Vec::new();
+        }
+    };
+
+    let mut geometries = Vec::new();
+    for mut layer in dataset.layers() {
+        let name = layer.name();
+        for feature in layer.features() {
+            let Some(geometry) = feature.geometry() else {
+                continue;
+            };
+            match geometry.to_geo() {
+                Ok(geometry) => geometries.push(geometry),
+                Err(e) => warn!("Skipping unsupported geometry in layer '{name}': {e}"),
+            }
+        }
+    }
+    geometries
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{LineString, MultiPoint, Point};
+
+    use super::*;
+
+    #[test]
+    fn test_read_geojson_features() {
+        let input = br#"{
+            "type": "FeatureCollection",
+            "features": [
+                {"type": "Feature", "properties": {"id": 1}, "geometry": {"type": "MultiPoint", "coordinates": [[0, 0], [1, 1]]}},
+                {"type": "Feature", "properties": {"id": 2}, "geometry": null},
+                {"type": "Feature", "properties": {"id": 3}, "geometry": {"type": "LineString", "coordinates": [[0, 0], [1, 0, 2]]}}
+            ]
+        }"#;
+        let geometries: Vec<_> = read_ogr_geometries(&input[..]).collect();
+
+        let expected = vec![
+            Geometry::MultiPoint(MultiPoint::new(vec![
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 1.0),
+            ])),
+            Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 0.0)])),
+        ];
+        assert_eq!(geometries, expected);
+    }
+
+    #[test]
+    fn test_garbage_is_empty() {
+        let geometries: Vec<_> = read_ogr_geometries(&b"garbage"[..]).collect();
+        assert!(geometries.is_empty());
+    }
+}
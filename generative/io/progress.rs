@@ -0,0 +1,123 @@
+use std::time::{Duration, Instant};
+
+/// How often to log progress
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An iterator adapter that periodically logs how many items have been processed
+///
+/// Progress is logged at the INFO level, so it goes to stderr without disrupting stdout. When
+/// disabled, the items are passed through without any reporting.
+pub struct Progress<I> {
+    inner: I,
+    enabled: bool,
+    count: usize,
+    total: Option<usize>,
+    start: Instant,
+    last_report: Instant,
+    finished: bool,
+}
+
+impl<I: Iterator> Progress<I> {
+    pub fn new(inner: I, enabled: bool) -> Self {
+        let now = Instant::now();
+        Self {
+            inner,
+            enabled,
+            count: 0,
+            total: None,
+            start: now,
+            last_report: now,
+            finished: false,
+        }
+    }
+
+    /// Set the total number of items, so that the percentage and ETA can be reported
+    pub fn total(mut self, total: usize) -> Self {
+        self.total = Some(total);
+        self
+    }
+}
+
+impl<I: Iterator> Iterator for Progress<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next();
+        if !self.enabled || self.finished {
+            return item;
+        }
+
+        let now = Instant::now();
+        if item.is_some() {
+            self.count += 1;
+            if now.duration_since(self.last_report) >= REPORT_INTERVAL {
+                self.last_report = now;
+                log::info!(
+                    "{}",
+                    format_progress(self.count, self.total, now.duration_since(self.start))
+                );
+            }
+        } else {
+            self.finished = true;
+            log::info!(
+                "Processed {} geometries in {:.1}s",
+                self.count,
+                now.duration_since(self.start).as_secs_f64()
+            );
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+fn format_progress(count: usize, total: Option<usize>, elapsed: Duration) -> String {
+    let elapsed = elapsed.as_secs_f64();
+    let rate = if elapsed > 0.0 {
+        count as f64 / elapsed
+    } else {
+        0.0
+    };
+
+    match total {
+        Some(total) if total > 0 => {
+            let percent = 100.0 * count as f64 / total as f64;
+            let eta = if rate > 0.0 {
+                format!("{:.0}s", total.saturating_sub(count) as f64 / rate)
+            } else {
+                String::from("unknown")
+            };
+            format!("Processed {count}/{total} geometries ({percent:.1}%, {rate:.1}/s, ETA {eta})")
+        }
+        _ => format!("Processed {count} geometries ({rate:.1}/s)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_passes_items_through() {
+        let items: Vec<_> = Progress::new(0..5, true).total(5).collect();
+        assert_eq!(items, [0, 1, 2, 3, 4]);
+
+        let items: Vec<_> = Progress::new(0..5, false).collect();
+        assert_eq!(items, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_format_progress() {
+        let elapsed = Duration::from_secs(2);
+        assert_eq!(
+            format_progress(100, None, elapsed),
+            "Processed 100 geometries (50.0/s)"
+        );
+        assert_eq!(
+            format_progress(100, Some(400), elapsed),
+            "Processed 100/400 geometries (25.0%, 50.0/s, ETA 6s)"
+        );
+    }
+}
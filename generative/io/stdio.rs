@@ -1,24 +1,38 @@
 use std::fs::File;
-use std::io::{BufReader, BufWriter, Read, Write};
-use std::path::PathBuf;
+use std::io::{BufReader, BufWriter, IsTerminal, LineWriter, Read, Write};
+use std::path::{Path, PathBuf};
 
-pub fn get_output_writer(output: &Option<PathBuf>) -> Result<BufWriter<Box<dyn Write>>, String> {
+/// Whether the given path is `-`, meaning stdin or stdout
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Get a writer for the given file, or stdout if the file is `None` or `-`
+///
+/// Terminals are line buffered, so that each geometry shows up as soon as it's written. Everything
+/// else, including pipes, named pipes, and `>(...)` process substitutions, is block buffered, so
+/// that chaining tools together doesn't cost a write per line.
+pub fn get_output_writer(output: &Option<PathBuf>) -> Result<Box<dyn Write>, String> {
     match output {
-        Some(path) => match File::create(path) {
+        Some(path) if !is_stdio(path) => match File::create(path) {
             Err(why) => Err(format!(
                 "Couldn't create: '{}' because: '{}'",
                 path.display(),
                 why
             )),
-            Ok(file) => Ok(BufWriter::new(Box::new(file))),
+            Ok(file) if file.is_terminal() => Ok(Box::new(LineWriter::new(file))),
+            Ok(file) => Ok(Box::new(BufWriter::new(file))),
         },
-        None => Ok(BufWriter::new(Box::new(std::io::stdout()))),
+        // Stdout is already line buffered
+        _ if std::io::stdout().is_terminal() => Ok(Box::new(std::io::stdout())),
+        _ => Ok(Box::new(BufWriter::new(std::io::stdout()))),
     }
 }
 
+/// Get a reader for the given file, or stdin if the file is `None` or `-`
 pub fn get_input_reader(input: &Option<PathBuf>) -> Result<BufReader<Box<dyn Read>>, String> {
     match input {
-        Some(path) => match File::open(path) {
+        Some(path) if !is_stdio(path) => match File::open(path) {
             Err(why) => Err(format!(
                 "Couldn't open: '{}' because: '{}'",
                 path.display(),
@@ -26,6 +40,35 @@ pub fn get_input_reader(input: &Option<PathBuf>) -> Result<BufReader<Box<dyn Rea
             )),
             Ok(file) => Ok(BufReader::new(Box::new(file))),
         },
-        None => Ok(BufReader::new(Box::new(std::io::stdin()))),
+        _ => Ok(BufReader::new(Box::new(std::io::stdin()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+
+    #[test]
+    fn test_regular_file_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("generative-stdio-{}.wkt", std::process::id()));
+        let mut writer = get_output_writer(&Some(path.clone())).unwrap();
+        writeln!(writer, "POINT(1 2)").unwrap();
+        writeln!(writer, "POINT(3 4)").unwrap();
+        drop(writer);
+
+        let reader = get_input_reader(&Some(path.clone())).unwrap();
+        let lines: Vec<_> = reader.lines().map(Result::unwrap).collect();
+        assert_eq!(lines, ["POINT(1 2)", "POINT(3 4)"]);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_dash_is_stdio() {
+        assert!(is_stdio(Path::new("-")));
+        assert!(!is_stdio(Path::new("./-")));
     }
 }
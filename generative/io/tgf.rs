@@ -1,14 +1,14 @@
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Read, Write};
 
-use clap::ValueEnum;
+use clap::{Args, ValueEnum};
 use geo::{Geometry, Line, Point};
 use petgraph::visit::EdgeRef;
 use petgraph::EdgeType;
 use wkt::TryFromWkt;
 
 use crate::graph::GeometryGraph;
-use crate::io::write_wkt_geometries;
+use crate::io::{write_binary_graph, write_dot_graph, write_graphml_graph, write_wkt_geometries};
 
 #[derive(Debug, Clone, ValueEnum)]
 pub enum GraphFormat {
@@ -18,6 +18,14 @@ pub enum GraphFormat {
     Tgf,
     /// Output the geometry graph as pure WKT geometries, one per line.
     Wkt,
+    /// Output the graph in a compact binary format, for large graphs
+    ///
+    /// Tools that read TGF graphs can also read the binary format.
+    Binary,
+    /// Output the graph in Graphviz DOT format, with each node's coordinates as attributes
+    Dot,
+    /// Output the graph in GraphML format, with each node's coordinates as attributes
+    Graphml,
 }
 
 impl std::fmt::Display for GraphFormat {
@@ -26,21 +34,68 @@ impl std::fmt::Display for GraphFormat {
             // important: Should match clap::ValueEnum format
             GraphFormat::Wkt => write!(f, "wkt"),
             GraphFormat::Tgf => write!(f, "tgf"),
+            GraphFormat::Binary => write!(f, "binary"),
+            GraphFormat::Dot => write!(f, "dot"),
+            GraphFormat::Graphml => write!(f, "graphml"),
+        }
+    }
+}
+
+/// Options for writing Trivial Graph Format, for interop with other tools
+///
+/// Can be flattened into a tool's clap options.
+#[derive(Debug, Clone, PartialEq, Eq, Args)]
+pub struct TgfOptions {
+    /// The separator between the fields of each TGF node and edge. Defaults to a tab.
+    ///
+    /// Only whitespace separators can be read back in by these tools.
+    #[clap(
+        long = "tgf-separator",
+        default_value = "\t",
+        hide_default_value = true
+    )]
+    pub separator: String,
+
+    /// Don't label the TGF nodes with their WKT POINT, and only write the graph topology
+    ///
+    /// Graphs without labels can't be read back in by these tools.
+    #[clap(long = "tgf-no-labels")]
+    pub no_labels: bool,
+}
+
+impl Default for TgfOptions {
+    fn default() -> Self {
+        Self {
+            separator: String::from("\t"),
+            no_labels: false,
         }
     }
 }
 
-pub fn write_graph<Direction, W>(
+pub fn write_graph<Direction, W>(writer: W, graph: &GeometryGraph<Direction>, format: &GraphFormat)
+where
+    W: Write,
+    Direction: EdgeType,
+{
+    write_graph_with_options(writer, graph, format, &TgfOptions::default());
+}
+
+/// Write the graph in the given format, using the given options if the format is TGF
+pub fn write_graph_with_options<Direction, W>(
     mut writer: W,
     graph: &GeometryGraph<Direction>,
     format: &GraphFormat,
+    options: &TgfOptions,
 ) where
     W: Write,
     Direction: EdgeType,
 {
     match format {
-        GraphFormat::Tgf => write_tgf_graph(&mut writer, graph),
+        GraphFormat::Tgf => write_tgf_graph_with_options(&mut writer, graph, options),
         GraphFormat::Wkt => write_wkt_graph(writer, graph),
+        GraphFormat::Binary => write_binary_graph(&mut writer, graph),
+        GraphFormat::Dot => write_dot_graph(&mut writer, graph),
+        GraphFormat::Graphml => write_graphml_graph(&mut writer, graph),
     }
 }
 
@@ -49,20 +104,61 @@ where
     W: Write,
     Direction: EdgeType,
 {
+    write_tgf_graph_with_options(writer, graph, &TgfOptions::default());
+}
+
+pub fn write_tgf_graph_with_options<Direction, W>(
+    writer: &mut W,
+    graph: &GeometryGraph<Direction>,
+    options: &TgfOptions,
+) where
+    W: Write,
+    Direction: EdgeType,
+{
+    write_tgf_graph_with_node_labels::<_, _, String>(writer, graph, options, &[]);
+}
+
+/// Write the graph in TGF, with an extra label after each node's WKT POINT
+///
+/// The extra label for the node with index `i` is `labels[i]`. Nodes without an extra label, and
+/// every node if `options.no_labels` is set, are written without one. The extra labels are ignored
+/// when the graph is read back in.
+pub fn write_tgf_graph_with_node_labels<Direction, W, L>(
+    writer: &mut W,
+    graph: &GeometryGraph<Direction>,
+    options: &TgfOptions,
+    labels: &[L],
+) where
+    W: Write,
+    Direction: EdgeType,
+    L: std::fmt::Display,
+{
+    let sep = &options.separator;
     // let (nodes, edges) = graph.into_nodes_edges();
     for idx in graph.node_indices() {
         let coord = graph
             .node_weight(idx)
             .expect("Got index to nonexistent node.");
         let index = idx.index();
-        writeln!(writer, "{}\tPOINT({} {})", index, coord.x(), coord.y())
-            .expect("Failed to write node label");
+        if options.no_labels {
+            writeln!(writer, "{index}")
+        } else if let Some(label) = labels.get(index) {
+            writeln!(
+                writer,
+                "{index}{sep}POINT({} {}){sep}{label}",
+                coord.x(),
+                coord.y()
+            )
+        } else {
+            writeln!(writer, "{index}{sep}POINT({} {})", coord.x(), coord.y())
+        }
+        .expect("Failed to write node label");
     }
     writeln!(writer, "#").expect("Failed to write node/edge separator");
     for edge in graph.edge_references() {
         writeln!(
             writer,
-            "{}\t{}",
+            "{}{sep}{}",
             edge.source().index(),
             edge.target().index()
         )
@@ -144,6 +240,19 @@ fn read_raw_edge(line: String) -> Result<(usize, usize), String> {
     Ok((source, target))
 }
 
+/// Whether the given TGF line should be skipped over
+///
+/// TGF from other tools may have blank lines, or '//' comments in either the node or edge
+/// sections.
+fn is_blank_or_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with("//")
+}
+
+/// Read a graph in Trivial Graph Format
+///
+/// The node and edge sections are separated by a line containing a lone '#'. Blank lines, and lines
+/// starting with '//' are skipped.
 pub fn read_tgf_graph<Direction, R>(reader: R) -> GeometryGraph<Direction>
 where
     R: Read,
@@ -158,9 +267,12 @@ where
         let Ok(current_line) = line else {
             break;
         };
-        if current_line.starts_with('#') {
+        if current_line.trim() == "#" {
             break;
         }
+        if is_blank_or_comment(&current_line) {
+            continue;
+        }
 
         let (raw_id, label) = match read_raw_node(current_line) {
             Ok(node) => node,
@@ -179,6 +291,9 @@ where
     // Read the edges
     for line in lines {
         let current_line = line.unwrap();
+        if is_blank_or_comment(&current_line) {
+            continue;
+        }
         let (raw_source, raw_target) = match read_raw_edge(current_line) {
             Ok(edge) => edge,
             Err(e) => {
@@ -213,6 +328,63 @@ mod tests {
 
     use super::*;
 
+    fn write_to_string(graph: &GeometryGraph<Undirected>, options: &TgfOptions) -> String {
+        let mut buffer = Vec::new();
+        write_tgf_graph_with_options(&mut buffer, graph, options);
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_write_tgf_options() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(2 2)\n#\n0\t1\n";
+        let graph = read_tgf_graph::<Undirected, _>(&tgf[..]);
+
+        let default = write_to_string(&graph, &TgfOptions::default());
+        assert_eq!(default.as_bytes(), tgf);
+
+        let options = TgfOptions {
+            separator: String::from(" "),
+            no_labels: false,
+        };
+        let spaces = write_to_string(&graph, &options);
+        assert_eq!(spaces, "0 POINT(0 0)\n1 POINT(2 2)\n#\n0 1\n");
+        // Whitespace separated TGF can be read back in
+        let round_trip = read_tgf_graph::<Undirected, _>(spaces.as_bytes());
+        assert_eq!(round_trip.node_count(), 2);
+        assert_eq!(round_trip.edge_count(), 1);
+
+        let options = TgfOptions {
+            separator: String::from(","),
+            no_labels: true,
+        };
+        let topology = write_to_string(&graph, &options);
+        assert_eq!(topology, "0\n1\n#\n0,1\n");
+    }
+
+    #[test]
+    fn test_write_tgf_node_labels() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(2 2)\n2\tPOINT(4 4)\n#\n0\t1\n1\t2\n";
+        let graph = read_tgf_graph::<Undirected, _>(&tgf[..]);
+
+        let mut buffer = Vec::new();
+        let labels = ["0,0", "1,0"];
+        write_tgf_graph_with_node_labels(&mut buffer, &graph, &TgfOptions::default(), &labels);
+        let labeled = String::from_utf8(buffer).unwrap();
+        assert_eq!(
+            labeled,
+            "0\tPOINT(0 0)\t0,0\n1\tPOINT(2 2)\t1,0\n2\tPOINT(4 4)\n#\n0\t1\n1\t2\n"
+        );
+
+        // The extra labels are ignored when reading the graph back in
+        let round_trip = read_tgf_graph::<Undirected, _>(labeled.as_bytes());
+        assert_eq!(round_trip.node_count(), 3);
+        assert_eq!(
+            round_trip.node_weight(1.into()),
+            Some(&Point::new(2.0, 2.0))
+        );
+        assert_eq!(round_trip.edge_count(), 2);
+    }
+
     #[test]
     fn test_read_tgf_undirected() {
         let tgf = b"1\tPOINT(0 0)\n42\tPOINT(2 2)\n#\n1\t42";
@@ -252,4 +424,27 @@ mod tests {
         assert!(graph.contains_edge(0.into(), 1.into()));
         assert!(!graph.contains_edge(1.into(), 0.into()));
     }
+
+    #[test]
+    fn test_read_tgf_skips_blank_lines_and_comments() {
+        let tgf = b"// nodes\n\n1\tPOINT(0 0)\n  // indented comment\n42\tPOINT(2 2)\n\n  #  \n\n// edges\n1\t42\n\n\n";
+        let graph = read_tgf_graph::<Undirected, _>(&tgf[..]);
+
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.node_weight(0.into()), Some(&Point::new(0.0, 0.0)));
+        assert_eq!(graph.node_weight(1.into()), Some(&Point::new(2.0, 2.0)));
+        assert!(graph.contains_edge(0.into(), 1.into()));
+    }
+
+    #[test]
+    fn test_read_tgf_only_lone_hash_is_separator() {
+        // The '#1' line isn't the separator, so it's skipped as a malformed node
+        let tgf = b"1\tPOINT(0 0)\n#1\tPOINT(1 1)\n2\tPOINT(2 2)\n#\n1\t2";
+        let graph = read_tgf_graph::<Undirected, _>(&tgf[..]);
+
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.node_weight(1.into()), Some(&Point::new(2.0, 2.0)));
+    }
 }
@@ -1,4 +1,6 @@
+use std::borrow::Cow;
 use std::io::{BufRead, BufReader, Lines, Read, Write};
+use std::path::Path;
 use std::str::FromStr;
 
 use clap::ValueEnum;
@@ -9,7 +11,9 @@ use geo::{
 use hex::{decode, encode_upper};
 use log::warn;
 use wkb::{geom_to_wkb, wkb_to_geom, write_geom_to_wkb};
-use wkt::{ToWkt, Wkt};
+use wkt::Wkt;
+
+use crate::io::get_input_reader;
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum GeometryFormat {
@@ -34,23 +38,126 @@ impl std::fmt::Display for GeometryFormat {
     }
 }
 
+/// The formats geometries can be read from; each [GeometryFormat], and the input-only formats
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum GeometryInputFormat {
+    /// One WKT geometry per line. Ignores trailing garbage; does not skip over leading garbage.
+    Wkt,
+    /// Stringified hex encoded WKB, one geometry per line
+    WkbHex,
+    /// Raw WKB bytes with no separator between geometries
+    WkbRaw,
+    /// Any vector dataset GDAL can read, like a GeoPackage, or a zipped Shapefile
+    #[cfg(feature = "gdal")]
+    Ogr,
+}
+
+impl std::fmt::Display for GeometryInputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            // important: Should match clap::ValueEnum format
+            GeometryInputFormat::Wkt => write!(f, "wkt"),
+            GeometryInputFormat::WkbHex => write!(f, "wkb-hex"),
+            GeometryInputFormat::WkbRaw => write!(f, "wkb-raw"),
+            #[cfg(feature = "gdal")]
+            GeometryInputFormat::Ogr => write!(f, "ogr"),
+        }
+    }
+}
+
+impl From<GeometryFormat> for GeometryInputFormat {
+    fn from(format: GeometryFormat) -> GeometryInputFormat {
+        match format {
+            GeometryFormat::Wkt => GeometryInputFormat::Wkt,
+            GeometryFormat::WkbHex => GeometryInputFormat::WkbHex,
+            GeometryFormat::WkbRaw => GeometryInputFormat::WkbRaw,
+        }
+    }
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub enum SvgStyle {
     PointRadius(f64),
     Stroke(String),
     StrokeWidth(f64),
     StrokeDasharray(String),
+    StrokeDashoffset(f64),
     Fill(String),
+    /// Unlike the other styles, a label only applies to the one geometry after it
+    Label(String),
+}
+
+impl SvgStyle {
+    /// An example of each style directive, in the order they should be documented
+    pub fn examples() -> Vec<SvgStyle> {
+        vec![
+            SvgStyle::PointRadius(2.0),
+            SvgStyle::Stroke("red".into()),
+            SvgStyle::StrokeWidth(0.5),
+            SvgStyle::StrokeDasharray("5 2".into()),
+            SvgStyle::StrokeDashoffset(1.5),
+            SvgStyle::Fill("blue".into()),
+            SvgStyle::Label("A".into()),
+        ]
+    }
+
+    /// What the style directive applies to the geometries after it
+    pub fn description(&self) -> &'static str {
+        match self {
+            SvgStyle::PointRadius(_) => "The radius of the circle drawn for each POINT",
+            SvgStyle::Stroke(_) => "The SVG stroke color",
+            SvgStyle::StrokeWidth(_) => "The SVG stroke width",
+            SvgStyle::StrokeDasharray(_) => {
+                "The SVG stroke dash pattern. Use STROKEDASHARRAY(NONE) for a solid stroke"
+            }
+            SvgStyle::StrokeDashoffset(_) => "How far into the stroke dash pattern to start",
+            SvgStyle::Fill(_) => {
+                "The SVG fill color for polygons, or a url(#id) reference to a --gradient. Use FILL(none) for no fill"
+            }
+            SvgStyle::Label(_) => {
+                "A text label drawn at the centroid of the next geometry only, in the stroke color"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for SvgStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // important: Should round trip through SvgStyle::try_from
+        match self {
+            SvgStyle::PointRadius(r) => write!(f, "POINTRADIUS({r})"),
+            SvgStyle::Stroke(s) => write!(f, "STROKE({s})"),
+            SvgStyle::StrokeWidth(w) => write!(f, "STROKEWIDTH({w})"),
+            SvgStyle::StrokeDasharray(d) => write!(f, "STROKEDASHARRAY({d})"),
+            SvgStyle::StrokeDashoffset(o) => write!(f, "STROKEDASHOFFSET({o})"),
+            SvgStyle::Fill(c) => write!(f, "FILL({c})"),
+            SvgStyle::Label(l) => write!(f, "LABEL({l})"),
+        }
+    }
+}
+
+fn wkt_inner<'a>(prefix: &str, s: &'a str) -> &'a str {
+    match s.get(..prefix.len()) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => {
+            let s = s[prefix.len()..].trim();
+            // Only strip the outermost parentheses, so that FILL(url(#id)) keeps its url(#id)
+            let s = s.strip_prefix('(').unwrap_or(s);
+            let s = s.strip_suffix(')').unwrap_or(s);
+            s.trim()
+        }
+        _ => "",
+    }
 }
 
-fn wkt_inner<'a>(prefix: &'a str, s: &'a str) -> &'a str {
-    if let Some(s) = s.strip_prefix(prefix) {
-        let s = s.trim();
-        let s = s.trim_start_matches('(');
-        let s = s.trim_end_matches(')');
-        s
+/// Colors are case insensitive, but the ids in `url(#id)` references to gradients aren't
+fn paint(inner: &str) -> String {
+    if inner
+        .get(..4)
+        .is_some_and(|head| head.eq_ignore_ascii_case("url("))
+    {
+        format!("url({}", &inner[4..])
     } else {
-        ""
+        inner.to_lowercase()
     }
 }
 
@@ -59,8 +166,9 @@ impl TryFrom<&str> for SvgStyle {
 
     // This isn't a very good parser (there's lots of edge cases it doesn't handle) but for now it
     // doesn't have to be!
-    fn try_from(s: &str) -> Result<Self, Self::Error> {
-        let s = s.to_uppercase();
+    fn try_from(original: &str) -> Result<Self, Self::Error> {
+        let original = original.trim();
+        let s = original.to_uppercase();
 
         if s.starts_with("POINTRADIUS") {
             let inner = wkt_inner("POINTRADIUS", &s);
@@ -76,15 +184,26 @@ impl TryFrom<&str> for SvgStyle {
                 Err(_) => return Err(format!("Failed to parse stroke width from '{inner}'")),
             };
             return Ok(SvgStyle::StrokeWidth(width));
+        } else if s.starts_with("STROKEDASHOFFSET") {
+            let inner = wkt_inner("STROKEDASHOFFSET", &s);
+            let offset = match f64::from_str(inner) {
+                Ok(offset) => offset,
+                Err(_) => return Err(format!("Failed to parse stroke dash offset from '{inner}'")),
+            };
+            return Ok(SvgStyle::StrokeDashoffset(offset));
         } else if s.starts_with("STROKEDASHARRAY") {
             let inner = wkt_inner("STROKEDASHARRAY", &s);
             return Ok(SvgStyle::StrokeDasharray(inner.into()));
         } else if s.starts_with("STROKE") {
-            let inner = wkt_inner("STROKE", &s);
-            return Ok(SvgStyle::Stroke(inner.to_lowercase()));
+            let inner = wkt_inner("STROKE", original);
+            return Ok(SvgStyle::Stroke(paint(inner)));
         } else if s.starts_with("FILL") {
-            let inner = wkt_inner("FILL", &s);
-            return Ok(SvgStyle::Fill(inner.to_lowercase()));
+            let inner = wkt_inner("FILL", original);
+            return Ok(SvgStyle::Fill(paint(inner)));
+        } else if s.starts_with("LABEL") {
+            // Labels are case sensitive
+            let inner = wkt_inner("LABEL", original);
+            return Ok(SvgStyle::Label(inner.into()));
         }
 
         Err(format!("Failed to parse SVG style from '{s}'"))
@@ -156,15 +275,17 @@ where
 
 pub fn read_geometries<R>(
     reader: R,
-    format: &GeometryFormat,
+    format: &GeometryInputFormat,
 ) -> Box<dyn Iterator<Item = Geometry<f64>>>
 where
     R: Read + 'static,
 {
     match format {
-        GeometryFormat::Wkt => Box::new(read_wkt_geometries(reader)),
-        GeometryFormat::WkbHex => Box::new(read_wkbhex_geometries(reader)),
-        GeometryFormat::WkbRaw => Box::new(read_wkbraw_geometries(reader)),
+        GeometryInputFormat::Wkt => Box::new(read_wkt_geometries(reader)),
+        GeometryInputFormat::WkbHex => Box::new(read_wkbhex_geometries(reader)),
+        GeometryInputFormat::WkbRaw => Box::new(read_wkbraw_geometries(reader)),
+        #[cfg(feature = "gdal")]
+        GeometryInputFormat::Ogr => Box::new(super::read_ogr_geometries(reader)),
     }
 }
 
@@ -185,15 +306,32 @@ where
     R: Read,
 {
     lines: Lines<BufReader<R>>,
+    dropped_z: usize,
 }
 
 pub struct WktGeometriesAndStyles<R>
+where
+    R: Read,
+{
+    lines: Lines<BufReader<R>>,
+    dropped_z: usize,
+}
+
+pub struct WktGeometriesStylesAndZ<R>
 where
     R: Read,
 {
     lines: Lines<BufReader<R>>,
 }
 
+pub struct LabeledWktGeometries<R>
+where
+    R: Read,
+{
+    lines: Lines<BufReader<R>>,
+    dropped_z: usize,
+}
+
 pub struct WkbHexGeometries<R>
 where
     R: Read,
@@ -208,6 +346,139 @@ where
     reader: BufReader<R>,
 }
 
+/// The prefix of the optional coordinate reference system header
+///
+/// The header is metadata for downstream GIS tools; it's skipped when reading WKT, and does not
+/// affect any geometry math.
+const CRS_HEADER_PREFIX: &str = "# CRS:";
+
+/// Parse the coordinate reference system (like `EPSG:4326`) from a `# CRS: EPSG:4326` header line
+pub fn parse_crs_header(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix(CRS_HEADER_PREFIX)
+        .map(|crs| crs.trim())
+}
+
+/// Write a `# CRS: <crs>` header line
+///
+/// Only the WKT format supports the header, because the WKB formats have nowhere to put comments.
+pub fn write_crs_header<W>(mut writer: W, crs: &str, format: GeometryFormat)
+where
+    W: Write,
+{
+    match format {
+        GeometryFormat::Wkt => {
+            writeln!(writer, "{CRS_HEADER_PREFIX} {crs}").expect("Writing failed");
+        }
+        _ => warn!("Can't write CRS header for {format} output"),
+    }
+}
+
+impl<R: Read> WktGeometries<R> {
+    /// The number of geometries read so far whose Z coordinates were dropped
+    pub fn dropped_z(&self) -> usize {
+        self.dropped_z
+    }
+}
+
+impl<R: Read> WktGeometriesAndStyles<R> {
+    /// The number of geometries read so far whose Z coordinates were dropped
+    pub fn dropped_z(&self) -> usize {
+        self.dropped_z
+    }
+}
+
+impl<R: Read> LabeledWktGeometries<R> {
+    /// The number of geometries read so far whose Z coordinates were dropped
+    pub fn dropped_z(&self) -> usize {
+        self.dropped_z
+    }
+}
+
+/// Count the geometry if it has Z coordinates that are about to be dropped
+///
+/// geo geometries are 2D, so parsing a 3D WKT geometry silently discards its Z coordinates. Warn on
+/// the first one in the stream, so that accidentally mixing 2D and 3D inputs isn't silent.
+fn check_dropped_z(line: &str, has_z: bool, dropped_z: &mut usize) {
+    if !has_z {
+        return;
+    }
+    if *dropped_z == 0 {
+        warn!("Dropping Z coordinates from '{line}' and any further 3D geometries");
+    } else {
+        log::debug!("Dropping Z coordinates from '{line}'");
+    }
+    *dropped_z += 1;
+}
+
+/// Remove the bare EMPTY members of multi-geometries that the wkt crate fails to parse
+///
+/// The wkt crate parses `LINESTRING EMPTY`, but not the untagged EMPTY member in
+/// `MULTIPOINT((1 2), EMPTY)`, and it parses `MULTILINESTRING((0 0, 1 1), EMPTY)` into a
+/// MultiLineString with an empty LineString that can't be written back out as valid WKT. Empty
+/// members don't contribute any coordinates, so drop them, and replace a member list left with
+/// nothing in it by EMPTY.
+fn strip_empty_members(wkt: &str) -> Cow<'_, str> {
+    if !wkt.to_ascii_uppercase().contains("EMPTY") {
+        return Cow::Borrowed(wkt);
+    }
+
+    let last_non_space = |s: &str| s.trim_end().chars().next_back();
+    let mut stripped = String::with_capacity(wkt.len());
+    let mut skip_comma = false;
+    let mut rest = wkt;
+    while let Some(c) = rest.chars().next() {
+        let is_empty_member = rest
+            .get(..5)
+            .is_some_and(|word| word.eq_ignore_ascii_case("EMPTY"))
+            && !rest[5..].starts_with(|c: char| c.is_ascii_alphanumeric())
+            && matches!(last_non_space(&stripped), Some('(') | Some(','));
+        if is_empty_member {
+            rest = &rest[5..];
+            if last_non_space(&stripped) == Some(',') {
+                stripped.truncate(stripped.trim_end().len() - 1);
+            } else {
+                skip_comma = true;
+            }
+            continue;
+        }
+
+        rest = &rest[c.len_utf8()..];
+        match c {
+            ',' if skip_comma => skip_comma = false,
+            ')' if last_non_space(&stripped) == Some('(') => {
+                skip_comma = false;
+                stripped.truncate(stripped.trim_end().len() - 1);
+                stripped.push_str(" EMPTY");
+            }
+            c => {
+                if !c.is_whitespace() {
+                    skip_comma = false;
+                }
+                stripped.push(c);
+            }
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+fn parse_wkt(line: &str) -> Result<Wkt<f64>, &'static str> {
+    Wkt::from_str(&strip_empty_members(line))
+}
+
+fn next_non_header_line<B: BufRead>(lines: &mut Lines<B>) -> Option<std::io::Result<String>> {
+    for line in lines.by_ref() {
+        if let Ok(l) = &line {
+            if let Some(crs) = parse_crs_header(l) {
+                log::debug!("Skipping CRS header '{crs}'");
+                continue;
+            }
+        }
+        return Some(line);
+    }
+    None
+}
+
 impl<R> Iterator for WktGeometries<R>
 where
     R: Read,
@@ -215,20 +486,12 @@ where
     type Item = Geometry<f64>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.lines.next() {
-            Some(Ok(line)) => match Wkt::<f64>::from_str(line.as_str()) {
-                Ok(geometry) => match geometry.try_into() {
-                    Ok(geometry) => Some(geometry),
-                    Err(e) => {
-                        warn!("Failed to convert '{}' to geo geometry: {:?}", line, e);
-                        None
-                    }
-                },
-                Err(e) => {
-                    warn!("Failed to parse '{}' as WKT: {:?}", line, e);
-                    None
-                }
-            },
+        match next_non_header_line(&mut self.lines) {
+            Some(Ok(line)) => {
+                let (geometry, has_z) = parse_wkt_geometry(&line, has_z)?;
+                check_dropped_z(&line, has_z, &mut self.dropped_z);
+                Some(geometry)
+            }
             Some(Err(e)) => {
                 warn!("Failed to read line: {:?}", e);
                 None
@@ -245,25 +508,34 @@ where
     type Item = GeometryAndStyle<f64>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.lines.next() {
-            Some(Ok(line)) => match Wkt::<f64>::from_str(line.as_str()) {
-                Ok(geometry) => match geometry.try_into() {
-                    Ok(geometry) => Some(geometry),
-                    Err(e) => {
-                        warn!("Failed to convert '{}' to geo geometry: {:?}", line, e);
-                        None
-                    }
-                },
-                Err(e) => match SvgStyle::try_from(line.as_str()) {
-                    Ok(style) => Some(GeometryAndStyle::Style(style)),
-                    Err(ee) => {
-                        warn!(
-                            "Failed to parse '{}' as WKT: {:?} and as SVG STYLE: {:?}",
-                            line, e, ee
-                        );
-                        None
-                    }
-                },
+        match next_non_header_line(&mut self.lines) {
+            Some(Ok(line)) => match parse_geometry_or_style(&line, has_z)? {
+                (GeometryAndStyle::Style(style), _) => Some(GeometryAndStyle::Style(style)),
+                (geometry, has_z) => {
+                    check_dropped_z(&line, has_z.unwrap_or(false), &mut self.dropped_z);
+                    Some(geometry)
+                }
+            },
+            Some(Err(e)) => {
+                warn!("Failed to read line: {:?}", e);
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+impl<R> Iterator for WktGeometriesStylesAndZ<R>
+where
+    R: Read,
+{
+    type Item = (GeometryAndStyle<f64>, Option<f64>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match next_non_header_line(&mut self.lines) {
+            Some(Ok(line)) => match parse_geometry_or_style(&line, mean_z)? {
+                (GeometryAndStyle::Style(style), _) => Some((GeometryAndStyle::Style(style), None)),
+                (geometry, z) => Some((geometry, z.flatten())),
             },
             Some(Err(e)) => {
                 warn!("Failed to read line: {:?}", e);
@@ -274,6 +546,139 @@ where
     }
 }
 
+impl<R> Iterator for LabeledWktGeometries<R>
+where
+    R: Read,
+{
+    type Item = (Option<String>, Geometry<f64>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match next_non_header_line(&mut self.lines) {
+            Some(Ok(line)) => {
+                let (label, wkt) = match line.split_once('\t') {
+                    Some((label, wkt)) => (Some(label.trim().to_string()), wkt),
+                    None => (None, line.as_str()),
+                };
+                let (geometry, has_z) = parse_wkt_geometry(wkt, has_z)?;
+                check_dropped_z(wkt, has_z, &mut self.dropped_z);
+                Some((label, geometry))
+            }
+            Some(Err(e)) => {
+                warn!("Failed to read line: {:?}", e);
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+/// Parse the WKT geometry, along with whatever `inspect` measures on the parsed WKT before it's
+/// converted into a 2D geo geometry
+fn parse_wkt_geometry<T>(
+    line: &str,
+    inspect: impl FnOnce(&Wkt<f64>) -> T,
+) -> Option<(Geometry<f64>, T)> {
+    match parse_wkt(line) {
+        Ok(wkt) => {
+            let inspected = inspect(&wkt);
+            match wkt.try_into() {
+                Ok(geometry) => Some((geometry, inspected)),
+                Err(e) => {
+                    warn!("Failed to convert '{}' to geo geometry: {:?}", line, e);
+                    None
+                }
+            }
+        }
+        Err(e) => {
+            warn!("Failed to parse '{}' as WKT: {:?}", line, e);
+            None
+        }
+    }
+}
+
+/// Like [parse_wkt_geometry], but falls back on parsing an SVG STYLE, which isn't inspected
+fn parse_geometry_or_style<T>(
+    line: &str,
+    inspect: impl FnOnce(&Wkt<f64>) -> T,
+) -> Option<(GeometryAndStyle<f64>, Option<T>)> {
+    match parse_wkt(line) {
+        Ok(wkt) => {
+            let inspected = inspect(&wkt);
+            match wkt.try_into() {
+                Ok(geometry) => Some((geometry, Some(inspected))),
+                Err(e) => {
+                    warn!("Failed to convert '{}' to geo geometry: {:?}", line, e);
+                    None
+                }
+            }
+        }
+        Err(e) => match SvgStyle::try_from(line) {
+            Ok(style) => Some((GeometryAndStyle::Style(style), None)),
+            Err(ee) => {
+                warn!(
+                    "Failed to parse '{}' as WKT: {:?} and as SVG STYLE: {:?}",
+                    line, e, ee
+                );
+                None
+            }
+        },
+    }
+}
+
+/// Whether the parsed WKT geometry has any Z coordinates
+///
+/// Untagged 3D coordinates like `POINT(1 2 3)` are parsed as XYZ, and `POINT M(1 2 3)` as XYM.
+fn has_z(wkt: &Wkt<f64>) -> bool {
+    let line_has_z = |l: &wkt::types::LineString<f64>| l.0.iter().any(|c| c.z.is_some());
+    let polygon_has_z = |p: &wkt::types::Polygon<f64>| p.0.iter().any(line_has_z);
+    match wkt {
+        Wkt::Point(p) => p.0.as_ref().is_some_and(|c| c.z.is_some()),
+        Wkt::LineString(l) => line_has_z(l),
+        Wkt::Polygon(p) => polygon_has_z(p),
+        Wkt::MultiPoint(m) => {
+            m.0.iter()
+                .any(|p| p.0.as_ref().is_some_and(|c| c.z.is_some()))
+        }
+        Wkt::MultiLineString(m) => m.0.iter().any(line_has_z),
+        Wkt::MultiPolygon(m) => m.0.iter().any(polygon_has_z),
+        Wkt::GeometryCollection(g) => g.0.iter().any(has_z),
+    }
+}
+
+/// The mean Z coordinate of the parsed WKT geometry, if it has any Z coordinates
+///
+/// The geo geometries are 2D, so Z coordinates are dropped when converting from WKT. Untagged 3D
+/// coordinates like `POINT(1 2 3)` are parsed as XYZ, but the third ordinate of `POINT M(1 2 3)`
+/// isn't a Z coordinate.
+fn mean_z(wkt: &Wkt<f64>) -> Option<f64> {
+    fn sum_z(wkt: &Wkt<f64>, sum: &mut f64, count: &mut usize) {
+        let mut add = |coords: &mut dyn Iterator<Item = &wkt::types::Coord<f64>>| {
+            for z in coords.filter_map(|c| c.z) {
+                *sum += z;
+                *count += 1;
+            }
+        };
+        match wkt {
+            Wkt::Point(p) => add(&mut p.0.iter()),
+            Wkt::LineString(l) => add(&mut l.0.iter()),
+            Wkt::Polygon(p) => add(&mut p.0.iter().flat_map(|l| &l.0)),
+            Wkt::MultiPoint(m) => add(&mut m.0.iter().filter_map(|p| p.0.as_ref())),
+            Wkt::MultiLineString(m) => add(&mut m.0.iter().flat_map(|l| &l.0)),
+            Wkt::MultiPolygon(m) => add(&mut m.0.iter().flat_map(|p| &p.0).flat_map(|l| &l.0)),
+            Wkt::GeometryCollection(g) => {
+                for wkt in &g.0 {
+                    sum_z(wkt, sum, count);
+                }
+            }
+        }
+    }
+
+    let mut sum = 0.0;
+    let mut count = 0;
+    sum_z(wkt, &mut sum, &mut count);
+    (count > 0).then(|| sum / count as f64)
+}
+
 impl<R> Iterator for WkbRawGeometries<R>
 where
     R: Read,
@@ -349,9 +754,108 @@ where
     WktGeometries {
         // TODO: Is there a nice way to implement whitespace-separated geometries?
         lines: BufReader::new(reader).lines(),
+        dropped_z: 0,
     }
 }
 
+/// Read the polygons from the given WKT file, e.g., to clip to
+///
+/// POLYGONs, MULTIPOLYGONs, RECTs, and TRIANGLEs are collected into a single MULTIPOLYGON, and any
+/// other geometries are skipped with a warning. It's an error for the file to have no polygons.
+pub fn read_polygons(path: &Path) -> Result<MultiPolygon, String> {
+    let reader = get_input_reader(&Some(path.to_path_buf()))?;
+    let mut polygons = Vec::new();
+    for geometry in read_wkt_geometries(reader) {
+        match geometry {
+            Geometry::Polygon(p) => polygons.push(p),
+            Geometry::MultiPolygon(m) => polygons.extend(m),
+            Geometry::Rect(r) => polygons.push(r.to_polygon()),
+            Geometry::Triangle(t) => polygons.push(t.to_polygon()),
+            other => warn!("Skipping non-polygon in '{}': {other:?}", path.display()),
+        }
+    }
+    if polygons.is_empty() {
+        return Err(format!("No polygons in '{}'", path.display()));
+    }
+    Ok(MultiPolygon::new(polygons))
+}
+
+/// Like [read_wkt_geometries], but each line may start with an `id<TAB>` label
+///
+/// The label can be any string without a tab, like the node IDs in TGF. Lines without a tab have no
+/// label. Use [write_labeled_wkt_geometries] to write the labels back out.
+pub fn read_labeled_wkt_geometries<R>(reader: R) -> LabeledWktGeometries<R>
+where
+    R: Read,
+{
+    LabeledWktGeometries {
+        lines: BufReader::new(reader).lines(),
+        dropped_z: 0,
+    }
+}
+
+/// Read all of the WKT geometries from the given reader, parsing the lines in parallel
+///
+/// Returns the same geometries in the same order as [read_wkt_geometries], including stopping at
+/// the first line that fails to parse, but has to read the whole input before parsing any of it.
+/// Only worth it for large inputs that would be collected anyways.
+#[cfg(feature = "parallel")]
+pub fn read_wkt_geometries_parallel<R>(reader: R) -> Vec<Geometry<f64>>
+where
+    R: Read,
+{
+    parse_lines_parallel(reader, |line| parse_wkt_geometry(line, has_z))
+}
+
+/// Like [read_wkt_geometries_parallel], but for [read_wkt_geometries_and_styles]
+#[cfg(feature = "parallel")]
+pub fn read_wkt_geometries_and_styles_parallel<R>(reader: R) -> Vec<GeometryAndStyle<f64>>
+where
+    R: Read,
+{
+    parse_lines_parallel(reader, |line| {
+        parse_geometry_or_style(line, has_z).map(|(g, has_z)| (g, has_z.unwrap_or(false)))
+    })
+}
+
+/// Parse each line of the input in parallel, stopping at the first line that fails to parse
+///
+/// The parser also returns whether the line had any Z coordinates that were dropped.
+#[cfg(feature = "parallel")]
+fn parse_lines_parallel<R, T, F>(reader: R, parse: F) -> Vec<T>
+where
+    R: Read,
+    T: Send,
+    F: Fn(&str) -> Option<(T, bool)> + Sync,
+{
+    use rayon::prelude::*;
+
+    let mut lines = BufReader::new(reader).lines();
+    let mut buffered = Vec::new();
+    while let Some(line) = next_non_header_line(&mut lines) {
+        match line {
+            Ok(line) => buffered.push(line),
+            Err(e) => {
+                warn!("Failed to read line: {:?}", e);
+                break;
+            }
+        }
+    }
+
+    let parsed: Vec<_> = buffered.par_iter().map(|line| parse(line)).collect();
+
+    let mut dropped_z = 0;
+    parsed
+        .into_iter()
+        .zip(&buffered)
+        .map_while(|(parsed, line)| {
+            let (parsed, has_z) = parsed?;
+            check_dropped_z(line, has_z, &mut dropped_z);
+            Some(parsed)
+        })
+        .collect()
+}
+
 fn read_wkbhex_geometries<R>(reader: R) -> WkbHexGeometries<R>
 where
     R: Read,
@@ -361,7 +865,7 @@ where
     }
 }
 
-fn read_wkbraw_geometries<R>(reader: R) -> WkbRawGeometries<R>
+pub(super) fn read_wkbraw_geometries<R>(reader: R) -> WkbRawGeometries<R>
 where
     R: Read,
 {
@@ -370,17 +874,189 @@ where
     }
 }
 
-/// Write the given geometries with the given Writer in WKT format
+/// Write the given geometries as WKT, one geometry per line
 ///
-/// Each geometry will be written on its own line.
-pub fn write_wkt_geometries<W, G>(mut writer: W, geometries: G)
+/// Coordinates are written in their shortest round-trip decimal form, and never in scientific
+/// notation.
+pub fn write_wkt_geometries<W, G>(writer: W, geometries: G)
 where
     W: Write,
     G: IntoIterator<Item = Geometry<f64>>,
+{
+    write_wkt_geometries_with_precision(writer, geometries, None);
+}
+
+/// Write the given geometries as WKT, rounding each coordinate to `precision` decimal places
+///
+/// Trailing zeros are trimmed, so that `1.50` is written as `1.5`, and negative zero is written
+/// as `0`. A precision of `None` writes the shortest decimal that round-trips back to the same
+/// `f64`. Either way, coordinates are never written in scientific notation.
+pub fn write_wkt_geometries_with_precision<W, G>(
+    mut writer: W,
+    geometries: G,
+    precision: Option<usize>,
+) where
+    W: Write,
+    G: IntoIterator<Item = Geometry<f64>>,
 {
     for geometry in geometries {
-        let wkt_geom = geometry.to_wkt();
-        writeln!(writer, "{}", wkt_geom).expect("Writing failed");
+        let mut wkt = String::new();
+        format_wkt_geometry(&mut wkt, &geometry, precision);
+        writeln!(writer, "{wkt}").expect("Writing failed");
+    }
+}
+
+/// Write the given geometries as WKT, prefixing each labeled geometry with its `id<TAB>` label
+pub fn write_labeled_wkt_geometries<W, G>(mut writer: W, geometries: G)
+where
+    W: Write,
+    G: IntoIterator<Item = (Option<String>, Geometry<f64>)>,
+{
+    for (label, geometry) in geometries {
+        let mut wkt = String::new();
+        if let Some(label) = label {
+            wkt.push_str(&label);
+            wkt.push('\t');
+        }
+        format_wkt_geometry(&mut wkt, &geometry, None);
+        writeln!(writer, "{wkt}").expect("Writing failed");
+    }
+}
+
+fn format_wkt_number(buf: &mut String, value: f64, precision: Option<usize>) {
+    // f64's Display never uses scientific notation, unlike LowerExp or other languages' defaults
+    let mut formatted = match precision {
+        Some(precision) => format!("{value:.precision$}"),
+        None => format!("{value}"),
+    };
+    if formatted.contains('.') {
+        let trimmed = formatted.trim_end_matches('0').trim_end_matches('.').len();
+        formatted.truncate(trimmed);
+    }
+    if formatted == "-0" {
+        formatted = String::from("0");
+    }
+    buf.push_str(&formatted);
+}
+
+fn format_wkt_coord(buf: &mut String, coord: &geo::Coord, precision: Option<usize>) {
+    format_wkt_number(buf, coord.x, precision);
+    buf.push(' ');
+    format_wkt_number(buf, coord.y, precision);
+}
+
+/// Format the coordinates, separated by commas, without any surrounding parentheses
+fn format_wkt_coords<'a>(
+    buf: &mut String,
+    coords: impl IntoIterator<Item = &'a geo::Coord>,
+    precision: Option<usize>,
+) {
+    for (i, coord) in coords.into_iter().enumerate() {
+        if i > 0 {
+            buf.push(',');
+        }
+        format_wkt_coord(buf, coord, precision);
+    }
+}
+
+/// Format the polygon's rings, each wrapped in parentheses
+fn format_wkt_rings(buf: &mut String, polygon: &Polygon, precision: Option<usize>) {
+    for (i, ring) in std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .enumerate()
+    {
+        if i > 0 {
+            buf.push(',');
+        }
+        buf.push('(');
+        format_wkt_coords(buf, &ring.0, precision);
+        buf.push(')');
+    }
+}
+
+/// Format the geometry the same way as [wkt::ToWkt], but with control over the coordinate formatting
+fn format_wkt_geometry(buf: &mut String, geometry: &Geometry, precision: Option<usize>) {
+    match geometry {
+        Geometry::Point(p) => {
+            buf.push_str("POINT(");
+            format_wkt_coord(buf, &p.0, precision);
+            buf.push(')');
+        }
+        Geometry::Line(l) => {
+            buf.push_str("LINESTRING(");
+            format_wkt_coords(buf, [&l.start, &l.end], precision);
+            buf.push(')');
+        }
+        Geometry::LineString(l) if l.0.is_empty() => buf.push_str("LINESTRING EMPTY"),
+        Geometry::LineString(l) => {
+            buf.push_str("LINESTRING(");
+            format_wkt_coords(buf, &l.0, precision);
+            buf.push(')');
+        }
+        Geometry::Polygon(p) if p.exterior().0.is_empty() => buf.push_str("POLYGON EMPTY"),
+        Geometry::Polygon(p) => {
+            buf.push_str("POLYGON(");
+            format_wkt_rings(buf, p, precision);
+            buf.push(')');
+        }
+        Geometry::MultiPoint(m) if m.0.is_empty() => buf.push_str("MULTIPOINT EMPTY"),
+        Geometry::MultiPoint(m) => {
+            buf.push_str("MULTIPOINT(");
+            for (i, p) in m.0.iter().enumerate() {
+                if i > 0 {
+                    buf.push(',');
+                }
+                buf.push('(');
+                format_wkt_coord(buf, &p.0, precision);
+                buf.push(')');
+            }
+            buf.push(')');
+        }
+        Geometry::MultiLineString(m) if m.0.is_empty() => buf.push_str("MULTILINESTRING EMPTY"),
+        Geometry::MultiLineString(m) => {
+            buf.push_str("MULTILINESTRING(");
+            for (i, l) in m.0.iter().enumerate() {
+                if i > 0 {
+                    buf.push(',');
+                }
+                buf.push('(');
+                format_wkt_coords(buf, &l.0, precision);
+                buf.push(')');
+            }
+            buf.push(')');
+        }
+        Geometry::MultiPolygon(m) if m.0.is_empty() => buf.push_str("MULTIPOLYGON EMPTY"),
+        Geometry::MultiPolygon(m) => {
+            buf.push_str("MULTIPOLYGON(");
+            for (i, p) in m.0.iter().enumerate() {
+                if i > 0 {
+                    buf.push(',');
+                }
+                buf.push('(');
+                format_wkt_rings(buf, p, precision);
+                buf.push(')');
+            }
+            buf.push(')');
+        }
+        Geometry::GeometryCollection(c) if c.0.is_empty() => {
+            buf.push_str("GEOMETRYCOLLECTION EMPTY")
+        }
+        Geometry::GeometryCollection(c) => {
+            buf.push_str("GEOMETRYCOLLECTION(");
+            for (i, g) in c.0.iter().enumerate() {
+                if i > 0 {
+                    buf.push(',');
+                }
+                format_wkt_geometry(buf, g, precision);
+            }
+            buf.push(')');
+        }
+        Geometry::Rect(r) => {
+            format_wkt_geometry(buf, &Geometry::Polygon(r.to_polygon()), precision)
+        }
+        Geometry::Triangle(t) => {
+            format_wkt_geometry(buf, &Geometry::Polygon(t.to_polygon()), precision)
+        }
     }
 }
 
@@ -401,7 +1077,7 @@ where
     }
 }
 
-fn write_wkbraw_geometries<W, G>(mut writer: W, geometries: G)
+pub(super) fn write_wkbraw_geometries<W, G>(mut writer: W, geometries: G)
 where
     W: Write,
     G: IntoIterator<Item = Geometry<f64>>,
@@ -420,12 +1096,26 @@ where
 {
     WktGeometriesAndStyles {
         lines: BufReader::new(reader).lines(),
+        dropped_z: 0,
+    }
+}
+
+/// Like [read_wkt_geometries_and_styles], but also yield the mean Z coordinate of each geometry
+///
+/// The Z coordinate is the mean of the geometry's Z coordinates, or `None` if it has none. Styles
+/// never have a Z coordinate.
+pub fn read_wkt_geometries_styles_and_z<R>(reader: R) -> WktGeometriesStylesAndZ<R>
+where
+    R: Read,
+{
+    WktGeometriesStylesAndZ {
+        lines: BufReader::new(reader).lines(),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use geo::{Geometry, Point};
+    use geo::{BoundingRect, Geometry, Point};
 
     use super::*;
 
@@ -452,6 +1142,94 @@ mod tests {
         assert_eq!(geometries.next(), None);
     }
 
+    #[test]
+    fn test_empty_geometries() {
+        let input = b"POINT EMPTY
+LINESTRING EMPTY
+POLYGON EMPTY
+MULTIPOINT EMPTY
+MULTILINESTRING EMPTY
+MULTIPOLYGON EMPTY
+GEOMETRYCOLLECTION EMPTY
+GEOMETRYCOLLECTION(POINT EMPTY, POINT(1 2))";
+        let geometries: Vec<_> = read_wkt_geometries(&input[..]).collect();
+        assert_eq!(geometries.len(), 8);
+        for geometry in &geometries[..7] {
+            assert_eq!(geometry.bounding_rect(), None);
+        }
+
+        // geo doesn't have an empty Point, so POINT EMPTY is read as an empty MultiPoint
+        let mut buffer = Vec::new();
+        write_wkt_geometries(&mut buffer, geometries);
+        let output = String::from_utf8(buffer).unwrap();
+        let expected = "MULTIPOINT EMPTY
+LINESTRING EMPTY
+POLYGON EMPTY
+MULTIPOINT EMPTY
+MULTILINESTRING EMPTY
+MULTIPOLYGON EMPTY
+GEOMETRYCOLLECTION EMPTY
+GEOMETRYCOLLECTION(MULTIPOINT EMPTY,POINT(1 2))
+";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_empty_members() {
+        let input = b"MULTIPOINT((1 2), EMPTY)
+MULTIPOINT(EMPTY, (1 2), EMPTY, (3 4))
+MULTIPOINT(EMPTY)
+MULTILINESTRING((0 0, 1 1), EMPTY)
+MULTIPOLYGON(((0 0, 1 0, 1 1, 0 0)), EMPTY)
+multipolygon(empty, empty)
+POLYGON((0 0, 1 0, 1 1, 0 0), EMPTY)";
+        let geometries: Vec<_> = read_wkt_geometries(&input[..]).collect();
+        assert_eq!(geometries.len(), 7);
+
+        let mut buffer = Vec::new();
+        write_wkt_geometries(&mut buffer, geometries);
+        let output = String::from_utf8(buffer).unwrap();
+        let expected = "MULTIPOINT((1 2))
+MULTIPOINT((1 2),(3 4))
+MULTIPOINT EMPTY
+MULTILINESTRING((0 0,1 1))
+MULTIPOLYGON(((0 0,1 0,1 1,0 0)))
+MULTIPOLYGON EMPTY
+POLYGON((0 0,1 0,1 1,0 0))
+";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_parallel_matches_serial() {
+        let mut input = String::from("# CRS: EPSG:4326\n");
+        for i in 0..1000 {
+            input += &format!("LINESTRING Z({i} 0 1, {i} 1 2)\nPOINT({i} {i})\n");
+        }
+        input += "garbage\nPOINT(1 2)\n";
+
+        let serial: Vec<_> = read_wkt_geometries(input.as_bytes()).collect();
+        let parallel = read_wkt_geometries_parallel(input.as_bytes());
+        assert_eq!(serial.len(), 2000);
+        assert_eq!(parallel, serial);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_parallel_styles_match_serial() {
+        let mut input = String::new();
+        for i in 0..500 {
+            input += &format!("STROKE(red)\nPOINT({i} {i})\nSTROKEWIDTH({i})\n");
+        }
+        input += "garbage\nPOINT(1 2)\n";
+
+        let serial: Vec<_> = read_wkt_geometries_and_styles(input.as_bytes()).collect();
+        let parallel = read_wkt_geometries_and_styles_parallel(input.as_bytes());
+        assert_eq!(serial.len(), 1500);
+        assert_eq!(parallel, serial);
+    }
+
     #[test]
     fn test_nothing_but_garbage() {
         let input = b"garbage";
@@ -518,6 +1296,59 @@ mod tests {
         assert_eq!(wkbraw_geometries.next().unwrap(), expected2);
     }
 
+    #[test]
+    fn test_wkt_output_round_trips() {
+        let input = b"POINT(1 2)\nLINESTRING(0 0,1.5 -2)\nPOLYGON((0 0,1 0,1 1,0 0),(0.2 0.2,0.8 0.2,0.8 0.8,0.2 0.2))\nMULTIPOINT((1 2),(3 4))\nGEOMETRYCOLLECTION(POINT(1 2),MULTILINESTRING((0 0,1 1),(2 2,3 3)))\n";
+        let geometries = read_wkt_geometries(&input[..]);
+
+        let mut output_buffer = Vec::<u8>::new();
+        write_wkt_geometries(&mut output_buffer, geometries);
+
+        assert_eq!(output_buffer, input);
+    }
+
+    #[test]
+    fn test_labeled_wkt_round_trips() {
+        let input = b"1\tPOINT(1 2)\nLINESTRING(0 0,1 1)\nnode a\tPOINT(3 4)\n";
+        let geometries: Vec<_> = read_labeled_wkt_geometries(&input[..]).collect();
+        assert_eq!(
+            geometries,
+            [
+                (Some("1".into()), Geometry::Point(Point::new(1.0, 2.0))),
+                (
+                    None,
+                    Geometry::LineString(vec![(0.0, 0.0), (1.0, 1.0)].into())
+                ),
+                (Some("node a".into()), Geometry::Point(Point::new(3.0, 4.0))),
+            ]
+        );
+
+        let mut output_buffer = Vec::<u8>::new();
+        write_labeled_wkt_geometries(&mut output_buffer, geometries);
+        assert_eq!(output_buffer, input);
+    }
+
+    #[test]
+    fn test_wkt_output_no_scientific_notation() {
+        let geometries = [
+            Geometry::Point(Point::new(1e-7, -2.5e21)),
+            Geometry::Point(Point::new(-0.0, 0.1 + 0.2)),
+        ];
+
+        let mut output_buffer = Vec::<u8>::new();
+        write_wkt_geometries(&mut output_buffer, geometries.clone());
+        let output = String::from_utf8(output_buffer).unwrap();
+        assert_eq!(
+            output,
+            "POINT(0.0000001 -2500000000000000000000)\nPOINT(0 0.30000000000000004)\n"
+        );
+
+        let mut output_buffer = Vec::<u8>::new();
+        write_wkt_geometries_with_precision(&mut output_buffer, geometries, Some(3));
+        let output = String::from_utf8(output_buffer).unwrap();
+        assert_eq!(output, "POINT(0 -2500000000000000000000)\nPOINT(0 0.3)\n");
+    }
+
     #[test]
     fn test_wkbhex_output() {
         let input_wkbhex = b"0101000000000000000000F03F000000000000F03F\n010100000000000000000000400000000000000C40\n";
@@ -553,6 +1384,70 @@ mod tests {
         assert_eq!(point, Some(Geometry::Point(Point::new(1.0, 2.0))));
     }
 
+    #[test]
+    fn test_counts_dropped_z() {
+        let wkt = b"POINT(1 2)\nPOINT Z(1 2 3)\nLINESTRING Z(0 0 1, 1 1 1)\nPOINT M(1 2 3)\n\
+                    GEOMETRYCOLLECTION(POINT(0 0),POINT Z(0 0 1))\n";
+        let mut geometries = read_wkt_geometries(&wkt[..]);
+        assert_eq!(geometries.by_ref().count(), 5);
+        assert_eq!(geometries.dropped_z(), 3);
+
+        let wkt = b"STROKE(red)\nPOINT(1 2)\nPOINT Z(1 2 3)\n";
+        let mut geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        assert_eq!(geometries.by_ref().count(), 3);
+        assert_eq!(geometries.dropped_z(), 1);
+    }
+
+    #[test]
+    fn test_read_polygons() {
+        let path =
+            std::env::temp_dir().join(format!("generative-polygons-{}.wkt", std::process::id()));
+        std::fs::write(
+            &path,
+            "POINT(0 0)\nPOLYGON((0 0,1 0,1 1,0 0))\nMULTIPOLYGON(((2 2,3 2,3 3,2 2)),((4 4,5 4,5 5,4 4)))\n",
+        )
+        .unwrap();
+        let polygons = read_polygons(&path).unwrap();
+        assert_eq!(polygons.0.len(), 3);
+
+        std::fs::write(&path, "POINT(0 0)\n").unwrap();
+        assert!(read_polygons(&path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_mean_z_of_parsed_wkt() {
+        let mean = |wkt| mean_z(&parse_wkt(wkt).unwrap());
+        assert_eq!(mean("POINT(1 2)"), None);
+        assert_eq!(mean("POINT M(1 2 3)"), None);
+        assert_eq!(mean("POINT ZM(1 2 3 4)"), Some(3.0));
+        assert_eq!(
+            mean("MULTIPOLYGON Z(((0 0 1,1 0 1,1 1 4,0 0 1)))"),
+            Some(1.75)
+        );
+        assert_eq!(
+            mean("GEOMETRYCOLLECTION(POINT M(0 0 100),POINT Z(0 0 2))"),
+            Some(2.0)
+        );
+    }
+
+    #[test]
+    fn test_read_geometries_styles_and_z() {
+        let wkt = b"STROKE(red)\nPOINT Z(1 2 3)\nPOINT(1 2)\n";
+        let geometries: Vec<_> = read_wkt_geometries_styles_and_z(&wkt[..]).collect();
+        assert_eq!(
+            geometries,
+            [
+                (
+                    GeometryAndStyle::Style(SvgStyle::Stroke("red".into())),
+                    None
+                ),
+                (GeometryAndStyle::Point(Point::new(1.0, 2.0)), Some(3.0)),
+                (GeometryAndStyle::Point(Point::new(1.0, 2.0)), None),
+            ]
+        );
+    }
+
     #[test]
     fn test_geometry_and_style_point() {
         let wkt = b"POINT(1 1)";
@@ -620,6 +1515,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_style_stroke_dashoffset() {
+        let wkt = b"STROKEDASHOFFSET(2.5)";
+        let mut geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let style = geometries.next();
+        assert_eq!(
+            style,
+            Some(GeometryAndStyle::Style(SvgStyle::StrokeDashoffset(2.5)))
+        );
+    }
+
     #[test]
     fn test_style_fill() {
         let wkt = b"FILL(red)";
@@ -630,4 +1536,58 @@ mod tests {
             Some(GeometryAndStyle::Style(SvgStyle::Fill("red".into())))
         );
     }
+
+    #[test]
+    fn test_style_fill_gradient() {
+        let wkt = b"fill(URL(#Grad1))";
+        let mut geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let style = geometries.next();
+        assert_eq!(
+            style,
+            Some(GeometryAndStyle::Style(SvgStyle::Fill(
+                "url(#Grad1)".into()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_style_label() {
+        let wkt = b"label(Node (1))";
+        let mut geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let style = geometries.next();
+        assert_eq!(
+            style,
+            Some(GeometryAndStyle::Style(SvgStyle::Label("Node (1)".into())))
+        );
+    }
+
+    #[test]
+    fn test_style_examples_round_trip() {
+        for style in SvgStyle::examples() {
+            let parsed = SvgStyle::try_from(style.to_string().as_str());
+            assert_eq!(parsed, Ok(style));
+        }
+    }
+
+    #[test]
+    fn test_crs_header_round_trip() {
+        let mut buffer = Vec::<u8>::new();
+        write_crs_header(&mut buffer, "EPSG:4326", GeometryFormat::Wkt);
+        write_wkt_geometries(&mut buffer, [Geometry::Point(Point::new(1.0, 2.0))]);
+        assert_eq!(buffer, b"# CRS: EPSG:4326\nPOINT(1 2)\n");
+
+        let geometries: Vec<_> = read_wkt_geometries(&buffer[..]).collect();
+        assert_eq!(geometries, [Geometry::Point(Point::new(1.0, 2.0))]);
+
+        let geometries: Vec<_> = read_wkt_geometries_and_styles(&buffer[..]).collect();
+        assert_eq!(geometries, [GeometryAndStyle::Point(Point::new(1.0, 2.0))]);
+    }
+
+    #[test]
+    fn test_parse_crs_header() {
+        assert_eq!(parse_crs_header("# CRS: EPSG:4326"), Some("EPSG:4326"));
+        assert_eq!(parse_crs_header("  # CRS:EPSG:3857  "), Some("EPSG:3857"));
+        assert_eq!(parse_crs_header("# a comment"), None);
+        assert_eq!(parse_crs_header("POINT(1 2)"), None);
+    }
 }
@@ -0,0 +1,309 @@
+use geo::{Coord, Point};
+use petgraph::EdgeType;
+
+use crate::graph::GeometryGraph;
+
+/// Tunable parameters for [force_directed]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ForceDirectedParams {
+    /// The number of relaxation iterations to perform
+    pub iterations: usize,
+    /// The ideal distance between adjacent nodes
+    ///
+    /// Edges act as springs with this rest length, and all pairs of nodes repel each other with a
+    /// strength proportional to its square.
+    pub edge_length: f64,
+    /// The maximum distance a node may move in the first iteration
+    ///
+    /// The maximum move distance cools linearly to zero over the course of the iterations.
+    pub initial_temperature: f64,
+}
+
+impl Default for ForceDirectedParams {
+    fn default() -> Self {
+        Self {
+            iterations: 100,
+            edge_length: 1.0,
+            initial_temperature: 1.0,
+        }
+    }
+}
+
+/// Relax the positions of the graph's nodes using the Fruchterman-Reingold force-directed layout
+///
+/// Every pair of nodes repels each other, and every edge pulls its endpoints together. Edge
+/// directions are ignored. The layout is deterministic; nodes that start at the same location are
+/// pushed apart along a fixed direction derived from their indices.
+pub fn force_directed<D: EdgeType>(graph: &mut GeometryGraph<D>, params: &ForceDirectedParams) {
+    let num_nodes = graph.node_count();
+    if num_nodes < 2 || params.iterations == 0 {
+        return;
+    }
+
+    let k = params.edge_length;
+    let mut positions: Vec<Coord> = graph.node_weights().map(|p| p.0).collect();
+    let mut displacements = vec![Coord::zero(); num_nodes];
+
+    for iteration in 0..params.iterations {
+        displacements.fill(Coord::zero());
+
+        // Repulsive forces between every pair of nodes
+        for i in 0..num_nodes {
+            for j in (i + 1)..num_nodes {
+                let (direction, distance) = separation(positions[i], positions[j], i, j);
+                let force = k * k / distance;
+                displacements[i] = displacements[i] + direction * force;
+                displacements[j] = displacements[j] - direction * force;
+            }
+        }
+
+        // Attractive forces along each edge
+        for edge in graph.raw_edges() {
+            let i = edge.source().index();
+            let j = edge.target().index();
+            if i == j {
+                continue;
+            }
+            let (direction, distance) = separation(positions[i], positions[j], i, j);
+            let force = distance * distance / k;
+            displacements[i] = displacements[i] - direction * force;
+            displacements[j] = displacements[j] + direction * force;
+        }
+
+        // Limit each node's movement by the current temperature
+        let temperature =
+            params.initial_temperature * (1.0 - iteration as f64 / params.iterations as f64);
+        for (position, displacement) in positions.iter_mut().zip(&displacements) {
+            let length = displacement.x.hypot(displacement.y);
+            if length > 0.0 {
+                *position = *position + *displacement * (length.min(temperature) / length);
+            }
+        }
+    }
+
+    for (node, position) in graph.node_weights_mut().zip(positions) {
+        *node = Point(position);
+    }
+}
+
+/// Lay out the graph's nodes using the eigenvectors of its Laplacian
+///
+/// Each node is placed at its components of the eigenvectors with the second and third smallest
+/// eigenvalues, which minimize the total squared edge length without collapsing the nodes together.
+/// The input node positions are ignored, and the layout is scaled so that the average edge has
+/// unit length. Edge directions are ignored.
+///
+/// The layout is deterministic, but disconnected graphs collapse each component to a small number
+/// of points, so it works best with a connected graph.
+pub fn spectral<D: EdgeType>(graph: &GeometryGraph<D>) -> GeometryGraph<D> {
+    let mut result = graph.clone();
+    let num_nodes = graph.node_count();
+    // There's no third eigenvector to use, so lay the nodes out in a line instead
+    if num_nodes < 3 {
+        for (i, node) in result.node_weights_mut().enumerate() {
+            *node = Point::new(i as f64, 0.0);
+        }
+        return result;
+    }
+
+    let mut degrees = vec![0.0; num_nodes];
+    let mut edges = Vec::with_capacity(graph.edge_count());
+    for edge in graph.raw_edges() {
+        let i = edge.source().index();
+        let j = edge.target().index();
+        if i != j {
+            degrees[i] += 1.0;
+            degrees[j] += 1.0;
+            edges.push((i, j));
+        }
+    }
+
+    // The smallest eigenvectors of the Laplacian L are the largest eigenvectors of shift*I - L,
+    // which power iteration can find. By the Gershgorin circle theorem, the eigenvalues of L are
+    // at most twice the max degree, so this shift keeps shift*I - L positive definite.
+    let shift = 2.0 * degrees.iter().copied().fold(0.0, f64::max) + 1.0;
+    let multiply = |v: &[f64]| -> Vec<f64> {
+        let mut result: Vec<f64> = v
+            .iter()
+            .zip(&degrees)
+            .map(|(v, d)| (shift - d) * v)
+            .collect();
+        for (i, j) in &edges {
+            result[*i] += v[*j];
+            result[*j] += v[*i];
+        }
+        result
+    };
+
+    // Start from arbitrary, but deterministic, vectors
+    let mut xs: Vec<f64> = (0..num_nodes).map(|i| (i as f64 + 1.0).sin()).collect();
+    let mut ys: Vec<f64> = (0..num_nodes).map(|i| (i as f64 + 1.0).cos()).collect();
+    orthonormalize(&mut xs, &[]);
+    orthonormalize(&mut ys, &[&xs]);
+
+    const MAX_ITERATIONS: usize = 10_000;
+    const TOLERANCE: f64 = 1e-10;
+    for _ in 0..MAX_ITERATIONS {
+        // The smallest eigenvector of L is the constant vector, which orthonormalize() projects
+        // out so that the iteration converges to the second and third smallest instead
+        let mut next_xs = multiply(&xs);
+        let mut next_ys = multiply(&ys);
+        orthonormalize(&mut next_xs, &[]);
+        orthonormalize(&mut next_ys, &[&next_xs]);
+
+        let change =
+            |a: &[f64], b: &[f64]| -> f64 { a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum() };
+        let difference = change(&xs, &next_xs) + change(&ys, &next_ys);
+        xs = next_xs;
+        ys = next_ys;
+        if difference < TOLERANCE {
+            break;
+        }
+    }
+
+    let mut total_length = 0.0;
+    for (i, j) in &edges {
+        total_length += (xs[*i] - xs[*j]).hypot(ys[*i] - ys[*j]);
+    }
+    let scale = if total_length > 0.0 {
+        edges.len() as f64 / total_length
+    } else {
+        1.0
+    };
+
+    for (node, (x, y)) in result.node_weights_mut().zip(xs.into_iter().zip(ys)) {
+        *node = Point::new(x * scale, y * scale);
+    }
+    result
+}
+
+/// Make the vector orthogonal to the constant vector and the given unit vectors, and normalize it
+fn orthonormalize(v: &mut [f64], others: &[&[f64]]) {
+    let mean = v.iter().sum::<f64>() / v.len() as f64;
+    v.iter_mut().for_each(|v| *v -= mean);
+    for other in others {
+        let dot: f64 = v.iter().zip(other.iter()).map(|(a, b)| a * b).sum();
+        v.iter_mut()
+            .zip(other.iter())
+            .for_each(|(v, o)| *v -= dot * o);
+    }
+    let norm = v.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > f64::EPSILON {
+        v.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+/// Get the unit direction from `b` to `a`, and the distance between them
+///
+/// Coincident nodes get an arbitrary, but deterministic, direction and a small distance so that
+/// they can be pushed apart.
+fn separation(a: Coord, b: Coord, i: usize, j: usize) -> (Coord, f64) {
+    let delta = a - b;
+    let distance = delta.x.hypot(delta.y);
+    if distance > f64::EPSILON {
+        return (delta / distance, distance);
+    }
+
+    let angle = (i * 31 + j * 17) as f64;
+    (
+        Coord {
+            x: angle.cos(),
+            y: angle.sin(),
+        },
+        0.01,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::Undirected;
+
+    use super::*;
+
+    #[test]
+    fn test_layout_empty_graph() {
+        let mut graph = GeometryGraph::<Undirected>::default();
+        force_directed(&mut graph, &ForceDirectedParams::default());
+        assert_eq!(graph.node_count(), 0);
+    }
+
+    #[test]
+    fn test_layout_pair_converges_to_edge_length() {
+        let mut graph = GeometryGraph::<Undirected>::default();
+        let a = graph.add_node(Point::new(0.0, 0.0));
+        let b = graph.add_node(Point::new(5.0, 0.0));
+        graph.add_edge(a, b, ());
+
+        let params = ForceDirectedParams {
+            iterations: 200,
+            edge_length: 1.0,
+            initial_temperature: 1.0,
+        };
+        force_directed(&mut graph, &params);
+
+        let delta = graph[a].0 - graph[b].0;
+        let distance = delta.x.hypot(delta.y);
+        assert!((distance - 1.0).abs() < 0.05, "distance: {distance}");
+        // The nodes move symmetrically, so they shouldn't leave the x-axis
+        assert_eq!(graph[a].y(), 0.0);
+        assert_eq!(graph[b].y(), 0.0);
+    }
+
+    #[test]
+    fn test_layout_separates_coincident_nodes() {
+        let mut graph = GeometryGraph::<Undirected>::default();
+        let a = graph.add_node(Point::new(0.0, 0.0));
+        let b = graph.add_node(Point::new(0.0, 0.0));
+        let c = graph.add_node(Point::new(0.0, 0.0));
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+
+        force_directed(&mut graph, &ForceDirectedParams::default());
+
+        assert_ne!(graph[a], graph[b]);
+        assert_ne!(graph[b], graph[c]);
+        assert_ne!(graph[a], graph[c]);
+    }
+
+    #[test]
+    fn test_spectral_layout_cycle_is_regular_polygon() {
+        let mut graph = GeometryGraph::<Undirected>::default();
+        let nodes: Vec<_> = (0..6)
+            .map(|_| graph.add_node(Point::new(0.0, 0.0)))
+            .collect();
+        for i in 0..nodes.len() {
+            graph.add_edge(nodes[i], nodes[(i + 1) % nodes.len()], ());
+        }
+
+        let layout = spectral(&graph);
+
+        let radii: Vec<f64> = layout.node_weights().map(|p| p.x().hypot(p.y())).collect();
+        for radius in &radii {
+            assert!((radius - radii[0]).abs() < 1e-6, "radii: {radii:?}");
+        }
+        for edge in layout.raw_edges() {
+            let delta = layout[edge.source()].0 - layout[edge.target()].0;
+            let length = delta.x.hypot(delta.y);
+            assert!((length - 1.0).abs() < 1e-6, "length: {length}");
+        }
+    }
+
+    #[test]
+    fn test_spectral_layout_path_is_monotonic() {
+        let mut graph = GeometryGraph::<Undirected>::default();
+        let nodes: Vec<_> = (0..5)
+            .map(|_| graph.add_node(Point::new(0.0, 0.0)))
+            .collect();
+        for pair in nodes.windows(2) {
+            graph.add_edge(pair[0], pair[1], ());
+        }
+
+        let layout = spectral(&graph);
+
+        // The Fiedler vector of a path orders the nodes along the path
+        let xs: Vec<f64> = layout.node_weights().map(|p| p.x()).collect();
+        let increasing = xs.windows(2).all(|w| w[0] < w[1]);
+        let decreasing = xs.windows(2).all(|w| w[0] > w[1]);
+        assert!(increasing || decreasing, "xs: {xs:?}");
+    }
+}
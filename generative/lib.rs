@@ -4,11 +4,18 @@ pub mod dla;
 pub mod flatten;
 mod geometry_mut_map;
 pub mod graph;
+pub mod hatch;
 pub mod io;
+pub mod layout;
 #[cfg(feature = "cxx-bindings")]
 pub mod noding;
+pub mod penrose;
+pub mod raster;
+pub mod sample;
 pub mod snap;
+pub mod svg;
 pub mod triangulation;
+pub mod winding;
 
 pub use geometry_mut_map::MapCoordsInPlaceMut;
 
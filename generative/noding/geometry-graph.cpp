@@ -46,6 +46,12 @@ void GeometryGraph::add_edge(std::size_t src, std::size_t dst)
 {
     m_nodes[src].adjacencies.emplace(dst);
     m_nodes[dst].adjacencies.emplace(src);
+
+    // Keep the orientation of the first time this edge was added
+    if (m_nodes[dst].successors.count(src) == 0)
+    {
+        m_nodes[src].successors.emplace(dst);
+    }
 }
 
 std::vector<std::pair<const GeometryGraph::Node&, const GeometryGraph::Node&>>
@@ -61,7 +67,16 @@ GeometryGraph::get_edge_pairs() const
             // Only print each edge once.
             if (node.index < adj)
             {
-                pairs.emplace_back(node, m_nodes[adj]);
+                const auto& other = m_nodes[adj];
+                const bool reversed =
+                    other.successors.count(node.index) != 0 && node.successors.count(adj) == 0;
+                if (reversed)
+                {
+                    pairs.emplace_back(other, node);
+                } else
+                {
+                    pairs.emplace_back(node, other);
+                }
             }
         }
     }
@@ -1,8 +1,15 @@
-use geo::{Coord, Geometry, LineString, Polygon};
+use geo::{BoundingRect, Coord, Geometry, Intersects, Line, LineString, Polygon, Rect};
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
 
 use crate::cxxbridge;
 use crate::graph::GeometryGraph;
 
+/// Node the given geometries, and build a graph from the resulting segments
+///
+/// For directed graphs, each edge is oriented in the direction of the (noded) input LINESTRING or
+/// POLYGON ring it came from. If the same segment appears more than once in the input, the
+/// orientation of its first occurrence wins.
 pub fn node<G, Direction: petgraph::EdgeType>(geoms: G) -> GeometryGraph<Direction>
 where
     G: IntoIterator<Item = Geometry>,
@@ -42,44 +49,136 @@ where
     graph
 }
 
-pub fn polygonize<Direction: petgraph::EdgeType>(
-    graph: &GeometryGraph<Direction>,
-) -> (Vec<Polygon>, Vec<LineString>) {
-    let ffi_graph = cxxbridge::to_ffi_graph(graph);
-    let result = cxxbridge::polygonize(&ffi_graph);
+/// Node a new geometry into an already noded graph
+///
+/// Rather than re-noding the whole graph, only the edges whose bounding boxes come within
+/// `tolerance` of the new geometry are pulled out of the graph and noded along with it. The
+/// resulting segments are merged back in, reusing the existing nodes within `tolerance` of each
+/// noded vertex, so the rest of the graph keeps its node indices.
+pub fn node_incremental<Direction: petgraph::EdgeType>(
+    graph: &mut GeometryGraph<Direction>,
+    new: Geometry,
+    tolerance: f64,
+) {
+    let Some(bounds) = new.bounding_rect() else {
+        return;
+    };
+    let pad = Coord {
+        x: tolerance,
+        y: tolerance,
+    };
+    let bounds = Rect::new(bounds.min() - pad, bounds.max() + pad);
+
+    let affected: Vec<_> = graph
+        .edge_indices()
+        .filter(|e| {
+            let (src, dst) = graph.edge_endpoints(*e).unwrap();
+            Line::new(graph[src].0, graph[dst].0)
+                .bounding_rect()
+                .intersects(&bounds)
+        })
+        .collect();
+    let mut geoms = Vec::with_capacity(affected.len() + 1);
+    geoms.push(new);
+    // Highest index first, because remove_edge() moves the last edge into the removed edge's index
+    for edge in affected.into_iter().rev() {
+        let (src, dst) = graph.edge_endpoints(edge).unwrap();
+        geoms.push(Geometry::Line(Line::new(graph[src].0, graph[dst].0)));
+        graph.remove_edge(edge);
+    }
+    log::debug!(
+        "Re-noding {} edges around the new geometry",
+        geoms.len() - 1
+    );
+
+    let noded: GeometryGraph<Direction> = node(geoms);
+
+    let mut index = KdTree::new(2);
+    for idx in graph.node_indices() {
+        let point = graph[idx];
+        index
+            .add([point.x(), point.y()], idx)
+            .expect("Failed to add point to kdtree");
+    }
+    let mut nodes = Vec::with_capacity(noded.node_count());
+    for point in noded.node_weights() {
+        let coord = [point.x(), point.y()];
+        let existing = index
+            .nearest(&coord, 1, &squared_euclidean)
+            .ok()
+            .and_then(|nearest| nearest.first().copied())
+            .filter(|(distance, _)| *distance <= tolerance * tolerance)
+            .map(|(_, idx)| *idx);
+        let idx = match existing {
+            Some(idx) => idx,
+            None => {
+                let idx = graph.add_node(*point);
+                index
+                    .add(coord, idx)
+                    .expect("Failed to add point to kdtree");
+                idx
+            }
+        };
+        nodes.push(idx);
+    }
 
-    let mut polys = Vec::new();
-    polys.reserve_exact(result.polygons.len());
-    for coordseq in result.polygons {
-        let coords: Vec<_> = coordseq
-            .vec
-            .into_iter()
-            .map(|c| Coord { x: c.x, y: c.y })
-            .collect();
-        let exterior = LineString::new(coords);
-        let interiors = Vec::new();
-        polys.push(Polygon::new(exterior, interiors));
+    for edge in noded.raw_edges() {
+        let src = nodes[edge.source().index()];
+        let dst = nodes[edge.target().index()];
+        if src != dst {
+            graph.update_edge(src, dst, ());
+        }
     }
+}
 
-    let mut dangles = Vec::new();
-    dangles.reserve_exact(result.dangles.len());
-    for coordseq in result.dangles {
+fn to_linestrings(coordseqs: Vec<cxxbridge::LineStringShim>) -> Vec<LineString> {
+    let mut linestrings = Vec::new();
+    linestrings.reserve_exact(coordseqs.len());
+    for coordseq in coordseqs {
         let coords: Vec<_> = coordseq
             .vec
             .into_iter()
             .map(|c| Coord { x: c.x, y: c.y })
             .collect();
-        dangles.push(LineString::new(coords));
+        linestrings.push(LineString::new(coords));
     }
+    linestrings
+}
 
-    (polys, dangles)
+/// Polygonize the given graph, returning the polygons, dangles, cut edges, and invalid ring lines
+///
+/// The edges that aren't part of any polygon are classified the same way GEOS does:
+/// * Dangles are edges with one or both ends unconnected to any other edge
+/// * Cut edges are connected at both ends, but have the same face on both sides, like an edge
+///   bridging two separate rings
+/// * Invalid ring lines are rings that don't form a valid polygon, like a self-intersecting ring
+pub fn polygonize<Direction: petgraph::EdgeType>(
+    graph: &GeometryGraph<Direction>,
+) -> (
+    Vec<Polygon>,
+    Vec<LineString>,
+    Vec<LineString>,
+    Vec<LineString>,
+) {
+    let ffi_graph = cxxbridge::to_ffi_graph(graph);
+    let result = cxxbridge::polygonize(&ffi_graph);
+
+    let polys = to_linestrings(result.polygons)
+        .into_iter()
+        .map(|exterior| Polygon::new(exterior, Vec::new()))
+        .collect();
+    let dangles = to_linestrings(result.dangles);
+    let cut_edges = to_linestrings(result.cut_edges);
+    let invalid_ring_lines = to_linestrings(result.invalid_ring_lines);
+
+    (polys, dangles, cut_edges, invalid_ring_lines)
 }
 
 #[cfg(test)]
 mod tests {
     use geo::Point;
     use petgraph::graph::{EdgeIndex, NodeIndex};
-    use petgraph::Undirected;
+    use petgraph::{Directed, Undirected};
     use wkt::TryFromWkt;
 
     use super::*;
@@ -120,6 +219,22 @@ mod tests {
         assert_eq!(edge0, expected0);
     }
 
+    #[test]
+    fn test_directed_follows_linestring() {
+        // The closing segment goes from the highest index node back to the lowest
+        let wkt = b"LINESTRING(0 0, 1 0, 1 1, 0 0)";
+        let geometries = read_wkt_geometries(&wkt[..]);
+
+        let graph = node::<_, Directed>(geometries);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+
+        assert!(graph.contains_edge(NodeIndex::new(0), NodeIndex::new(1)));
+        assert!(graph.contains_edge(NodeIndex::new(1), NodeIndex::new(2)));
+        assert!(graph.contains_edge(NodeIndex::new(2), NodeIndex::new(0)));
+        assert!(!graph.contains_edge(NodeIndex::new(0), NodeIndex::new(2)));
+    }
+
     #[test]
     fn test_single_linestring() {
         let wkt = b"LINESTRING(0 0, 1 0, 2 0)";
@@ -178,6 +293,19 @@ mod tests {
         assert_eq!(edges, expected);
     }
 
+    #[test]
+    fn test_multi_geometries() {
+        // The same as test_crossing_linestrings, but with MULTI-geometries nested in a collection
+        let wkt = b"GEOMETRYCOLLECTION(MULTILINESTRING((0 0, 1 0), (0.5 -1, 0.5 1)), MULTIPOINT((5 5), (6 6)))";
+        let geometries = read_wkt_geometries(&wkt[..]);
+
+        let graph = node::<_, Undirected>(geometries);
+        assert_eq!(graph.node_count(), 7);
+        assert_eq!(graph.edge_count(), 4);
+        assert!(graph.node_weights().any(|p| *p == Point::new(0.5, 0.0)));
+        assert!(graph.node_weights().any(|p| *p == Point::new(6.0, 6.0)));
+    }
+
     #[test]
     fn test_rectangle() {
         // a tic-tac-toe pattern
@@ -233,9 +361,11 @@ mod tests {
         let geoms = read_wkt_geometries(&wkt[..]);
         let graph = node::<_, Undirected>(geoms);
 
-        let (polygons, dangles) = polygonize(&graph);
+        let (polygons, dangles, cut_edges, invalid_ring_lines) = polygonize(&graph);
         assert_eq!(polygons.len(), 1);
         assert_eq!(dangles.len(), 8);
+        assert!(cut_edges.is_empty());
+        assert!(invalid_ring_lines.is_empty());
 
         let expected: Polygon =
             Polygon::try_from_wkt_str("POLYGON((2 2, 2 6, 6 6, 6 2, 2 2))").unwrap();
@@ -253,4 +383,57 @@ mod tests {
         ];
         assert_eq!(dangles, expected);
     }
+
+    #[test]
+    fn test_polygonize_cut_edge() {
+        // Two squares bridged by a line, which has the same (exterior) face on both sides
+        let wkt = b"GEOMETRYCOLLECTION( LINESTRING(0 0, 1 0, 1 1, 0 1, 0 0), LINESTRING(2 0, 3 0, 3 1, 2 1, 2 0), LINESTRING(1 0.5, 2 0.5))";
+        let geoms = read_wkt_geometries(&wkt[..]);
+        let graph = node::<_, Undirected>(geoms);
+
+        let (polygons, dangles, cut_edges, invalid_ring_lines) = polygonize(&graph);
+        assert_eq!(polygons.len(), 2);
+        assert!(dangles.is_empty());
+        assert!(invalid_ring_lines.is_empty());
+
+        assert_eq!(cut_edges.len(), 1);
+        let mut cut_edge = cut_edges[0].clone();
+        if cut_edge.0[0].x > cut_edge.0[1].x {
+            cut_edge.0.reverse();
+        }
+        let expected = LineString::try_from_wkt_str("LINESTRING(1 0.5, 2 0.5)").unwrap();
+        assert_eq!(cut_edge, expected);
+    }
+    #[test]
+    fn test_node_incremental() {
+        let wkt = b"LINESTRING(0 0, 2 0)\nLINESTRING(10 10, 11 11)";
+        let geometries = read_wkt_geometries(&wkt[..]);
+        let mut graph = node::<_, Undirected>(geometries);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+        let far: Vec<_> = graph
+            .node_indices()
+            .filter(|n| graph[*n].x() >= 10.0)
+            .collect();
+
+        // Crosses the first linestring, but is nowhere near the second
+        let new = Geometry::try_from_wkt_str("LINESTRING(1 -1, 1 1)").unwrap();
+        node_incremental(&mut graph, new, 0.0);
+        assert_eq!(graph.node_count(), 7);
+        assert_eq!(graph.edge_count(), 5);
+
+        let crossing = graph
+            .node_indices()
+            .find(|n| graph[*n] == Point::new(1.0, 0.0))
+            .unwrap();
+        assert_eq!(graph.neighbors(crossing).count(), 4);
+
+        // The far away edge kept its nodes
+        let still_far: Vec<_> = graph
+            .node_indices()
+            .filter(|n| graph[*n].x() >= 10.0)
+            .collect();
+        assert_eq!(far, still_far);
+        assert!(graph.contains_edge(far[0], far[1]));
+    }
 }
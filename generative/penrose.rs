@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+use geo::{Coord, Point};
+use petgraph::graph::NodeIndex;
+use petgraph::Undirected;
+
+use crate::graph::GeometryGraph;
+
+/// The golden ratio
+const PHI: f64 = 1.618_033_988_749_895;
+
+/// The initial configuration of a Penrose tiling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PenroseConfiguration {
+    /// A wheel of ten thin half-rhombi around the origin
+    Sun,
+    /// Five thick rhombi meeting at their acute corners
+    Star,
+}
+
+impl std::fmt::Display for PenroseConfiguration {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            // important: Should match clap::ValueEnum format
+            PenroseConfiguration::Sun => write!(f, "sun"),
+            PenroseConfiguration::Star => write!(f, "star"),
+        }
+    }
+}
+
+/// A Robinson triangle; half of a Penrose rhombus
+///
+/// The rhombus is the triangle mirrored across its base BC, so AB and AC are sides of the rhombus,
+/// and BC is one of its diagonals.
+#[derive(Debug, Clone, Copy)]
+struct Triangle {
+    /// Whether this is half of a thick rhombus (obtuse angle at A), or a thin rhombus (acute angle
+    /// at A)
+    thick: bool,
+    a: Coord,
+    b: Coord,
+    c: Coord,
+}
+
+fn from_polar(r: f64, theta: f64) -> Coord {
+    Coord {
+        x: r * theta.cos(),
+        y: r * theta.sin(),
+    }
+}
+
+/// Build the initial triangles with unit length rhombus sides
+fn initial_triangles(configuration: PenroseConfiguration) -> Vec<Triangle> {
+    use std::f64::consts::PI;
+    let origin = Coord::zero();
+
+    match configuration {
+        PenroseConfiguration::Sun => (0..10)
+            .map(|i| {
+                let mut b = from_polar(1.0, (2 * i) as f64 * PI / 10.0 - PI / 10.0);
+                let mut c = from_polar(1.0, (2 * i) as f64 * PI / 10.0 + PI / 10.0);
+                // Alternate the handedness, so that neighboring triangles are mirror images
+                if i % 2 == 0 {
+                    std::mem::swap(&mut b, &mut c);
+                }
+                Triangle {
+                    thick: false,
+                    a: origin,
+                    b,
+                    c,
+                }
+            })
+            .collect(),
+        PenroseConfiguration::Star => (0..5)
+            .flat_map(|i| {
+                // Each rhombus is split along its long diagonal from the origin to its tip
+                let theta = (2 * i) as f64 * PI / 5.0;
+                let tip = from_polar(PHI, theta);
+                [-1.0, 1.0].map(|side| Triangle {
+                    thick: true,
+                    a: from_polar(1.0, theta + side * PI / 5.0),
+                    b: origin,
+                    c: tip,
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Subdivide each triangle into smaller triangles, shrinking the rhombus sides by a factor of PHI
+fn deflate(triangles: &[Triangle]) -> Vec<Triangle> {
+    let mut result = Vec::with_capacity(triangles.len() * 3);
+    for t in triangles {
+        if t.thick {
+            let q = t.b + (t.a - t.b) / PHI;
+            let r = t.b + (t.c - t.b) / PHI;
+            result.push(Triangle {
+                thick: true,
+                a: r,
+                b: t.c,
+                c: t.a,
+            });
+            result.push(Triangle {
+                thick: true,
+                a: q,
+                b: r,
+                c: t.b,
+            });
+            result.push(Triangle {
+                thick: false,
+                a: r,
+                b: q,
+                c: t.a,
+            });
+        } else {
+            let p = t.a + (t.b - t.a) / PHI;
+            result.push(Triangle {
+                thick: false,
+                a: t.c,
+                b: p,
+                c: t.b,
+            });
+            result.push(Triangle {
+                thick: true,
+                a: p,
+                b: t.c,
+                c: t.a,
+            });
+        }
+    }
+    result
+}
+
+/// Generate a Penrose P3 rhombus tiling by repeatedly subdividing the initial configuration
+///
+/// Each generation shrinks the rhombi by a factor of the golden ratio, so the tiling is scaled up
+/// by `PHI^generations` to give rhombi with sides of the given length. The rhombi on the boundary
+/// of the tiling may be halved along their diagonal, and are left open.
+pub fn p3_tiling(
+    configuration: PenroseConfiguration,
+    generations: usize,
+    edge_length: f64,
+) -> GeometryGraph<Undirected> {
+    let mut triangles = initial_triangles(configuration);
+    for _ in 0..generations {
+        triangles = deflate(&triangles);
+    }
+    let scale = edge_length * PHI.powi(generations as i32);
+
+    // The same vertex is generated by several triangles with slightly different floating point
+    // error, so vertices are deduplicated on a fine lattice relative to the edge length.
+    let tolerance = edge_length * 1e-6;
+    let mut graph = GeometryGraph::<Undirected>::default();
+    let mut nodes: HashMap<(i64, i64), NodeIndex<usize>> = HashMap::new();
+    let mut add_node = |graph: &mut GeometryGraph<Undirected>, coord: Coord| {
+        let coord = coord * scale;
+        let key = (
+            (coord.x / tolerance).round() as i64,
+            (coord.y / tolerance).round() as i64,
+        );
+        *nodes
+            .entry(key)
+            .or_insert_with(|| graph.add_node(Point(coord)))
+    };
+
+    for t in triangles {
+        let a = add_node(&mut graph, t.a);
+        let b = add_node(&mut graph, t.b);
+        let c = add_node(&mut graph, t.c);
+        // The diagonal BC is shared with the other half of the rhombus, and isn't a tile edge
+        graph.update_edge(a, b, ());
+        graph.update_edge(a, c, ());
+    }
+
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Distance, Euclidean};
+
+    use super::*;
+
+    #[test]
+    fn test_star_initial_configuration() {
+        let graph = p3_tiling(PenroseConfiguration::Star, 0, 1.0);
+        // The center, five tips, and the five vertices shared between neighboring rhombi
+        assert_eq!(graph.node_count(), 11);
+        assert_eq!(graph.edge_count(), 15);
+    }
+
+    #[test]
+    fn test_sun_initial_configuration() {
+        let graph = p3_tiling(PenroseConfiguration::Sun, 0, 1.0);
+        // Just the ten spokes; the outer edges are the diagonals of the boundary rhombi
+        assert_eq!(graph.node_count(), 11);
+        assert_eq!(graph.edge_count(), 10);
+    }
+
+    #[test]
+    fn test_edges_have_the_same_length() {
+        for configuration in [PenroseConfiguration::Sun, PenroseConfiguration::Star] {
+            let graph = p3_tiling(configuration, 4, 2.0);
+            assert!(graph.edge_count() > 15);
+            for edge in graph.raw_edges() {
+                let length = Euclidean::distance(graph[edge.source()], graph[edge.target()]);
+                assert!((length - 2.0).abs() < 1e-6, "length: {length}");
+            }
+        }
+    }
+}
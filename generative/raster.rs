@@ -0,0 +1,485 @@
+use geo::{AffineOps, AffineTransform, BoundingRect, Coord, Geometry, LineString, Polygon, Rect};
+use image::{Rgba, RgbaImage};
+
+use crate::flatten::flatten_nested_geometries;
+
+/// Parse a `#rrggbb` or `#rrggbbaa` hex color, a few basic color names, or `none` for transparent
+pub fn parse_color(s: &str) -> Result<Rgba<u8>, String> {
+    let color = match s.to_lowercase().as_str() {
+        "none" => [0, 0, 0, 0],
+        "black" => [0, 0, 0, 255],
+        "white" => [255, 255, 255, 255],
+        "red" => [255, 0, 0, 255],
+        "green" => [0, 128, 0, 255],
+        "blue" => [0, 0, 255, 255],
+        "gray" | "grey" => [128, 128, 128, 255],
+        _ => {
+            let digits = s.strip_prefix('#').unwrap_or(s);
+            let bytes = hex::decode(digits).map_err(|e| format!("Failed to parse '{s}': {e}"))?;
+            match bytes.as_slice() {
+                [r, g, b] => [*r, *g, *b, 255],
+                [r, g, b, a] => [*r, *g, *b, *a],
+                _ => return Err(format!("Expected a #rrggbb or #rrggbbaa color, got '{s}'")),
+            }
+        }
+    };
+    Ok(Rgba(color))
+}
+
+/// Rasterize geometries to an image, for quick previews without an SVG renderer
+///
+/// Lines are drawn one pixel wide, and polygons are filled with an even-odd scanline fill before
+/// their rings are drawn. The geometries are scaled to fit the image, with the y axis pointing up.
+///
+/// ```
+/// use generative::raster::RasterRenderer;
+/// use geo::{Geometry, Point};
+///
+/// let image = RasterRenderer::new()
+///     .width(100)
+///     .antialias(true)
+///     .render([Geometry::Point(Point::new(0.0, 0.0)), Geometry::Point(Point::new(1.0, 1.0))]);
+/// assert_eq!(image.dimensions(), (100, 100));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RasterRenderer {
+    width: u32,
+    height: Option<u32>,
+    padding: u32,
+    point_radius: f64,
+    stroke: Rgba<u8>,
+    fill: Option<Rgba<u8>>,
+    background: Rgba<u8>,
+    antialias: bool,
+}
+
+impl Default for RasterRenderer {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: None,
+            padding: 3,
+            point_radius: 1.0,
+            stroke: Rgba([0, 0, 0, 255]),
+            fill: None,
+            background: Rgba([255, 255, 255, 255]),
+            antialias: false,
+        }
+    }
+}
+
+impl RasterRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The width of the image in pixels
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// The height of the image in pixels
+    ///
+    /// If not set, the height is picked to preserve the geometries' aspect ratio.
+    pub fn height(mut self, height: Option<u32>) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// The number of pixels to leave around the geometries on each side of the image
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// The radius of the POINTs in pixels
+    pub fn point_radius(mut self, radius: f64) -> Self {
+        self.point_radius = radius;
+        self
+    }
+
+    pub fn stroke(mut self, stroke: Rgba<u8>) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    /// The color to fill polygons with. Polygons aren't filled by default.
+    pub fn fill(mut self, fill: Option<Rgba<u8>>) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    pub fn background(mut self, background: Rgba<u8>) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Antialias the lines and points
+    ///
+    /// Lines are drawn with Xiaolin Wu's algorithm if set, and with Bresenham's algorithm if not.
+    pub fn antialias(mut self, antialias: bool) -> Self {
+        self.antialias = antialias;
+        self
+    }
+
+    /// Render the given geometries to an image
+    ///
+    /// Like [SvgRenderer::render](crate::svg::SvgRenderer::render), this has to collect the
+    /// geometries to find the bounding box of the whole collection.
+    pub fn render<G>(&self, geometries: G) -> RgbaImage
+    where
+        G: IntoIterator<Item = Geometry>,
+    {
+        let geometries: Vec<_> = flatten_nested_geometries(geometries).collect();
+        let bbox = geometries
+            .iter()
+            .filter_map(|g| g.bounding_rect())
+            .reduce(|a, b| {
+                Rect::new(
+                    Coord {
+                        x: a.min().x.min(b.min().x),
+                        y: a.min().y.min(b.min().y),
+                    },
+                    Coord {
+                        x: a.max().x.max(b.max().x),
+                        y: a.max().y.max(b.max().y),
+                    },
+                )
+            });
+        let Some(bbox) = bbox else {
+            log::warn!("No geometries to rasterize");
+            let height = self.height.unwrap_or(self.width);
+            return RgbaImage::from_pixel(self.width, height, self.background);
+        };
+
+        let (transform, height) = self.calculate_transform(&bbox);
+        log::debug!(
+            "Transforming geometries with {transform:?} to fit into {}x{height} pixels",
+            self.width
+        );
+        let mut image = RgbaImage::from_pixel(self.width, height, self.background);
+        for geometry in geometries {
+            self.draw(&mut image, geometry.affine_transform(&transform));
+        }
+        image
+    }
+
+    /// Calculate the transform from the geometry coordinates to pixels, and the image height
+    fn calculate_transform(&self, bbox: &Rect) -> (AffineTransform, u32) {
+        let padding = 2.0 * self.padding as f64;
+        // Fit the geometries between the centers of the outermost pixels, so that the geometries
+        // on the far edges aren't clipped
+        let fit = |pixels: u32, size: f64| {
+            let pixels = (pixels as f64 - padding - 1.0).max(1.0);
+            if size > 0.0 {
+                pixels / size
+            } else {
+                f64::INFINITY
+            }
+        };
+        let scale = match self.height {
+            Some(height) => fit(self.width, bbox.width()).min(fit(height, bbox.height())),
+            // Fit vertical lines to the width too, so that they're not drawn tiny
+            None if bbox.width() == 0.0 => fit(self.width, bbox.height()),
+            None => fit(self.width, bbox.width()),
+        };
+        // A single point can be scaled any amount
+        let scale = if scale.is_finite() { scale } else { 1.0 };
+        let height = self
+            .height
+            .unwrap_or_else(|| ((bbox.height() * scale).ceil() + padding + 1.0) as u32);
+
+        // Center the geometries in the image, and flip the y axis to point up
+        let center = bbox.center();
+        let transform = AffineTransform::translate(-center.x, -center.y)
+            .scaled(scale, -scale, Coord::zero())
+            .translated(self.width as f64 / 2.0, height as f64 / 2.0);
+        (transform, height)
+    }
+
+    fn draw(&self, image: &mut RgbaImage, geometry: Geometry) {
+        match geometry {
+            Geometry::Point(p) => self.draw_point(image, p.0),
+            Geometry::Line(l) => self.draw_line(image, l.start, l.end),
+            Geometry::LineString(l) => self.draw_linestring(image, &l),
+            Geometry::Polygon(p) => self.draw_polygon(image, &p),
+            Geometry::Rect(r) => self.draw_polygon(image, &r.to_polygon()),
+            Geometry::Triangle(t) => self.draw_polygon(image, &t.to_polygon()),
+            _ => unreachable!("MULTI-geometries get flattened before rasterizing"),
+        }
+    }
+
+    fn draw_point(&self, image: &mut RgbaImage, center: Coord) {
+        let radius = self.point_radius;
+        let (ci, cj) = (center.x.floor() as i64, center.y.floor() as i64);
+        let extent = radius.ceil() as i64 + 1;
+        for j in (cj - extent)..=(cj + extent) {
+            for i in (ci - extent)..=(ci + extent) {
+                let d = (i as f64 + 0.5 - center.x).hypot(j as f64 + 0.5 - center.y);
+                let coverage = if self.antialias {
+                    (radius + 0.5 - d).clamp(0.0, 1.0)
+                } else if d <= radius || (i, j) == (ci, cj) {
+                    1.0
+                } else {
+                    0.0
+                };
+                blend(image, i, j, self.stroke, coverage);
+            }
+        }
+    }
+
+    fn draw_linestring(&self, image: &mut RgbaImage, linestring: &LineString) {
+        for line in linestring.lines() {
+            self.draw_line(image, line.start, line.end);
+        }
+    }
+
+    fn draw_polygon(&self, image: &mut RgbaImage, polygon: &Polygon) {
+        if let Some(fill) = self.fill {
+            fill_polygon(image, polygon, fill);
+        }
+        self.draw_linestring(image, polygon.exterior());
+        for interior in polygon.interiors() {
+            self.draw_linestring(image, interior);
+        }
+    }
+
+    fn draw_line(&self, image: &mut RgbaImage, start: Coord, end: Coord) {
+        if self.antialias {
+            draw_line_wu(image, start, end, self.stroke);
+        } else {
+            draw_line_bresenham(image, start, end, self.stroke);
+        }
+    }
+}
+
+/// Blend the color over the pixel, with the given fraction of the pixel covered
+///
+/// Pixels outside of the image are ignored.
+fn blend(image: &mut RgbaImage, i: i64, j: i64, color: Rgba<u8>, coverage: f64) {
+    if coverage <= 0.0 || i < 0 || j < 0 || i >= image.width() as i64 || j >= image.height() as i64
+    {
+        return;
+    }
+    let pixel = image.get_pixel_mut(i as u32, j as u32);
+    let alpha = color[3] as f64 / 255.0 * coverage.min(1.0);
+    let dst_alpha = pixel[3] as f64 / 255.0;
+    let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+    if out_alpha <= 0.0 {
+        return;
+    }
+    for c in 0..3 {
+        let value =
+            (color[c] as f64 * alpha + pixel[c] as f64 * dst_alpha * (1.0 - alpha)) / out_alpha;
+        pixel[c] = value.round() as u8;
+    }
+    pixel[3] = (out_alpha * 255.0).round() as u8;
+}
+
+/// Draw a one pixel wide line with Bresenham's algorithm
+fn draw_line_bresenham(image: &mut RgbaImage, start: Coord, end: Coord, color: Rgba<u8>) {
+    let (mut i0, mut j0) = (start.x.floor() as i64, start.y.floor() as i64);
+    let (i1, j1) = (end.x.floor() as i64, end.y.floor() as i64);
+    let (di, si) = ((i1 - i0).abs(), if i0 < i1 { 1 } else { -1 });
+    let (dj, sj) = (-(j1 - j0).abs(), if j0 < j1 { 1 } else { -1 });
+    let mut error = di + dj;
+    loop {
+        blend(image, i0, j0, color, 1.0);
+        if i0 == i1 && j0 == j1 {
+            break;
+        }
+        let e2 = 2 * error;
+        if e2 >= dj {
+            error += dj;
+            i0 += si;
+        }
+        if e2 <= di {
+            error += di;
+            j0 += sj;
+        }
+    }
+}
+
+/// The fractional part of x, which is always positive, unlike [f64::fract]
+fn fpart(x: f64) -> f64 {
+    x - x.floor()
+}
+
+/// Draw an antialiased one pixel wide line with Xiaolin Wu's algorithm
+fn draw_line_wu(image: &mut RgbaImage, start: Coord, end: Coord, color: Rgba<u8>) {
+    // Wu's algorithm puts the pixel centers at the integer coordinates
+    let (mut x0, mut y0) = (start.x - 0.5, start.y - 0.5);
+    let (mut x1, mut y1) = (end.x - 0.5, end.y - 0.5);
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    if steep {
+        std::mem::swap(&mut x0, &mut y0);
+        std::mem::swap(&mut x1, &mut y1);
+    }
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+    let dx = x1 - x0;
+    let gradient = if dx == 0.0 { 1.0 } else { (y1 - y0) / dx };
+
+    let mut plot = |x: i64, y: i64, coverage: f64| {
+        if steep {
+            blend(image, y, x, color, coverage);
+        } else {
+            blend(image, x, y, color, coverage);
+        }
+    };
+
+    // The endpoints only partially cover their pixels along the line
+    let mut endpoint = |x: f64, y: f64, xgap: f64| {
+        let xend = x.round();
+        let yend = y + gradient * (xend - x);
+        let (xpixel, ypixel) = (xend as i64, yend.floor() as i64);
+        plot(xpixel, ypixel, (1.0 - fpart(yend)) * xgap);
+        plot(xpixel, ypixel + 1, fpart(yend) * xgap);
+        (xpixel, yend)
+    };
+    let (first, yend) = endpoint(x0, y0, 1.0 - fpart(x0 + 0.5));
+    let (last, _) = endpoint(x1, y1, fpart(x1 + 0.5));
+
+    let mut y = yend + gradient;
+    for x in (first + 1)..last {
+        plot(x, y.floor() as i64, 1.0 - fpart(y));
+        plot(x, y.floor() as i64 + 1, fpart(y));
+        y += gradient;
+    }
+}
+
+/// Fill the polygon with an even-odd scanline fill, sampled at the pixel centers
+fn fill_polygon(image: &mut RgbaImage, polygon: &Polygon, color: Rgba<u8>) {
+    let Some(bbox) = polygon.bounding_rect() else {
+        return;
+    };
+    let rings: Vec<_> = std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .collect();
+
+    let first_row = bbox.min().y.floor().max(0.0) as i64;
+    let last_row = bbox.max().y.ceil().min(image.height() as f64) as i64;
+    let mut crossings = Vec::new();
+    for j in first_row..last_row {
+        let y = j as f64 + 0.5;
+        crossings.clear();
+        for line in rings.iter().flat_map(|r| r.lines()) {
+            let (a, b) = (line.start, line.end);
+            if (a.y <= y) != (b.y <= y) {
+                crossings.push(a.x + (y - a.y) * (b.x - a.x) / (b.y - a.y));
+            }
+        }
+        crossings.sort_by(f64::total_cmp);
+        for span in crossings.chunks_exact(2) {
+            // The pixels whose centers are between the crossings
+            let first = (span[0] - 0.5).ceil().max(0.0) as i64;
+            let last = (span[1] - 0.5).floor().min(image.width() as f64) as i64;
+            for i in first..=last {
+                blend(image, i, j, color, 1.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::read_wkt_geometries;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+    fn render(renderer: RasterRenderer, wkt: &str) -> RgbaImage {
+        renderer.render(read_wkt_geometries(wkt.as_bytes()))
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#ff0000"), Ok(RED));
+        assert_eq!(parse_color("FF000080"), Ok(Rgba([255, 0, 0, 128])));
+        assert_eq!(parse_color("Black"), Ok(BLACK));
+        assert_eq!(parse_color("none"), Ok(Rgba([0, 0, 0, 0])));
+        assert!(parse_color("#ff00").is_err());
+        assert!(parse_color("purple").is_err());
+    }
+
+    #[test]
+    fn test_render_preserves_aspect_ratio() {
+        let renderer = RasterRenderer::new().width(12).padding(0);
+        let image = render(renderer.clone(), "LINESTRING(0 0, 11 5)");
+        assert_eq!(image.dimensions(), (12, 6));
+        assert_eq!(*image.get_pixel(0, 5), BLACK);
+        assert_eq!(*image.get_pixel(11, 0), BLACK);
+
+        // The geometries are centered in the image if its aspect ratio doesn't match
+        let image = render(renderer.height(Some(12)), "LINESTRING(0 0, 11 5)");
+        assert_eq!(image.dimensions(), (12, 12));
+        assert_eq!(*image.get_pixel(0, 8), BLACK);
+        assert_eq!(*image.get_pixel(11, 3), BLACK);
+        assert_eq!(*image.get_pixel(0, 11), WHITE);
+    }
+
+    #[test]
+    fn test_render_line() {
+        let renderer = RasterRenderer::new().width(10).height(Some(3)).padding(0);
+        let image = render(renderer, "LINESTRING(0 0, 10 0)");
+        for i in 0..10 {
+            assert_eq!(*image.get_pixel(i, 0), WHITE);
+            assert_eq!(*image.get_pixel(i, 1), BLACK);
+            assert_eq!(*image.get_pixel(i, 2), WHITE);
+        }
+    }
+
+    #[test]
+    fn test_render_antialiased_line() {
+        let wkt = "LINESTRING(0 0, 10 3)";
+        let renderer = RasterRenderer::new().width(20).padding(0);
+        let aliased = render(renderer.clone(), wkt);
+        let antialiased = render(renderer.antialias(true), wkt);
+
+        let is_gray = |p: &Rgba<u8>| *p != WHITE && *p != BLACK;
+        assert!(!aliased.pixels().any(is_gray));
+        assert!(antialiased.pixels().any(is_gray));
+        // Every column is drawn in
+        for i in 0..20 {
+            assert!((0..antialiased.height()).any(|j| *antialiased.get_pixel(i, j) != WHITE));
+        }
+    }
+
+    #[test]
+    fn test_fill_polygon_with_hole() {
+        let renderer = RasterRenderer::new()
+            .width(10)
+            .padding(0)
+            .stroke(Rgba([0, 0, 0, 0]))
+            .fill(Some(RED));
+        let image = render(
+            renderer,
+            "POLYGON((0 0, 10 0, 10 10, 0 10, 0 0), (4 4, 6 4, 6 6, 4 6, 4 4))",
+        );
+        assert_eq!(image.dimensions(), (10, 10));
+        assert_eq!(*image.get_pixel(1, 1), RED);
+        assert_eq!(*image.get_pixel(8, 8), RED);
+        assert_eq!(*image.get_pixel(3, 5), RED);
+        assert_eq!(*image.get_pixel(4, 4), WHITE);
+        assert_eq!(*image.get_pixel(5, 5), WHITE);
+    }
+
+    #[test]
+    fn test_render_points() {
+        let renderer = RasterRenderer::new().width(11).padding(0).point_radius(0.0);
+        let image = render(renderer, "POINT(0 0)\nPOINT(10 10)");
+        assert_eq!(image.dimensions(), (11, 11));
+        let drawn: Vec<_> = image
+            .enumerate_pixels()
+            .filter(|(_, _, p)| **p == BLACK)
+            .map(|(i, j, _)| (i, j))
+            .collect();
+        // The y axis is flipped
+        assert_eq!(drawn, [(10, 0), (0, 10)]);
+    }
+}
@@ -0,0 +1,164 @@
+use geo::{LineString, Point};
+
+/// Sample points every `spacing` units of arc length along the linestring
+///
+/// The first point is the start of the linestring, and the last point is the furthest multiple of
+/// `spacing` that fits. A closed ring doesn't repeat its start point at the end, so that beads
+/// placed around a ring don't overlap. A linestring shorter than `spacing` is sampled at its start.
+pub fn sample_linestring(linestring: &LineString, spacing: f64) -> Vec<Point> {
+    if spacing <= 0.0 || linestring.0.is_empty() {
+        return Vec::new();
+    }
+    let length = arc_length(linestring);
+    // Allow for floating point error when the spacing evenly divides the length
+    let mut count = (length / spacing + 1e-9).floor() as usize + 1;
+    let last = (count - 1) as f64 * spacing;
+    if linestring.is_closed() && count > 1 && last >= length - spacing * 1e-9 {
+        count -= 1;
+    }
+    points_at_distances(linestring, (0..count).map(|i| i as f64 * spacing))
+}
+
+/// Sample `count` evenly spaced points along the linestring
+///
+/// An open linestring is sampled at both of its ends. A closed ring is sampled at its start, but
+/// not again at its end.
+pub fn sample_linestring_count(linestring: &LineString, count: usize) -> Vec<Point> {
+    if count == 0 || linestring.0.is_empty() {
+        return Vec::new();
+    }
+    let length = arc_length(linestring);
+    let intervals = if linestring.is_closed() {
+        count
+    } else {
+        count - 1
+    };
+    let spacing = if intervals == 0 {
+        0.0
+    } else {
+        length / intervals as f64
+    };
+    points_at_distances(linestring, (0..count).map(|i| i as f64 * spacing))
+}
+
+fn arc_length(linestring: &LineString) -> f64 {
+    linestring
+        .lines()
+        .map(|line| line.dx().hypot(line.dy()))
+        .sum()
+}
+
+/// Interpolate the points at the given increasing arc lengths along the linestring
+///
+/// Distances past the end of the linestring are clamped to its last point.
+fn points_at_distances(
+    linestring: &LineString,
+    distances: impl Iterator<Item = f64>,
+) -> Vec<Point> {
+    let mut points = Vec::new();
+    let mut lines = linestring.lines().peekable();
+    // The arc length at the start of the current segment
+    let mut traveled = 0.0;
+    for distance in distances {
+        while let Some(line) = lines.peek() {
+            let length = line.dx().hypot(line.dy());
+            if distance <= traveled + length {
+                break;
+            }
+            traveled += length;
+            lines.next();
+        }
+
+        let point = match lines.peek() {
+            Some(line) => {
+                let length = line.dx().hypot(line.dy());
+                let t = if length > 0.0 {
+                    (distance - traveled) / length
+                } else {
+                    0.0
+                };
+                line.start + (line.end - line.start) * t
+            }
+            None => *linestring.0.last().unwrap(),
+        };
+        points.push(Point::from(point));
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::line_string;
+
+    use super::*;
+
+    fn coords(points: &[Point]) -> Vec<(f64, f64)> {
+        points.iter().map(|p| (p.x(), p.y())).collect()
+    }
+
+    #[test]
+    fn test_sample_across_segments() {
+        let linestring = line_string![(x: 0.0, y: 0.0), (x: 1.5, y: 0.0), (x: 1.5, y: 1.5)];
+        let points = sample_linestring(&linestring, 1.0);
+        assert_eq!(
+            coords(&points),
+            [(0.0, 0.0), (1.0, 0.0), (1.5, 0.5), (1.5, 1.5)]
+        );
+
+        let points = sample_linestring(&linestring, 0.75);
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[4], Point::new(1.5, 1.5));
+    }
+
+    #[test]
+    fn test_sample_closed_ring() {
+        let ring = line_string![
+            (x: 0.0, y: 0.0),
+            (x: 1.0, y: 0.0),
+            (x: 1.0, y: 1.0),
+            (x: 0.0, y: 1.0),
+            (x: 0.0, y: 0.0),
+        ];
+        let points = sample_linestring(&ring, 1.0);
+        assert_eq!(
+            coords(&points),
+            [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+        );
+
+        let points = sample_linestring_count(&ring, 8);
+        assert_eq!(points.len(), 8);
+        assert_eq!(points[1], Point::new(0.5, 0.0));
+        assert_eq!(points[7], Point::new(0.0, 0.5));
+    }
+
+    #[test]
+    fn test_sample_short_lines() {
+        let short = line_string![(x: 0.0, y: 0.0), (x: 0.1, y: 0.0)];
+        assert_eq!(coords(&sample_linestring(&short, 1.0)), [(0.0, 0.0)]);
+
+        let point = line_string![(x: 2.0, y: 3.0)];
+        assert_eq!(coords(&sample_linestring(&point, 1.0)), [(2.0, 3.0)]);
+        assert_eq!(
+            coords(&sample_linestring_count(&point, 2)),
+            [(2.0, 3.0), (2.0, 3.0)]
+        );
+
+        let empty = LineString::<f64>::new(Vec::new());
+        assert!(sample_linestring(&empty, 1.0).is_empty());
+        assert!(sample_linestring(&short, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_sample_count() {
+        let linestring = line_string![(x: 0.0, y: 0.0), (x: 3.0, y: 0.0)];
+        let points = sample_linestring_count(&linestring, 4);
+        assert_eq!(
+            coords(&points),
+            [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)]
+        );
+        assert_eq!(
+            coords(&sample_linestring_count(&linestring, 1)),
+            [(0.0, 0.0)]
+        );
+    }
+}
@@ -1,8 +1,12 @@
-use geo::{Coord, CoordsIter, Geometry, Line, LineString, Polygon, Triangle};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+
+use geo::{Coord, CoordsIter, Geometry, HasDimensions, Line, LineString, Point, Polygon, Triangle};
 use kdtree::distance::squared_euclidean;
 use kdtree::KdTree;
 use petgraph::graph::NodeIndex;
-use petgraph::EdgeType;
+use petgraph::visit::EdgeRef;
+use petgraph::{EdgeType, Graph};
 
 use crate::flatten::{flatten_geometries_into_points_ref, flatten_nested_geometries};
 use crate::graph::GeometryGraph;
@@ -25,18 +29,158 @@ pub enum SnappingStrategy {
     RegularGrid(f64),
 }
 
+/// Snapping strategies that need the whole collection at once, and so can't snap one geometry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CollectionSnappingStrategy {
+    /// Snap vertices onto the closest point of any nearby segment, even between its vertices
+    ///
+    /// The snapped vertex is also inserted into the segment it was snapped onto, so that a line
+    /// that ends just shy of another line gets noded together with it.
+    VertexToEdge(f64),
+    /// Weld the endpoints of lines together, leaving their interior vertices untouched
+    ///
+    /// Only the endpoints of LINEs and LINESTRINGs are snapped, and only to each other, so each
+    /// line keeps its shape between its endpoints. For graphs, the nodes with a single neighbor
+    /// are the endpoints. Closes the gaps at junctions of hand-drawn or traced linework, up to
+    /// the tolerance distance wide.
+    EndpointsOnly(f64),
+}
+
+/// Either kind of snapping strategy, for [snap_geoms] and [snap_graph]
+///
+/// Every strategy's tolerance is a distance in the same units as the coordinates. Points within
+/// the tolerance of each other get snapped together, and the regular grid has that spacing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Snapping {
+    /// Snap each vertex on its own, which [snap_geom] can also do
+    Vertex(SnappingStrategy),
+    /// Snap using the whole collection, which only [snap_geoms] and [snap_graph] can do
+    Collection(CollectionSnappingStrategy),
+}
+
+impl From<SnappingStrategy> for Snapping {
+    fn from(strategy: SnappingStrategy) -> Snapping {
+        Snapping::Vertex(strategy)
+    }
+}
+
+impl From<CollectionSnappingStrategy> for Snapping {
+    fn from(strategy: CollectionSnappingStrategy) -> Snapping {
+        Snapping::Collection(strategy)
+    }
+}
+
+/// A snapping tolerance, or 'auto' to estimate one from the input with [estimate_tolerance]
+///
+/// Like the [Snapping] tolerances, it's a distance, not a squared distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    Auto,
+    Fixed(f64),
+}
+
+impl std::fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // important: Should round trip through Tolerance::from_str
+        match self {
+            Tolerance::Auto => write!(f, "auto"),
+            Tolerance::Fixed(tolerance) => write!(f, "{tolerance}"),
+        }
+    }
+}
+
+impl FromStr for Tolerance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("auto") {
+            return Ok(Tolerance::Auto);
+        }
+        s.trim()
+            .parse()
+            .map(Tolerance::Fixed)
+            .map_err(|e| format!("Expected a tolerance or 'auto', got '{s}': {e}"))
+    }
+}
+
+/// Estimate a snapping tolerance from the spacing of the geometries' vertices
+///
+/// Returns a tenth of the median distance from each unique vertex to its nearest neighbor, so that
+/// vertices much closer together than is typical for the input get snapped together. That assumes
+/// most vertices aren't near-duplicates. Returns zero if there are fewer than two unique vertices.
+pub fn estimate_tolerance<'g>(geoms: impl IntoIterator<Item = &'g Geometry>) -> f64 {
+    estimate_tolerance_from_coords(geoms.into_iter().flat_map(|g| g.coords_iter()))
+}
+
+/// Estimate a snapping tolerance from the spacing of the graph's nodes, like [estimate_tolerance]
+pub fn estimate_graph_tolerance<D: EdgeType>(graph: &GeometryGraph<D>) -> f64 {
+    estimate_tolerance_from_coords(graph.node_weights().map(|p| p.0))
+}
+
+fn estimate_tolerance_from_coords(coords: impl Iterator<Item = Coord>) -> f64 {
+    let mut index = GeomKdTree::new(2);
+    let mut unique = Vec::new();
+    for coord in coords {
+        let point = [coord.x, coord.y];
+        let closest = index.nearest(&point, 1, &squared_euclidean).unwrap();
+        if closest
+            .first()
+            .is_some_and(|(distance, _)| *distance == 0.0)
+        {
+            continue;
+        }
+        index.add(point, coord).unwrap();
+        unique.push(point);
+    }
+
+    let mut distances: Vec<f64> = unique
+        .iter()
+        .filter_map(|point| {
+            // The closest point is always the vertex itself
+            let neighbors = index.nearest(point, 2, &squared_euclidean).unwrap();
+            neighbors.get(1).map(|(distance, _)| distance.sqrt())
+        })
+        .collect();
+    if distances.is_empty() {
+        return 0.0;
+    }
+    let middle = distances.len() / 2;
+    let (_, median, _) = distances.select_nth_unstable_by(middle, f64::total_cmp);
+    0.1 * *median
+}
+
+/// Snap the vertices of the given geometries together
+///
+/// If `preserve_dimension` is set, any snap that would collapse a geometry into a lower dimension
+/// (like a POLYGON into a LINESTRING, or a LINESTRING into a POINT) is reverted, and the geometry
+/// is left unsnapped.
 pub fn snap_geoms(
     geoms: impl Iterator<Item = Geometry>,
-    strategy: SnappingStrategy,
+    strategy: impl Into<Snapping>,
+    preserve_dimension: bool,
 ) -> Box<dyn Iterator<Item = Geometry>> {
     // Build a k-d tree from the given geometries. flatten_geometries_into_points would require
     // cloning all of the given geometries, so flatten first, and then use the _ref() variant.
     let geoms = flatten_nested_geometries(geoms);
     let geoms: Vec<_> = geoms.collect();
 
+    let strategy = match strategy.into() {
+        Snapping::Vertex(strategy) => strategy,
+        Snapping::Collection(CollectionSnappingStrategy::VertexToEdge(tolerance)) => {
+            let snapped = snap_geoms_to_edges(geoms, tolerance, preserve_dimension);
+            return Box::new(snapped.into_iter());
+        }
+        Snapping::Collection(CollectionSnappingStrategy::EndpointsOnly(tolerance)) => {
+            let snapped = snap_geoms_endpoints(geoms, tolerance, preserve_dimension);
+            return Box::new(snapped.into_iter());
+        }
+    };
+
     // Short circuit the creation of the k-d tree
     if let SnappingStrategy::RegularGrid(tolerance) = strategy {
-        let snapped = geoms.into_iter().map(move |g| snap_geom_grid(g, tolerance));
+        let snapped = geoms.into_iter().map(move |g| {
+            preserving_dimension(g, preserve_dimension, |g| snap_geom_grid(g, tolerance))
+        });
         return Box::new(snapped);
     }
 
@@ -63,14 +207,47 @@ pub fn snap_geoms(
     // to load all of the geometries into a single vector to begin with)
     let snapped = geoms
         .into_iter()
-        .map(move |g| snap_geom(g, &mut index, &strategy));
+        .map(move |g| snap_geom(g, &mut index, &strategy, preserve_dimension));
     Box::new(snapped)
 }
 
-pub fn snap_geom(geom: Geometry, index: &mut GeomKdTree, strategy: &SnappingStrategy) -> Geometry {
-    match strategy {
+/// Snap a single geometry to the vertices in the given k-d tree
+pub fn snap_geom(
+    geom: Geometry,
+    index: &mut GeomKdTree,
+    strategy: &SnappingStrategy,
+    preserve_dimension: bool,
+) -> Geometry {
+    preserving_dimension(geom, preserve_dimension, |geom| match strategy {
         SnappingStrategy::ClosestPoint(tolerance) => snap_geom_impl(geom, index, *tolerance),
         SnappingStrategy::RegularGrid(tolerance) => snap_geom_grid(geom, *tolerance),
+    })
+}
+
+/// Apply the given snapping function, reverting the snap if it collapsed the geometry's dimension
+///
+/// NOTE: When snapping to the closest point, the reverted geometry's vertices have already been
+/// removed from the k-d tree, so later geometries won't snap to them.
+fn preserving_dimension(
+    geom: Geometry,
+    preserve_dimension: bool,
+    snap: impl FnOnce(Geometry) -> Geometry,
+) -> Geometry {
+    if !preserve_dimension {
+        return snap(geom);
+    }
+
+    let original = geom.clone();
+    let snapped = snap(geom);
+    if snapped.dimensions() < original.dimensions() {
+        log::debug!(
+            "Reverting snap that would collapse a {:?} geometry to {:?}",
+            original.dimensions(),
+            snapped.dimensions()
+        );
+        original
+    } else {
+        snapped
     }
 }
 
@@ -82,8 +259,9 @@ fn snap_geom_impl(mut geom: Geometry, index: &mut GeomKdTree, tolerance: f64) ->
 fn snap_coord(coord: Coord, index: &mut GeomKdTree, tolerance: f64) -> Coord {
     // Find the closest two points in the index, because the first closest should always be ourself.
     let coords = [coord.x, coord.y];
+    // The k-d tree measures squared distances
     let neighbors = index
-        .within(&coords, tolerance, &squared_euclidean)
+        .within(&coords, tolerance * tolerance, &squared_euclidean)
         .unwrap();
     // We should always find ourselves, or, if move_snapped_point is true, at least find where
     // ourselves have already been snapped to (because one point in the kd-tree could be multiple
@@ -107,6 +285,249 @@ fn snap_coord(coord: Coord, index: &mut GeomKdTree, tolerance: f64) -> Coord {
     coord
 }
 
+/// A k-d tree of segment midpoints, for looking up the segments near a point
+///
+/// A segment can only be within some distance of a point if its midpoint is within that distance
+/// plus half of the segment's length, so the lookups pad their search radius by the longest
+/// half-length.
+struct SegmentIndex<T> {
+    index: KdTree<f64, usize, [f64; 2]>,
+    segments: Vec<(Line, T)>,
+    max_half_length: f64,
+}
+
+impl<T> SegmentIndex<T> {
+    fn new(segments: Vec<(Line, T)>) -> Self {
+        let mut index = KdTree::new(2);
+        let mut max_half_length: f64 = 0.0;
+        for (i, (line, _)) in segments.iter().enumerate() {
+            let midpoint = (line.start + line.end) / 2.0;
+            index.add([midpoint.x, midpoint.y], i).unwrap();
+            let delta = line.delta();
+            max_half_length = max_half_length.max(0.5 * delta.x.hypot(delta.y));
+        }
+        Self {
+            index,
+            segments,
+            max_half_length,
+        }
+    }
+
+    /// Find the closest point within `tolerance` on any segment that isn't skipped
+    ///
+    /// Returns the closest point, how far along its segment it is in [0, 1], and the segment's data
+    fn closest(
+        &self,
+        coord: Coord,
+        tolerance: f64,
+        skip: impl Fn(&Line, &T) -> bool,
+    ) -> Option<(Coord, f64, &T)> {
+        let radius = tolerance + self.max_half_length;
+        let candidates = self
+            .index
+            .within(&[coord.x, coord.y], radius * radius, &squared_euclidean)
+            .unwrap();
+
+        let mut closest: Option<(f64, Coord, f64, &T)> = None;
+        for (_, i) in candidates {
+            let (line, data) = &self.segments[*i];
+            if skip(line, data) {
+                continue;
+            }
+            let (projected, t) = project_onto_line(coord, line);
+            let offset = projected - coord;
+            let distance = offset.x.hypot(offset.y);
+            if distance <= tolerance && closest.is_none_or(|(d, ..)| distance < d) {
+                closest = Some((distance, projected, t, data));
+            }
+        }
+        closest.map(|(_, projected, t, data)| (projected, t, data))
+    }
+}
+
+/// Project the coordinate onto the line, returning the closest point and how far along it is
+fn project_onto_line(coord: Coord, line: &Line) -> (Coord, f64) {
+    let delta = line.delta();
+    let length2 = delta.x * delta.x + delta.y * delta.y;
+    if length2 == 0.0 {
+        return (line.start, 0.0);
+    }
+    let offset = coord - line.start;
+    let t = ((offset.x * delta.x + offset.y * delta.y) / length2).clamp(0.0, 1.0);
+    (line.start + delta * t, t)
+}
+
+/// The coordinates of each POINT, LINESTRING, or POLYGON ring making up the geometry
+fn linear_parts(geom: &Geometry) -> Vec<Vec<Coord>> {
+    match geom {
+        Geometry::Point(p) => vec![vec![p.0]],
+        Geometry::Line(l) => vec![vec![l.start, l.end]],
+        Geometry::LineString(ls) => vec![ls.0.clone()],
+        Geometry::Polygon(p) => std::iter::once(p.exterior())
+            .chain(p.interiors())
+            .map(|ring| ring.0.clone())
+            .collect(),
+        Geometry::Rect(r) => linear_parts(&Geometry::Polygon(r.to_polygon())),
+        Geometry::Triangle(t) => linear_parts(&Geometry::Polygon(t.to_polygon())),
+        _ => unreachable!("flatten_nested_geometries in the call graph prevents MULTI-geometries"),
+    }
+}
+
+/// Rebuild the geometry from its snapped [linear_parts]
+///
+/// RECTs and TRIANGLEs may have gained vertices, so they're rebuilt as POLYGONs.
+fn from_linear_parts(geom: Geometry, parts: Vec<Vec<Coord>>) -> Geometry {
+    let mut parts = parts.into_iter();
+    let first = parts.next().unwrap_or_default();
+    match geom {
+        Geometry::Point(_) => Geometry::Point(first[0].into()),
+        Geometry::Line(_) | Geometry::LineString(_) => Geometry::LineString(LineString::new(first)),
+        _ => Geometry::Polygon(Polygon::new(
+            LineString::new(first),
+            parts.map(LineString::new).collect(),
+        )),
+    }
+}
+
+fn snap_geoms_to_edges(
+    geoms: Vec<Geometry>,
+    tolerance: f64,
+    preserve_dimension: bool,
+) -> Vec<Geometry> {
+    let mut parts: Vec<_> = geoms.iter().map(linear_parts).collect();
+
+    // Each segment is identified by its geometry, its part, and the index of its first vertex
+    let mut segments = Vec::new();
+    for (g, geom_parts) in parts.iter().enumerate() {
+        for (p, part) in geom_parts.iter().enumerate() {
+            for (v, pair) in part.windows(2).enumerate() {
+                segments.push((Line::new(pair[0], pair[1]), (g, p, v)));
+            }
+        }
+    }
+    let index = SegmentIndex::new(segments);
+
+    // Snap against the original segments, so that the result doesn't depend on the snapping order
+    let mut insertions: BTreeMap<(usize, usize, usize), Vec<(f64, Coord)>> = BTreeMap::new();
+    for geom_parts in parts.iter_mut() {
+        for part in geom_parts.iter_mut() {
+            for coord in part.iter_mut() {
+                // Don't snap a vertex onto the segments it's already an endpoint of
+                let original = *coord;
+                let is_endpoint =
+                    |line: &Line, _: &_| line.start == original || line.end == original;
+                let closest = index.closest(original, tolerance, is_endpoint);
+                if let Some((snapped, t, segment)) = closest {
+                    *coord = snapped;
+                    if 0.0 < t && t < 1.0 {
+                        insertions.entry(*segment).or_default().push((t, snapped));
+                    }
+                }
+            }
+        }
+    }
+
+    // Insert from the last segment to the first, so that the earlier vertex indices stay valid
+    for ((g, p, v), mut vertices) in insertions.into_iter().rev() {
+        vertices.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+        parts[g][p].splice(v + 1..v + 1, vertices.into_iter().map(|(_, c)| c));
+    }
+
+    geoms
+        .into_iter()
+        .zip(parts)
+        .map(|(geom, parts)| {
+            preserving_dimension(geom, preserve_dimension, |geom| {
+                filter_duplicate_vertices(from_linear_parts(geom, parts))
+            })
+        })
+        .collect()
+}
+
+/// The endpoints of a LINE or LINESTRING, or None for any other geometry
+fn endpoints_mut(geom: &mut Geometry) -> Option<(&mut Coord, Option<&mut Coord>)> {
+    match geom {
+        Geometry::Line(l) => Some((&mut l.start, Some(&mut l.end))),
+        Geometry::LineString(ls) => match ls.0.as_mut_slice() {
+            [] => None,
+            [only] => Some((only, None)),
+            [first, .., last] => Some((first, Some(last))),
+        },
+        _ => None,
+    }
+}
+
+/// A hashable key for the exact coordinate
+fn coord_key(coord: Coord) -> [u64; 2] {
+    [coord.x.to_bits(), coord.y.to_bits()]
+}
+
+/// Cluster the coordinates so that each is mapped to the first coordinate within `tolerance` of it
+///
+/// Each coordinate is either the representative of its own cluster, or is snapped to the
+/// representative of an earlier cluster, so that both sides of a gap snap to the same place,
+/// rather than swapping places.
+fn cluster_coords(coords: impl Iterator<Item = Coord>, tolerance: f64) -> HashMap<[u64; 2], Coord> {
+    let mut index = KdTree::new(2);
+    let mut unique = Vec::new();
+    let mut seen = HashSet::new();
+    for coord in coords {
+        if seen.insert(coord_key(coord)) {
+            index.add([coord.x, coord.y], unique.len()).unwrap();
+            unique.push(coord);
+        }
+    }
+
+    let mut snapped = HashMap::with_capacity(unique.len());
+    for &coord in &unique {
+        if snapped.contains_key(&coord_key(coord)) {
+            continue;
+        }
+        // The k-d tree measures squared distances
+        let neighbors = index
+            .within(
+                &[coord.x, coord.y],
+                tolerance * tolerance,
+                &squared_euclidean,
+            )
+            .unwrap();
+        for (_, i) in neighbors {
+            snapped.entry(coord_key(unique[*i])).or_insert(coord);
+        }
+    }
+    snapped
+}
+
+fn snap_geoms_endpoints(
+    mut geoms: Vec<Geometry>,
+    tolerance: f64,
+    preserve_dimension: bool,
+) -> Vec<Geometry> {
+    let endpoints: Vec<_> = geoms
+        .iter_mut()
+        .filter_map(endpoints_mut)
+        .flat_map(|(first, last)| std::iter::once(*first).chain(last.map(|c| *c)))
+        .collect();
+    let snapped = cluster_coords(endpoints.into_iter(), tolerance);
+    let snap = |c: &mut Coord| *c = snapped[&coord_key(*c)];
+
+    geoms
+        .into_iter()
+        .map(|geom| {
+            preserving_dimension(geom, preserve_dimension, |mut geom| {
+                if let Some((first, last)) = endpoints_mut(&mut geom) {
+                    snap(first);
+                    if let Some(last) = last {
+                        snap(last);
+                    }
+                    return filter_duplicate_vertices(geom);
+                }
+                geom
+            })
+        })
+        .collect()
+}
+
 fn snap_coord_grid(coord: Coord, tolerance: f64) -> Coord {
     Coord {
         x: snap_f64_grid(coord.x, tolerance),
@@ -199,9 +620,130 @@ fn filter_duplicate_ls(mut ls: LineString, closed: bool) -> Geometry {
     }
 }
 
-pub fn snap_graph<D>(graph: GeometryGraph<D>, strategy: SnappingStrategy) -> GeometryGraph<D>
+/// Remove the vertices where the angle is within `tolerance` degrees of a straight line
+///
+/// Snapping often leaves vertices that are nearly collinear with their neighbors, and no longer add
+/// any shape. The endpoints of LINESTRINGs are always kept, and POLYGON rings keep at least three
+/// vertices.
+pub fn remove_collinear_vertices(geom: Geometry, tolerance: f64) -> Geometry {
+    remove_collinear_impl(geom, tolerance.to_radians())
+}
+
+fn remove_collinear_impl(geom: Geometry, tolerance: f64) -> Geometry {
+    match geom {
+        Geometry::LineString(ls) => Geometry::LineString(remove_collinear_ls(ls, tolerance)),
+        Geometry::Polygon(p) => Geometry::Polygon(remove_collinear_polygon(p, tolerance)),
+        Geometry::MultiLineString(mls) => Geometry::MultiLineString(
+            mls.into_iter()
+                .map(|ls| remove_collinear_ls(ls, tolerance))
+                .collect(),
+        ),
+        Geometry::MultiPolygon(mp) => Geometry::MultiPolygon(
+            mp.into_iter()
+                .map(|p| remove_collinear_polygon(p, tolerance))
+                .collect(),
+        ),
+        Geometry::GeometryCollection(gc) => Geometry::GeometryCollection(
+            gc.into_iter()
+                .map(|g| remove_collinear_impl(g, tolerance))
+                .collect(),
+        ),
+        _ => geom,
+    }
+}
+
+/// Whether the angle at `vertex` is within `tolerance` radians of a straight line
+fn is_collinear(prev: Coord, vertex: Coord, next: Coord, tolerance: f64) -> bool {
+    let a = prev - vertex;
+    let b = next - vertex;
+    let cross = a.x * b.y - a.y * b.x;
+    let dot = a.x * b.x + a.y * b.y;
+    let angle = cross.abs().atan2(dot);
+    std::f64::consts::PI - angle <= tolerance
+}
+
+/// Remove the collinear vertices, comparing against the last kept vertex so that runs of
+/// collinear vertices are removed together
+fn remove_collinear_coords(coords: &[Coord], tolerance: f64) -> Vec<Coord> {
+    let mut kept: Vec<Coord> = Vec::with_capacity(coords.len());
+    for (i, coord) in coords.iter().enumerate() {
+        if let (Some(prev), Some(next)) = (kept.last(), coords.get(i + 1)) {
+            if is_collinear(*prev, *coord, *next, tolerance) {
+                continue;
+            }
+        }
+        kept.push(*coord);
+    }
+    kept
+}
+
+fn remove_collinear_ls(ls: LineString, tolerance: f64) -> LineString {
+    if ls.is_closed() {
+        return remove_collinear_ring(ls, tolerance);
+    }
+    LineString::new(remove_collinear_coords(&ls.0, tolerance))
+}
+
+fn remove_collinear_ring(ring: LineString, tolerance: f64) -> LineString {
+    // A triangle can't lose any vertices
+    if ring.0.len() < 5 {
+        return ring;
+    }
+
+    let mut kept = remove_collinear_coords(&ring.0[..ring.0.len() - 1], tolerance);
+    // The first and last vertices of the open ring are always kept above, so check them against
+    // their neighbors on the other side of the ring's seam
+    let n = kept.len();
+    if n > 3 && is_collinear(kept[n - 2], kept[n - 1], kept[0], tolerance) {
+        kept.pop();
+    }
+    let n = kept.len();
+    if n > 3 && is_collinear(kept[n - 1], kept[0], kept[1], tolerance) {
+        kept.remove(0);
+    }
+    if kept.len() < 3 {
+        return ring;
+    }
+
+    let mut ring = LineString::new(kept);
+    ring.close();
+    ring
+}
+
+fn remove_collinear_polygon(polygon: Polygon, tolerance: f64) -> Polygon {
+    let (exterior, interiors) = polygon.into_inner();
+    Polygon::new(
+        remove_collinear_ring(exterior, tolerance),
+        interiors
+            .into_iter()
+            .map(|i| remove_collinear_ring(i, tolerance))
+            .collect(),
+    )
+}
+
+/// Snap together the nodes of the given graph
+pub fn snap_graph<D>(graph: GeometryGraph<D>, strategy: impl Into<Snapping>) -> GeometryGraph<D>
 where
     D: EdgeType,
+{
+    snap_graph_with(graph, strategy, |_existing, _snapped| {})
+}
+
+/// Snap together the nodes of a graph with arbitrary edge weights
+///
+/// Snapping a node moves its edges to the node it was snapped to. If one of the moved edges
+/// duplicates an edge that already exists, `merge_edges` combines the moved edge's weight into the
+/// existing edge's weight, for example by summing them, or by keeping the max. The node weights are
+/// the node positions, so the node that gets snapped to keeps its own position.
+pub fn snap_graph_with<D, E, F>(
+    graph: Graph<Point, E, D, usize>,
+    strategy: impl Into<Snapping>,
+    mut merge_edges: F,
+) -> Graph<Point, E, D, usize>
+where
+    D: EdgeType,
+    E: Clone,
+    F: FnMut(&mut E, &E),
 {
     // You can't look up a node in a graph by its weight (the coordinate)
     // So we need an auxiliary GraphKdTree index for us to look up NodeIndices from their
@@ -213,25 +755,36 @@ where
         index.add(coords, node_idx).unwrap();
     }
 
-    match strategy {
-        SnappingStrategy::ClosestPoint(tolerance) => {
-            snap_graph_closest_point(graph, &mut index, tolerance)
+    match strategy.into() {
+        Snapping::Vertex(SnappingStrategy::ClosestPoint(tolerance)) => {
+            snap_graph_closest_point(graph, &mut index, tolerance, &mut merge_edges)
+        }
+        Snapping::Vertex(SnappingStrategy::RegularGrid(tolerance)) => {
+            snap_graph_grid(graph, tolerance, &mut merge_edges)
+        }
+        Snapping::Collection(CollectionSnappingStrategy::VertexToEdge(tolerance)) => {
+            snap_graph_to_edges(graph, tolerance, &mut merge_edges)
+        }
+        Snapping::Collection(CollectionSnappingStrategy::EndpointsOnly(tolerance)) => {
+            snap_graph_endpoints(graph, tolerance, &mut merge_edges)
         }
-        SnappingStrategy::RegularGrid(tolerance) => snap_graph_grid(graph, tolerance),
     }
 }
 
-fn snap_graph_closest_point<D>(
-    mut graph: GeometryGraph<D>,
+fn snap_graph_closest_point<D, E, F>(
+    mut graph: Graph<Point, E, D, usize>,
     index: &mut GraphKdTree,
     tolerance: f64,
-) -> GeometryGraph<D>
+    merge_edges: &mut F,
+) -> Graph<Point, E, D, usize>
 where
     D: EdgeType,
+    E: Clone,
+    F: FnMut(&mut E, &E),
 {
     let mut nodes_to_remove = Vec::new();
     for node in graph.node_indices() {
-        if let Some(snapped) = snap_graph_node(&mut graph, node, index, tolerance) {
+        if let Some(snapped) = snap_graph_node(&mut graph, node, index, tolerance, merge_edges) {
             nodes_to_remove.push(snapped);
         }
     }
@@ -246,18 +799,21 @@ where
     graph
 }
 
-fn snap_graph_node<D>(
-    graph: &mut GeometryGraph<D>,
+fn snap_graph_node<D, E, F>(
+    graph: &mut Graph<Point, E, D, usize>,
     node_idx: NodeIndex<usize>,
     index: &mut GraphKdTree,
     tolerance: f64,
+    merge_edges: &mut F,
 ) -> Option<NodeIndex<usize>>
 where
     D: EdgeType,
+    E: Clone,
+    F: FnMut(&mut E, &E),
 {
     let coords = [graph[node_idx].0.x, graph[node_idx].0.y];
     let nearest_coords = index
-        .within(&coords, tolerance, &squared_euclidean)
+        .within(&coords, tolerance * tolerance, &squared_euclidean)
         .unwrap();
     debug_assert!(
         !nearest_coords.is_empty(),
@@ -278,27 +834,38 @@ where
 
     index.remove(&coords, &node_idx).unwrap();
 
-    snap_graph_nodes(graph, node_idx, found_idx);
+    snap_graph_nodes(graph, node_idx, found_idx, merge_edges);
     Some(node_idx)
 }
 
-fn snap_graph_nodes<D>(
-    graph: &mut GeometryGraph<D>,
+fn snap_graph_nodes<D, E, F>(
+    graph: &mut Graph<Point, E, D, usize>,
     snap_from: NodeIndex<usize>,
     snap_to: NodeIndex<usize>,
+    merge_edges: &mut F,
 ) where
     D: EdgeType,
+    E: Clone,
+    F: FnMut(&mut E, &E),
 {
     if snap_from == snap_to || graph[snap_from] == graph[snap_to] {
         return;
     }
 
-    let neighbors: Vec<_> = graph.neighbors(snap_from).collect();
+    let edges: Vec<_> = graph
+        .edges(snap_from)
+        .map(|edge| (edge.target(), edge.weight().clone()))
+        .collect();
     let mut neighbors_to_snap = Vec::new();
-    for neighbor in neighbors {
+    for (neighbor, weight) in edges {
         // don't add a self-edge
         if neighbor != snap_to {
-            graph.update_edge(snap_to, neighbor, ());
+            match graph.find_edge(snap_to, neighbor) {
+                Some(existing) => merge_edges(&mut graph[existing], &weight),
+                None => {
+                    graph.add_edge(snap_to, neighbor, weight);
+                }
+            }
             if graph[neighbor].0 == graph[snap_from].0 {
                 neighbors_to_snap.push(neighbor);
             }
@@ -307,13 +874,19 @@ fn snap_graph_nodes<D>(
     graph[snap_from] = graph[snap_to];
 
     for neighbor in neighbors_to_snap {
-        snap_graph_nodes(graph, neighbor, snap_to);
+        snap_graph_nodes(graph, neighbor, snap_to, merge_edges);
     }
 }
 
-fn snap_graph_grid<D>(mut graph: GeometryGraph<D>, tolerance: f64) -> GeometryGraph<D>
+fn snap_graph_grid<D, E, F>(
+    mut graph: Graph<Point, E, D, usize>,
+    tolerance: f64,
+    merge_edges: &mut F,
+) -> Graph<Point, E, D, usize>
 where
     D: EdgeType,
+    E: Clone,
+    F: FnMut(&mut E, &E),
 {
     let mut index = GraphKdTree::new(2);
     let mut nodes_to_remove = Vec::new();
@@ -325,7 +898,11 @@ where
         let mut already_snapped = None;
         let snapped_coords = [snapped_coord.x, snapped_coord.y];
         let nearest = index
-            .within(&snapped_coords, tolerance / 2.0, &squared_euclidean)
+            .within(
+                &snapped_coords,
+                0.25 * tolerance * tolerance,
+                &squared_euclidean,
+            )
             .unwrap();
         for (_distance, node_idx) in nearest {
             if graph[*node_idx].0 == snapped_coord {
@@ -335,7 +912,7 @@ where
 
         if let Some(already_snapped) = already_snapped {
             nodes_to_remove.push(node_idx);
-            snap_graph_nodes(&mut graph, node_idx, *already_snapped);
+            snap_graph_nodes(&mut graph, node_idx, *already_snapped, merge_edges);
         } else {
             index.add(snapped_coords, node_idx).unwrap();
             graph[node_idx].0 = snapped_coord;
@@ -351,6 +928,146 @@ where
     graph
 }
 
+fn snap_graph_to_edges<D, E, F>(
+    mut graph: Graph<Point, E, D, usize>,
+    tolerance: f64,
+    merge_edges: &mut F,
+) -> Graph<Point, E, D, usize>
+where
+    D: EdgeType,
+    E: Clone,
+    F: FnMut(&mut E, &E),
+{
+    let segments = graph
+        .edge_references()
+        .map(|edge| {
+            let line = Line::new(graph[edge.source()].0, graph[edge.target()].0);
+            (line, (edge.source(), edge.target()))
+        })
+        .collect();
+    let index = SegmentIndex::new(segments);
+
+    // Find where each node snaps to before modifying the graph. Nodes that snap onto an edge's
+    // interior split the edge, and nodes that snap onto an edge's endpoint are merged with it.
+    let mut splits: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    let mut merges = Vec::new();
+    for node in graph.node_indices() {
+        // Don't snap a node onto its own edges
+        let is_incident = |_: &Line, &(source, target): &(NodeIndex<usize>, NodeIndex<usize>)| {
+            source == node || target == node
+        };
+        let closest = index.closest(graph[node].0, tolerance, is_incident);
+        if let Some((snapped, t, &(source, target))) = closest {
+            if t == 0.0 {
+                merges.push((node, source));
+            } else if t == 1.0 {
+                merges.push((node, target));
+            } else {
+                splits
+                    .entry((source, target))
+                    .or_default()
+                    .push((t, node, snapped));
+            }
+        }
+    }
+
+    for ((source, target), mut nodes) in splits {
+        let Some(edge) = graph.find_edge(source, target) else {
+            continue;
+        };
+        let weight = graph.remove_edge(edge).unwrap();
+        nodes.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut previous = source;
+        let chain = nodes
+            .into_iter()
+            .map(|(_, node, snapped)| (node, Some(snapped)));
+        for (node, snapped) in chain.chain(std::iter::once((target, None))) {
+            if let Some(snapped) = snapped {
+                graph[node] = Point(snapped);
+            }
+            match graph.find_edge(previous, node) {
+                Some(existing) => merge_edges(&mut graph[existing], &weight),
+                None => {
+                    graph.add_edge(previous, node, weight.clone());
+                }
+            }
+            previous = node;
+        }
+    }
+
+    let mut nodes_to_remove = Vec::new();
+    for (node, snap_to) in merges {
+        // Merging coincident nodes is left to the ClosestPoint strategy
+        if nodes_to_remove.contains(&snap_to) || graph[node] == graph[snap_to] {
+            continue;
+        }
+        snap_graph_nodes(&mut graph, node, snap_to, merge_edges);
+        nodes_to_remove.push(node);
+    }
+
+    // Removing nodes invalidates any existing indices >= the removed index, so remove nodes from
+    // greater to smaller, so that smaller indices aren't invalidated by the removal
+    nodes_to_remove.sort_unstable();
+    for node_idx in nodes_to_remove.into_iter().rev() {
+        graph.remove_node(node_idx);
+    }
+    graph
+}
+
+fn snap_graph_endpoints<D, E, F>(
+    mut graph: Graph<Point, E, D, usize>,
+    tolerance: f64,
+    merge_edges: &mut F,
+) -> Graph<Point, E, D, usize>
+where
+    D: EdgeType,
+    E: Clone,
+    F: FnMut(&mut E, &E),
+{
+    let endpoints: Vec<_> = graph
+        .node_indices()
+        .filter(|n| graph.neighbors_undirected(*n).count() == 1)
+        .collect();
+    let mut index = GraphKdTree::new(2);
+    for &node in &endpoints {
+        index.add([graph[node].x(), graph[node].y()], node).unwrap();
+    }
+
+    // Like cluster_coords(), each endpoint is merged into the first endpoint within the tolerance
+    let mut visited = HashSet::new();
+    let mut nodes_to_remove = Vec::new();
+    for &node in &endpoints {
+        if !visited.insert(node) {
+            continue;
+        }
+        let coords = [graph[node].x(), graph[node].y()];
+        // The k-d tree measures squared distances
+        let neighbors: Vec<_> = index
+            .within(&coords, tolerance * tolerance, &squared_euclidean)
+            .unwrap()
+            .into_iter()
+            .map(|(_, n)| *n)
+            .collect();
+        for neighbor in neighbors {
+            // Merging coincident nodes is left to the ClosestPoint strategy
+            if !visited.insert(neighbor) || graph[neighbor] == graph[node] {
+                continue;
+            }
+            snap_graph_nodes(&mut graph, neighbor, node, merge_edges);
+            nodes_to_remove.push(neighbor);
+        }
+    }
+
+    // Removing nodes invalidates any existing indices >= the removed index, so remove nodes from
+    // greater to smaller, so that smaller indices aren't invalidated by the removal
+    nodes_to_remove.sort_unstable();
+    for node_idx in nodes_to_remove.into_iter().rev() {
+        graph.remove_node(node_idx);
+    }
+    graph
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::BufWriter;
@@ -360,7 +1077,7 @@ mod tests {
     use petgraph::Undirected;
 
     use super::*;
-    use crate::io::{read_tgf_graph, write_tgf_graph};
+    use crate::io::{read_tgf_graph, read_wkt_geometries, write_tgf_graph};
 
     fn get_tgf<D>(graph: &GeometryGraph<D>) -> String
     where
@@ -418,6 +1135,57 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_tolerance_from_str() {
+        assert_eq!("auto".parse(), Ok(Tolerance::Auto));
+        assert_eq!(" AUTO".parse(), Ok(Tolerance::Auto));
+        assert_eq!("0.5".parse(), Ok(Tolerance::Fixed(0.5)));
+        assert!("garbage".parse::<Tolerance>().is_err());
+
+        for tolerance in [Tolerance::Auto, Tolerance::Fixed(0.001)] {
+            assert_eq!(tolerance.to_string().parse(), Ok(tolerance));
+        }
+    }
+
+    #[test]
+    fn test_estimate_tolerance() {
+        // A unit grid of vertices, with one near-duplicate, and the closing vertex of the ring
+        let geoms = [
+            Geometry::Polygon(Polygon::new(
+                LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]),
+                vec![],
+            )),
+            Geometry::LineString(LineString::from(vec![(2.0, 0.0), (2.0, 1.0), (2.01, 1.0)])),
+        ];
+        assert_approx_eq!(f64, estimate_tolerance(&geoms), 0.1);
+
+        let graph: GeometryGraph<Undirected> =
+            read_tgf_graph(&b"0\tPOINT(0 0)\n1\tPOINT(0 2)\n2\tPOINT(0 4)\n#\n"[..]);
+        assert_approx_eq!(f64, estimate_graph_tolerance(&graph), 0.2);
+
+        assert_eq!(estimate_tolerance(&[]), 0.0);
+        let point = [Geometry::Point(Point::new(1.0, 1.0))];
+        assert_eq!(estimate_tolerance(&point), 0.0);
+    }
+
+    #[test]
+    fn test_auto_tolerance_leaves_a_regular_grid_unchanged() {
+        let wkt = b"LINESTRING(0 0, 0.01 0, 0.02 0)\n\
+                    LINESTRING(0 0.01, 0.01 0.01, 0.02 0.01)\n\
+                    LINESTRING(0 0.02, 0.01 0.02, 0.02 0.02)";
+        let geoms: Vec<_> = read_wkt_geometries(&wkt[..]).collect();
+        let tolerance = estimate_tolerance(&geoms);
+        assert_approx_eq!(f64, tolerance, 0.001);
+
+        let snapped: Vec<_> = snap_geoms(
+            geoms.clone().into_iter(),
+            SnappingStrategy::ClosestPoint(tolerance),
+            false,
+        )
+        .collect();
+        assert_eq!(snapped, geoms);
+    }
+
     #[test]
     fn test_snap_two_points() {
         let geoms = [
@@ -431,8 +1199,12 @@ mod tests {
             Geometry::Point(Point::new(0.5, 0.0)),
         ];
 
-        let actual: Vec<_> =
-            snap_geoms(geoms.into_iter(), SnappingStrategy::ClosestPoint(0.6)).collect();
+        let actual: Vec<_> = snap_geoms(
+            geoms.into_iter(),
+            SnappingStrategy::ClosestPoint(0.6),
+            false,
+        )
+        .collect();
         assert_eq!(actual, expected);
     }
 
@@ -447,11 +1219,90 @@ mod tests {
             Coord { x: 0.1, y: 0.0 },
             Coord { x: 1.0, y: 0.0 },
         ))];
-        let actual: Vec<_> =
-            snap_geoms(geoms.into_iter(), SnappingStrategy::ClosestPoint(0.5)).collect();
+        let actual: Vec<_> = snap_geoms(
+            geoms.into_iter(),
+            SnappingStrategy::ClosestPoint(0.5),
+            false,
+        )
+        .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_snapping_collapses_dimension() {
+        let wkt = b"LINESTRING(0 0, 0.1 0)\nPOLYGON((0 0, 0.1 0, 0.1 0.1, 0 0.1, 0 0))";
+        let geoms = read_wkt_geometries(&wkt[..]);
+        let expected = [
+            Geometry::Point(Point::new(0.0, 0.0)),
+            Geometry::Point(Point::new(0.0, 0.0)),
+        ];
+
+        let actual: Vec<_> = snap_geoms(geoms, SnappingStrategy::RegularGrid(1.0), false).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_snapping_preserves_dimension() {
+        let wkt = b"LINESTRING(0 0, 0.1 0)\nPOLYGON((0 0, 0.1 0, 0.1 0.1, 0 0.1, 0 0))";
+        let expected: Vec<_> = read_wkt_geometries(&wkt[..]).collect();
+
+        let geoms = read_wkt_geometries(&wkt[..]);
+        let actual: Vec<_> = snap_geoms(geoms, SnappingStrategy::RegularGrid(1.0), true).collect();
+        assert_eq!(actual, expected);
+
+        let wkt = b"LINESTRING(0 0, 0.1 0)";
+        let expected: Vec<_> = read_wkt_geometries(&wkt[..]).collect();
+        let geoms = read_wkt_geometries(&wkt[..]);
+        let actual: Vec<_> = snap_geoms(geoms, SnappingStrategy::ClosestPoint(0.2), true).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_preserve_dimension_still_snaps() {
+        let wkt = b"POLYGON((0 0, 2.1 0, 2.1 1.9, 0 0))";
+        let geoms = read_wkt_geometries(&wkt[..]);
+        let expected = [Geometry::Polygon(Polygon::new(
+            LineString::from(vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 0.0)]),
+            vec![],
+        ))];
+
+        let actual: Vec<_> = snap_geoms(geoms, SnappingStrategy::RegularGrid(1.0), true).collect();
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_remove_collinear_vertices() {
+        let wkt = b"LINESTRING(0 0, 1 0, 2 0)";
+        let geom = read_wkt_geometries(&wkt[..]).next().unwrap();
+        let expected = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (2.0, 0.0)]));
+        assert_eq!(remove_collinear_vertices(geom, 0.0), expected);
+
+        // The angle at (1, 0.01) is about 1.15 degrees away from a straight line
+        let wkt = b"LINESTRING(0 0, 1 0.01, 2 0)";
+        let geom = read_wkt_geometries(&wkt[..]).next().unwrap();
+        assert_eq!(remove_collinear_vertices(geom.clone(), 1.0), geom);
+        assert_eq!(remove_collinear_vertices(geom, 2.0), expected);
+    }
+
+    #[test]
+    fn test_remove_collinear_ring_vertices() {
+        // Both the vertex on the bottom edge, and the starting vertex in the middle of the left
+        // edge are collinear
+        let wkt = b"POLYGON((0 1, 0 0, 1 0, 2 0, 2 2, 0 2, 0 1))";
+        let geom = read_wkt_geometries(&wkt[..]).next().unwrap();
+        let expected = Geometry::Polygon(Polygon::new(
+            LineString::from(vec![
+                (0.0, 0.0),
+                (2.0, 0.0),
+                (2.0, 2.0),
+                (0.0, 2.0),
+                (0.0, 0.0),
+            ]),
+            vec![],
+        ));
+        assert_eq!(remove_collinear_vertices(geom, 0.1), expected);
+    }
+
     #[test]
     fn test_snap_graph_grid() {
         let tgf = b"1\tPOINT(0 0)\n42\tPOINT(0.1 0)\n69\tPOINT(2 0)\n#\n1\t42\n42\t69";
@@ -494,8 +1345,139 @@ mod tests {
         let tgf = b"0\tPOINT(2 0)\n1\tPOINT(0 -0.1)\n#\n1\t0\n";
         let expected_tgf = String::from_utf8_lossy(tgf);
 
-        let actual = snap_graph(graph, SnappingStrategy::ClosestPoint(0.11));
+        let actual = snap_graph(graph, SnappingStrategy::ClosestPoint(0.33));
+
+        let actual_tgf = get_tgf(&actual);
+        assert_eq!(actual_tgf, expected_tgf);
+    }
+
+    #[test]
+    fn test_tolerance_is_a_distance_for_every_strategy() {
+        // Two lines whose ends are 0.5 apart
+        let wkt = b"LINESTRING(0 0, 1 0)\nLINESTRING(1.5 0, 2.5 0)";
+        let strategies: [fn(f64) -> Snapping; 3] = [
+            |t| SnappingStrategy::ClosestPoint(t).into(),
+            |t| CollectionSnappingStrategy::VertexToEdge(t).into(),
+            |t| CollectionSnappingStrategy::EndpointsOnly(t).into(),
+        ];
+        let original: Vec<_> = read_wkt_geometries(&wkt[..])
+            .flat_map(|g| g.coords_iter().collect::<Vec<_>>())
+            .collect();
+        let snapped_coords = |strategy: Snapping| -> Vec<_> {
+            snap_geoms(read_wkt_geometries(&wkt[..]), strategy, false)
+                .flat_map(|g| g.coords_iter().collect::<Vec<_>>())
+                .collect()
+        };
+        for strategy in strategies {
+            assert_eq!(snapped_coords(strategy(0.4)), original);
+            assert_ne!(snapped_coords(strategy(0.6)), original);
+        }
+    }
+
+    #[test]
+    fn test_snap_graph_merges_edge_weights() {
+        // Two nearby nodes, both connected to a third
+        let mut graph = Graph::<Point, f64, Undirected, usize>::default();
+        let a = graph.add_node(Point::new(0.0, 0.0));
+        let b = graph.add_node(Point::new(0.05, 0.0));
+        let c = graph.add_node(Point::new(2.0, 0.0));
+        graph.add_edge(a, c, 1.0);
+        graph.add_edge(b, c, 2.0);
+        graph.add_edge(a, b, 4.0);
+
+        let actual = snap_graph_with(graph, SnappingStrategy::ClosestPoint(0.1), |w, s| *w += s);
+        assert_eq!(actual.node_count(), 2);
+        assert_eq!(actual.edge_count(), 1);
+        assert_eq!(actual.edge_weights().copied().collect::<Vec<_>>(), [3.0]);
+    }
+
+    #[test]
+    fn test_snap_vertex_to_edge() {
+        // A T-junction whose stem ends just shy of the crossbar
+        let wkt = b"LINESTRING(0 0, 2 0)\nLINESTRING(1 0.05, 1 1)";
+        let geoms = read_wkt_geometries(&wkt[..]);
+        let expected = [
+            Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)])),
+            Geometry::Line(Line::new((1.0, 0.0), (1.0, 1.0))),
+        ];
+
+        let actual: Vec<_> =
+            snap_geoms(geoms, CollectionSnappingStrategy::VertexToEdge(0.1), false).collect();
+        assert_eq!(actual, expected);
+
+        // Nothing is close enough to snap, but two-vertex LINESTRINGs are still converted to LINEs
+        let geoms = read_wkt_geometries(&wkt[..]);
+        let expected = [
+            Geometry::Line(Line::new((0.0, 0.0), (2.0, 0.0))),
+            Geometry::Line(Line::new((1.0, 0.05), (1.0, 1.0))),
+        ];
+        let actual: Vec<_> =
+            snap_geoms(geoms, CollectionSnappingStrategy::VertexToEdge(0.01), false).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_snap_graph_vertex_to_edge() {
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(2 0)\n2\tPOINT(1 0.05)\n3\tPOINT(1 1)\n#\n0\t1\n2\t3\n";
+        let graph = read_tgf_graph::<Undirected, _>(&tgf[..]);
+
+        let tgf =
+            b"0\tPOINT(0 0)\n1\tPOINT(2 0)\n2\tPOINT(1 0)\n3\tPOINT(1 1)\n#\n2\t3\n0\t2\n2\t1\n";
+        let expected_tgf = String::from_utf8_lossy(tgf);
+
+        let actual = snap_graph(graph, CollectionSnappingStrategy::VertexToEdge(0.1));
+
+        let actual_tgf = get_tgf(&actual);
+        assert_eq!(actual_tgf, expected_tgf);
+    }
+
+    #[test]
+    fn test_snap_endpoints_only() {
+        // Two lines whose endpoints nearly meet, and whose interior vertices are close enough to
+        // snap together, but shouldn't be
+        let wkt = b"LINESTRING(0 0, 1 0.05, 2 0)\nLINESTRING(2.05 0.05, 1 0.1, 0 1)\nPOINT(2 0.01)";
+        let geoms = read_wkt_geometries(&wkt[..]);
+        let expected = [
+            Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 0.05), (2.0, 0.0)])),
+            Geometry::LineString(LineString::from(vec![(2.0, 0.0), (1.0, 0.1), (0.0, 1.0)])),
+            Geometry::Point(Point::new(2.0, 0.01)),
+        ];
+
+        let actual: Vec<_> =
+            snap_geoms(geoms, CollectionSnappingStrategy::EndpointsOnly(0.1), false).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_snap_endpoints_collapses_short_lines() {
+        let wkt = b"LINESTRING(0 0, 0.05 0)";
+        let geoms = read_wkt_geometries(&wkt[..]);
+        let actual: Vec<_> =
+            snap_geoms(geoms, CollectionSnappingStrategy::EndpointsOnly(0.1), false).collect();
+        assert_eq!(actual, [Geometry::Point(Point::new(0.0, 0.0))]);
+
+        let geoms = read_wkt_geometries(&wkt[..]);
+        let actual: Vec<_> =
+            snap_geoms(geoms, CollectionSnappingStrategy::EndpointsOnly(0.1), true).collect();
+        let expected = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (0.05, 0.0)]));
+        assert_eq!(actual, [expected]);
+    }
+
+    #[test]
+    fn test_snap_graph_endpoints_only() {
+        // Two paths whose ends nearly meet at (2, 0), and an interior node 1 near the other path
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0.05)\n2\tPOINT(2 0)\n\
+                    3\tPOINT(2.05 0.05)\n4\tPOINT(1 0.1)\n5\tPOINT(0 1)\n\
+                    #\n0\t1\n1\t2\n3\t4\n4\t5\n";
+        let graph = read_tgf_graph::<Undirected, _>(&tgf[..]);
+
+        // Removing node 3 moves the last node into its place
+        let tgf = b"0\tPOINT(0 0)\n1\tPOINT(1 0.05)\n2\tPOINT(2 0)\n\
+                    3\tPOINT(0 1)\n4\tPOINT(1 0.1)\n\
+                    #\n0\t1\n1\t2\n2\t4\n4\t3\n";
+        let expected_tgf = String::from_utf8_lossy(tgf);
 
+        let actual = snap_graph(graph, CollectionSnappingStrategy::EndpointsOnly(0.1));
         let actual_tgf = get_tgf(&actual);
         assert_eq!(actual_tgf, expected_tgf);
     }
@@ -0,0 +1,1194 @@
+use std::str::FromStr;
+
+use ::svg::node::{element, Comment, Text};
+use ::svg::{Document, Node};
+use geo::{
+    AffineOps, AffineTransform, BoundingRect, Centroid, Coord, CoordsIter, Geometry, Line,
+    LineString, MapCoords, Point, Polygon, Rect, Triangle,
+};
+
+use crate::flatten::flatten_nested_geometries;
+use crate::io::{GeometryAndStyle, SvgStyle};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScaleType {
+    Identity,
+    ScaleAndExpandViewBoxToFit(f64),
+    FitToViewBox(Rect),
+}
+
+/// A gradient that can be referenced by id from a fill or stroke, like `FILL(url(#id))`
+///
+/// The gradient coordinates are fractions of the bounding box of each geometry that uses it, with
+/// the same orientation as the rendered geometries. Parsed from strings like
+/// `id:linear:x1,y1:x2,y2:color,color,...` or `id:radial:cx,cy:r:color,color,...`, with the colors
+/// spaced evenly along the gradient.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gradient {
+    Linear {
+        id: String,
+        start: Coord,
+        end: Coord,
+        colors: Vec<String>,
+    },
+    Radial {
+        id: String,
+        center: Coord,
+        radius: f64,
+        colors: Vec<String>,
+    },
+}
+
+fn parse_coord(s: &str) -> Result<Coord, String> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| format!("Expected 'x,y', got '{s}'"))?;
+    let x = x
+        .trim()
+        .parse()
+        .map_err(|e| format!("Bad x in '{s}': {e}"))?;
+    let y = y
+        .trim()
+        .parse()
+        .map_err(|e| format!("Bad y in '{s}': {e}"))?;
+    Ok(Coord { x, y })
+}
+
+impl FromStr for Gradient {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.split(':').map(str::trim).collect();
+        let [id, kind, a, b, colors] = parts.as_slice() else {
+            return Err(format!(
+                "Expected 'id:linear:x1,y1:x2,y2:colors' or 'id:radial:cx,cy:r:colors', got '{s}'"
+            ));
+        };
+        let id = id.to_string();
+        let colors: Vec<String> = colors.split(',').map(|c| c.trim().to_string()).collect();
+        if colors.len() < 2 {
+            return Err(format!("Gradient '{id}' needs at least two colors"));
+        }
+
+        match kind.to_lowercase().as_str() {
+            "linear" => Ok(Gradient::Linear {
+                id,
+                start: parse_coord(a)?,
+                end: parse_coord(b)?,
+                colors,
+            }),
+            "radial" => Ok(Gradient::Radial {
+                id,
+                center: parse_coord(a)?,
+                radius: b.parse().map_err(|e| format!("Bad radius in '{s}': {e}"))?,
+                colors,
+            }),
+            _ => Err(format!(
+                "Unknown gradient type '{kind}'; expected linear or radial"
+            )),
+        }
+    }
+}
+
+impl Gradient {
+    /// Convert to an SVG gradient element, mirroring the y coordinates if necessary to match the
+    /// geometries
+    fn to_element(&self, flip_y: bool) -> Box<dyn ::svg::Node> {
+        let y = |y: f64| if flip_y { 1.0 - y } else { y };
+        let stops = |colors: &[String]| {
+            let last = (colors.len() - 1) as f64;
+            colors
+                .iter()
+                .enumerate()
+                .map(|(i, color)| {
+                    element::Stop::new()
+                        .set("offset", i as f64 / last)
+                        .set("stop-color", color.as_str())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        match self {
+            Gradient::Linear {
+                id,
+                start,
+                end,
+                colors,
+            } => {
+                let mut gradient = element::LinearGradient::new()
+                    .set("id", id.as_str())
+                    .set("x1", start.x)
+                    .set("y1", y(start.y))
+                    .set("x2", end.x)
+                    .set("y2", y(end.y));
+                for stop in stops(colors) {
+                    gradient = gradient.add(stop);
+                }
+                Box::new(gradient)
+            }
+            Gradient::Radial {
+                id,
+                center,
+                radius,
+                colors,
+            } => {
+                let mut gradient = element::RadialGradient::new()
+                    .set("id", id.as_str())
+                    .set("cx", center.x)
+                    .set("cy", y(center.y))
+                    .set("r", *radius);
+                for stop in stops(colors) {
+                    gradient = gradient.add(stop);
+                }
+                Box::new(gradient)
+            }
+        }
+    }
+}
+
+/// A colormap for turning values between 0 and 1 into colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Colormap {
+    /// Perceptually uniform dark purple to yellow
+    Viridis,
+    /// Perceptually uniform black to pale yellow, through red
+    Magma,
+    /// Black to white
+    Grayscale,
+}
+
+impl std::fmt::Display for Colormap {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            // important: Should match clap::ValueEnum format
+            Colormap::Viridis => write!(f, "viridis"),
+            Colormap::Magma => write!(f, "magma"),
+            Colormap::Grayscale => write!(f, "grayscale"),
+        }
+    }
+}
+
+impl Colormap {
+    /// Evenly spaced colors to interpolate between
+    fn stops(&self) -> &'static [[u8; 3]] {
+        match self {
+            Colormap::Viridis => &[
+                [0x44, 0x01, 0x54],
+                [0x3b, 0x52, 0x8b],
+                [0x21, 0x91, 0x8c],
+                [0x5e, 0xc9, 0x62],
+                [0xfd, 0xe7, 0x25],
+            ],
+            Colormap::Magma => &[
+                [0x00, 0x00, 0x04],
+                [0x51, 0x12, 0x7c],
+                [0xb7, 0x37, 0x79],
+                [0xfc, 0x89, 0x61],
+                [0xfc, 0xfd, 0xbf],
+            ],
+            Colormap::Grayscale => &[[0x00, 0x00, 0x00], [0xff, 0xff, 0xff]],
+        }
+    }
+
+    /// Map the value to a `#rrggbb` color, clamping it between 0 and 1
+    pub fn color(&self, value: f64) -> String {
+        let stops = self.stops();
+        let position = value.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+        let i = (position.floor() as usize).min(stops.len() - 2);
+        let t = position - i as f64;
+
+        let [r, g, b] =
+            std::array::from_fn(|c| stops[i][c] as f64 * (1.0 - t) + stops[i + 1][c] as f64 * t);
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            r.round() as u8,
+            g.round() as u8,
+            b.round() as u8
+        )
+    }
+}
+
+/// Render geometries to an SVG document
+///
+/// Geometries may be interleaved with [SvgStyle]s, which override the global style for every
+/// geometry that follows them.
+///
+/// ```
+/// use generative::svg::SvgRenderer;
+/// use geo::{Geometry, Point};
+///
+/// let points = [Geometry::Point(Point::new(0.0, 0.0)), Geometry::Point(Point::new(1.0, 1.0))];
+/// let document = SvgRenderer::new()
+///     .scale(10.0)
+///     .stroke("red")
+///     .render(points.into_iter().map(|g| g.into()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SvgRenderer {
+    scale_type: ScaleType,
+    screen_coordinates: bool,
+    padding: bool,
+    scale_stroke: bool,
+    point_radius: f64,
+    stroke: String,
+    stroke_width: f64,
+    stroke_dasharray: Option<String>,
+    dash_cycle: Vec<String>,
+    fill: String,
+    gradients: Vec<Gradient>,
+    comment: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    group_by_type: bool,
+    label_size: f64,
+    label_offset: Coord,
+    precision: Option<usize>,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> Self {
+        Self {
+            scale_type: ScaleType::Identity,
+            screen_coordinates: false,
+            padding: true,
+            scale_stroke: false,
+            point_radius: 1.0,
+            stroke: String::from("black"),
+            stroke_width: 2.0,
+            stroke_dasharray: None,
+            dash_cycle: Vec::new(),
+            fill: String::from("none"),
+            gradients: Vec::new(),
+            comment: None,
+            title: None,
+            description: None,
+            group_by_type: false,
+            label_size: 12.0,
+            label_offset: Coord { x: 2.0, y: 2.0 },
+            precision: None,
+        }
+    }
+}
+
+/// The per-geometry style overrides, as set by any [SvgStyle]s interleaved with the geometries
+#[derive(Debug, Default)]
+struct StyleOverrides {
+    point_radius: Option<f64>,
+    stroke: Option<String>,
+    stroke_width: Option<f64>,
+    stroke_dasharray: Option<String>,
+    stroke_dashoffset: Option<f64>,
+    fill: Option<String>,
+    /// The dash pattern from the renderer's dash cycle for the current geometry
+    cycled_dasharray: Option<String>,
+    /// The [SvgStyle::Label] for the next geometry, which is cleared once it's drawn
+    label: Option<String>,
+}
+
+impl StyleOverrides {
+    /// The dash pattern to use, preferring any [SvgStyle::StrokeDasharray] over the dash cycle
+    fn dasharray(&self) -> Option<&String> {
+        self.stroke_dasharray
+            .as_ref()
+            .or(self.cycled_dasharray.as_ref())
+    }
+}
+
+impl SvgRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scale the geometries the specified amount, and then scale the viewbox to fit
+    ///
+    /// Overrides any previously set [viewbox](Self::viewbox)
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale_type = ScaleType::ScaleAndExpandViewBoxToFit(scale);
+        self
+    }
+
+    /// Scale the geometries to fit the given viewbox
+    ///
+    /// Overrides any previously set [scale](Self::scale)
+    pub fn viewbox(mut self, viewbox: Rect) -> Self {
+        self.scale_type = ScaleType::FitToViewBox(viewbox);
+        self
+    }
+
+    /// Use screen coordinates instead of Cartesian (y increases downwards)
+    pub fn screen_coordinates(mut self, screen_coordinates: bool) -> Self {
+        self.screen_coordinates = screen_coordinates;
+        self
+    }
+
+    /// Whether to add a little bit of padding all the way around the viewbox
+    pub fn padding(mut self, padding: bool) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Scale the stroke widths and point radii by the same factor as the geometries
+    ///
+    /// When fitting to a [viewbox](Self::viewbox) with a different aspect ratio than the
+    /// geometries, the smaller of the two axis scales is used.
+    pub fn scale_stroke(mut self, scale_stroke: bool) -> Self {
+        self.scale_stroke = scale_stroke;
+        self
+    }
+
+    pub fn point_radius(mut self, radius: f64) -> Self {
+        self.point_radius = radius;
+        self
+    }
+
+    pub fn stroke(mut self, stroke: impl Into<String>) -> Self {
+        self.stroke = stroke.into();
+        self
+    }
+
+    pub fn stroke_width(mut self, width: f64) -> Self {
+        self.stroke_width = width;
+        self
+    }
+
+    pub fn stroke_dasharray(mut self, dasharray: Option<String>) -> Self {
+        self.stroke_dasharray = dasharray;
+        self
+    }
+
+    /// Cycle through the given dash patterns, one for each geometry drawn
+    ///
+    /// Any STROKEDASHARRAY styles interleaved with the geometries take precedence.
+    pub fn dash_cycle(mut self, dash_cycle: Vec<String>) -> Self {
+        self.dash_cycle = dash_cycle;
+        self
+    }
+
+    pub fn fill(mut self, fill: impl Into<String>) -> Self {
+        self.fill = fill.into();
+        self
+    }
+
+    /// Define gradients that can be referenced by id from a fill or stroke, like
+    /// `FILL(url(#id))`
+    pub fn gradients(mut self, gradients: Vec<Gradient>) -> Self {
+        self.gradients = gradients;
+        self
+    }
+
+    /// Add an XML comment to the top of the document, like the tool that generated it
+    ///
+    /// XML comments can't contain `--`, so any are replaced with `- -`.
+    pub fn comment(mut self, comment: Option<String>) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    /// Add a `<title>` to the document
+    pub fn title(mut self, title: Option<String>) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Add a `<desc>` to the document, like the command line or seed used to generate it
+    pub fn description(mut self, description: Option<String>) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Group the rendered geometries into a `<g>` for each type of geometry
+    ///
+    /// The groups have the classes "polygons", "lines", and "points", and are drawn in that order.
+    /// Styles still apply to the geometries in the order they were given.
+    pub fn group_by_type(mut self, group_by_type: bool) -> Self {
+        self.group_by_type = group_by_type;
+        self
+    }
+
+    /// The font size of any [SvgStyle::Label]s
+    ///
+    /// Scaled along with the stroke widths if [scale_stroke](Self::scale_stroke) is set.
+    pub fn label_size(mut self, size: f64) -> Self {
+        self.label_size = size;
+        self
+    }
+
+    /// How far to offset each [SvgStyle::Label] from its geometry's centroid
+    ///
+    /// The offset is in the same direction as the coordinates (so a positive y offset moves the
+    /// label up, unless using [screen_coordinates](Self::screen_coordinates)), but isn't scaled
+    /// along with them. Labels don't grow the viewbox, so they may be clipped at its edges.
+    pub fn label_offset(mut self, offset: Coord) -> Self {
+        self.label_offset = offset;
+        self
+    }
+
+    /// Round the coordinates of the drawn geometries and labels to this many decimal places
+    ///
+    /// The coordinates are rounded after they're scaled, and rounding them to a few decimal places
+    /// makes the SVG much smaller, since they're written in full precision by default. The viewBox
+    /// isn't rounded, so that it still contains the geometries.
+    pub fn precision(mut self, precision: Option<usize>) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Round the given coordinate value to the [precision](Self::precision), if any
+    fn round(&self, value: f64) -> f64 {
+        match self.precision {
+            // Adding zero turns -0 into 0
+            Some(precision) => {
+                let factor = 10_f64.powi(precision as i32);
+                (value * factor).round() / factor + 0.0
+            }
+            None => value,
+        }
+    }
+
+    /// Render the given geometries and styles to an SVG document
+    ///
+    /// Can't lazily convert to SVG because we have to know the whole collection's bounding box to
+    /// know how to scale.
+    pub fn render<G>(&self, geometries: G) -> Document
+    where
+        G: IntoIterator<Item = GeometryAndStyle>,
+    {
+        let geometries = flatten_geometries_and_styles(geometries.into_iter().collect());
+
+        let Some(bbox) = bounding_box(geometries.iter()) else {
+            log::error!("Failed to calculate geometry bounding box");
+            return Document::new();
+        };
+
+        let (mut transform, mut viewbox, scale) = self.calculate_transform(&bbox);
+        // SVG's y axis points down, so mirror the geometries about the viewbox's horizontal
+        // centerline. This is done to the geometries themselves rather than with a transform on
+        // the <svg> element, which not every SVG renderer supports.
+        if !self.screen_coordinates {
+            let flip = AffineTransform::scale(1.0, -1.0, viewbox.center());
+            transform = Some(transform.map_or(flip, |t| t.compose(&flip)));
+        }
+        let stroke_scale = if self.scale_stroke { scale } else { 1.0 };
+        if self.padding {
+            const PADDING: Coord = Coord { x: 3.0, y: 3.0 };
+            let min = viewbox.min();
+            let new_min = min - PADDING;
+            viewbox.set_min(new_min);
+
+            let max = viewbox.max();
+            let new_max = max + PADDING;
+            viewbox.set_max(new_max);
+        }
+        let min = viewbox.min();
+        let viewbox = (min.x, min.y, viewbox.width(), viewbox.height());
+        log::debug!(
+            "Transforming geometries with: {:?} to fit into viewBox {:?}",
+            transform,
+            viewbox
+        );
+
+        let mut document = Document::new().set("viewBox", viewbox);
+        document = self.add_metadata(document);
+        document = document.add(self.get_global_style(stroke_scale));
+        if !self.gradients.is_empty() {
+            let mut defs = element::Definitions::new();
+            for gradient in &self.gradients {
+                defs = defs.add(gradient.to_element(!self.screen_coordinates));
+            }
+            document = document.add(defs);
+        }
+
+        let mut groups = TYPE_GROUPS.map(|class| element::Group::new().set("class", class));
+        let mut overrides = StyleOverrides::default();
+        let mut dash_cycle = self.dash_cycle.iter().cycle();
+        for geometry in geometries {
+            if !matches!(geometry, GeometryAndStyle::Style(_)) {
+                overrides.cycled_dasharray = dash_cycle.next().cloned();
+            }
+            if self.group_by_type {
+                let group = &mut groups[type_group(&geometry)];
+                self.to_svg(geometry, &transform, stroke_scale, group, &mut overrides);
+            } else {
+                self.to_svg(
+                    geometry,
+                    &transform,
+                    stroke_scale,
+                    &mut document,
+                    &mut overrides,
+                );
+            }
+        }
+        if self.group_by_type {
+            for group in groups {
+                if group.get_children().is_some_and(|c| !c.is_empty()) {
+                    document = document.add(group);
+                }
+            }
+        }
+        document
+    }
+
+    fn add_metadata(&self, mut document: Document) -> Document {
+        if let Some(comment) = &self.comment {
+            // Replace repeatedly, so that '---' doesn't leave a '--' behind
+            let mut comment = comment.clone();
+            while comment.contains("--") {
+                comment = comment.replace("--", "- -");
+            }
+            document = document.add(Comment::new(comment));
+        }
+        if let Some(title) = &self.title {
+            document = document.add(element::Title::new(title.as_str()));
+        }
+        if let Some(description) = &self.description {
+            document = document.add(element::Description::new().add(Text::new(description)));
+        }
+        document
+    }
+
+    fn get_global_style(&self, stroke_scale: f64) -> element::Style {
+        // let style = element::Style::new("svg { stroke:black; stroke-width:2px; fill:none;}");
+
+        let style = if let Some(dasharray) = &self.stroke_dasharray {
+            format!("stroke-dasharray:{dasharray};")
+        } else {
+            String::new()
+        };
+
+        let style = format!(
+            "{} stroke:{}; stroke-width:{}; fill:{};",
+            style,
+            self.stroke,
+            self.stroke_width * stroke_scale,
+            self.fill
+        );
+
+        element::Style::new(format!("svg {{{style}}}"))
+    }
+
+    /// Calculate the transform, the resulting viewbox, and the transform's scale factor
+    fn calculate_transform(&self, bounding_box: &Rect) -> (Option<AffineTransform>, Rect, f64) {
+        match self.scale_type {
+            ScaleType::Identity => (None, *bounding_box, 1.0),
+            ScaleType::ScaleAndExpandViewBoxToFit(scale) => {
+                let center = bounding_box.center();
+                let transform = AffineTransform::scale(scale, scale, center);
+                let viewbox = bounding_box.affine_transform(&transform);
+                (Some(transform), viewbox, scale)
+            }
+            ScaleType::FitToViewBox(viewbox) => {
+                let offset = viewbox.center() - bounding_box.center();
+                let x_scale = viewbox.width() / bounding_box.width();
+                let y_scale = viewbox.height() / bounding_box.height();
+                let center = bounding_box.center();
+
+                let transform =
+                    AffineTransform::translate(offset.x, offset.y).scaled(x_scale, y_scale, center);
+
+                (Some(transform), viewbox, x_scale.min(y_scale))
+            }
+        }
+    }
+
+    fn update_overrides(&self, style: SvgStyle, stroke_scale: f64, overrides: &mut StyleOverrides) {
+        match style {
+            SvgStyle::PointRadius(r) => {
+                if r == self.point_radius {
+                    overrides.point_radius = None;
+                } else {
+                    overrides.point_radius = Some(r * stroke_scale);
+                }
+            }
+            SvgStyle::Stroke(s) => {
+                if s == self.stroke {
+                    overrides.stroke = None;
+                } else {
+                    overrides.stroke = Some(s);
+                }
+            }
+            SvgStyle::StrokeWidth(w) => {
+                if w == self.stroke_width {
+                    overrides.stroke_width = None;
+                } else {
+                    overrides.stroke_width = Some(w * stroke_scale);
+                }
+            }
+            SvgStyle::StrokeDasharray(d) => {
+                if Some(&d) == self.stroke_dasharray.as_ref() || d.is_empty() || d == "NONE" {
+                    overrides.stroke_dasharray = None;
+                } else {
+                    overrides.stroke_dasharray = Some(d);
+                }
+            }
+            SvgStyle::StrokeDashoffset(o) => {
+                if o == 0.0 {
+                    overrides.stroke_dashoffset = None;
+                } else {
+                    overrides.stroke_dashoffset = Some(o);
+                }
+            }
+            SvgStyle::Fill(f) => {
+                if f == self.fill {
+                    overrides.fill = None;
+                } else {
+                    overrides.fill = Some(f);
+                }
+            }
+            SvgStyle::Label(l) => overrides.label = Some(l),
+        }
+    }
+
+    fn add_label(
+        &self,
+        label: String,
+        anchor: Point,
+        stroke_scale: f64,
+        parent: &mut impl Node,
+        overrides: &StyleOverrides,
+    ) {
+        let offset = self.label_offset * stroke_scale;
+        // The geometries have already been flipped, but the offset hasn't
+        let dy = if self.screen_coordinates {
+            offset.y
+        } else {
+            -offset.y
+        };
+        // Text is filled rather than stroked, so draw it in the stroke color
+        let color = overrides.stroke.as_ref().unwrap_or(&self.stroke);
+        let node = element::Text::new(label)
+            .set("x", self.round(anchor.x() + offset.x))
+            .set("y", self.round(anchor.y() + dy))
+            .set("font-size", self.label_size * stroke_scale)
+            .set("stroke", "none")
+            .set("fill", color.clone());
+        parent.append(node);
+    }
+
+    fn to_svg(
+        &self,
+        geometry: GeometryAndStyle,
+        transform: &Option<AffineTransform>,
+        stroke_scale: f64,
+        parent: &mut impl Node,
+        overrides: &mut StyleOverrides,
+    ) {
+        match geometry {
+            GeometryAndStyle::Style(style) => {
+                self.update_overrides(style, stroke_scale, overrides);
+            }
+            _ => {
+                let geometry: Geometry = geometry.into();
+                let mut transformed_geometry = if let Some(transform) = transform {
+                    geometry.affine_transform(transform)
+                } else {
+                    geometry
+                };
+                if self.precision.is_some() {
+                    transformed_geometry = transformed_geometry.map_coords(|c| Coord {
+                        x: self.round(c.x),
+                        y: self.round(c.y),
+                    });
+                }
+                let label = overrides.label.take().and_then(|label| {
+                    let anchor = transformed_geometry.centroid()?;
+                    Some((label, anchor))
+                });
+
+                match transformed_geometry {
+                    Geometry::Point(p) => {
+                        let radius = overrides
+                            .point_radius
+                            .unwrap_or(self.point_radius * stroke_scale);
+                        add_point_to_document(p, radius, parent, overrides)
+                    }
+                    Geometry::Line(l) => add_line_to_document(l, parent, overrides),
+                    Geometry::LineString(l) => add_linestring_to_document(l, parent, overrides),
+                    Geometry::Polygon(p) => add_polygon_to_document(p, parent, overrides),
+                    Geometry::Rect(r) => add_rect_to_document(r, parent, overrides),
+                    Geometry::Triangle(t) => add_triangle_to_document(t, parent, overrides),
+                    _ => unreachable!("MULTI-geometries get flattened before conversion to SVG"),
+                }
+                if let Some((label, anchor)) = label {
+                    self.add_label(label, anchor, stroke_scale, parent, overrides);
+                }
+            }
+        }
+    }
+}
+
+/// The classes of the [SvgRenderer::group_by_type] groups, in the order they're drawn
+const TYPE_GROUPS: [&str; 3] = ["polygons", "lines", "points"];
+
+/// The index of the [TYPE_GROUPS] group the geometry belongs to
+fn type_group(geometry: &GeometryAndStyle) -> usize {
+    match geometry {
+        GeometryAndStyle::Point(_) | GeometryAndStyle::MultiPoint(_) => 2,
+        GeometryAndStyle::Line(_)
+        | GeometryAndStyle::LineString(_)
+        | GeometryAndStyle::MultiLineString(_) => 1,
+        _ => 0,
+    }
+}
+
+/// Flatten any MULTI or GEOMETRYCOLLECTION geometries, while preserving the style ordering
+fn flatten_geometries_and_styles(geometries: Vec<GeometryAndStyle>) -> Vec<GeometryAndStyle> {
+    // [g, g, g, s, g, s, s, g] => [g, g, g, s], [g, s], [s], [g]
+    let geometries_ending_with_styles =
+        geometries.split_inclusive(|x| matches!(x, GeometryAndStyle::Style(_)));
+    let flattened = geometries_ending_with_styles.map(|slice| {
+        if matches!(slice.last(), Some(GeometryAndStyle::Style(_))) {
+            let (style, geometries) = slice.split_last().unwrap();
+            // Have to convert to geo_types to flatten
+            let flattened = flatten_nested_geometries(geometries.iter().map(|g| g.clone().into()));
+            // But then need to convert back to add the style back in
+            let flattened = flattened.map(|g| g.into());
+            let mut flattened: Vec<GeometryAndStyle> = flattened.collect();
+            flattened.push(style.clone());
+            flattened
+        } else {
+            let flattened = flatten_nested_geometries(slice.iter().map(|g| g.clone().into()));
+            let flattened = flattened.map(|g| g.into());
+            flattened.collect()
+        }
+    });
+    flattened.flatten().collect()
+}
+
+/// Set the styles that override the global style on the given SVG element
+///
+/// Every geometry type is styled here, so that each style only has to be handled in one place.
+fn apply_overrides<N: Node>(mut node: N, overrides: &StyleOverrides) -> N {
+    if let Some(stroke) = overrides.stroke.as_ref() {
+        node.assign("stroke", stroke.clone());
+    }
+    if let Some(width) = overrides.stroke_width.as_ref() {
+        node.assign("stroke-width", *width);
+    }
+    if let Some(dasharray) = overrides.dasharray() {
+        node.assign("stroke-dasharray", dasharray.clone());
+    }
+    if let Some(offset) = overrides.stroke_dashoffset.as_ref() {
+        node.assign("stroke-dashoffset", *offset);
+    }
+    if let Some(fill) = overrides.fill.as_ref() {
+        node.assign("fill", fill.clone());
+    }
+    node
+}
+
+fn add_point_to_document(
+    point: Point,
+    radius: f64,
+    parent: &mut impl Node,
+    overrides: &StyleOverrides,
+) {
+    let node = element::Circle::new()
+        .set("cx", point.x())
+        .set("cy", point.y())
+        .set("r", radius);
+    parent.append(apply_overrides(node, overrides));
+}
+
+fn add_line_to_document(line: Line, parent: &mut impl Node, overrides: &StyleOverrides) {
+    let node = element::Line::new()
+        .set("x1", line.start.x)
+        .set("y1", line.start.y)
+        .set("x2", line.end.x)
+        .set("y2", line.end.y);
+    parent.append(apply_overrides(node, overrides));
+}
+
+fn add_linestring_to_document(
+    linestring: LineString,
+    parent: &mut impl Node,
+    overrides: &StyleOverrides,
+) {
+    let points: Vec<(f64, f64)> = linestring
+        .into_inner()
+        .into_iter()
+        .map(|c| c.into())
+        .collect();
+    let node = element::Polyline::new().set("points", points);
+    parent.append(apply_overrides(node, overrides));
+}
+
+fn add_ring_to_path_data(mut data: element::path::Data, ring: &LineString) -> element::path::Data {
+    if ring.coords_count() < 3 {
+        return data;
+    }
+
+    // Handle both closed and open rings
+    let end = if ring.0.first() == ring.0.last() {
+        ring.0.len() - 1
+    } else {
+        ring.0.len()
+    };
+    // Orientation doesn't matter in SVG path data.
+    let start: (f64, f64) = ring.0[0].into();
+    data = data.move_to(start);
+    for coord in &ring.0[1..end] {
+        let point = (coord.x, coord.y);
+        data = data.line_to(point);
+    }
+    data.close()
+}
+
+fn add_polygon_to_document(polygon: Polygon, parent: &mut impl Node, overrides: &StyleOverrides) {
+    let mut data = element::path::Data::new();
+    data = add_ring_to_path_data(data, polygon.exterior());
+    for interior in polygon.interiors() {
+        data = add_ring_to_path_data(data, interior);
+    }
+    let node = element::Path::new()
+        .set("fill-rule", "evenodd")
+        .set("d", data);
+    parent.append(apply_overrides(node, overrides));
+}
+
+fn add_rect_to_document(rect: Rect, parent: &mut impl Node, overrides: &StyleOverrides) {
+    let node = element::Rectangle::new()
+        .set("x", rect.min().x)
+        .set("y", rect.max().y) // (x, y) is upper left corner
+        .set("width", rect.width())
+        .set("height", rect.height());
+    parent.append(apply_overrides(node, overrides));
+}
+
+fn add_triangle_to_document(
+    triangle: Triangle,
+    parent: &mut impl Node,
+    overrides: &StyleOverrides,
+) {
+    let points: Vec<(f64, f64)> = triangle.to_array().into_iter().map(|c| c.into()).collect();
+    let node = element::Polygon::new().set("points", points);
+    parent.append(apply_overrides(node, overrides));
+}
+
+fn expand_to_fit(bbox1: Option<Rect>, bbox2: Option<Rect>) -> Option<Rect> {
+    match (bbox1, bbox2) {
+        (None, None) => None,
+        (Some(b), None) | (None, Some(b)) => Some(b),
+        (Some(bbox1), Some(bbox2)) => {
+            let min1 = bbox1.min();
+            let min2 = bbox2.min();
+            let min = Coord {
+                x: f64::min(min1.x, min2.x),
+                y: f64::min(min1.y, min2.y),
+            };
+
+            let max1 = bbox1.max();
+            let max2 = bbox2.max();
+            let max = Coord {
+                x: f64::max(max1.x, max2.x),
+                y: f64::max(max1.y, max2.y),
+            };
+
+            Some(Rect::new(min, max))
+        }
+    }
+}
+
+fn bounding_box<'g>(geometries: impl Iterator<Item = &'g GeometryAndStyle>) -> Option<Rect> {
+    let mut bbox = None;
+    for geometry in geometries {
+        let temp = match geometry {
+            GeometryAndStyle::Point(p) => Some(p.bounding_rect()),
+            GeometryAndStyle::Line(l) => Some(l.bounding_rect()),
+            GeometryAndStyle::LineString(l) => l.bounding_rect(),
+            GeometryAndStyle::Polygon(p) => p.bounding_rect(),
+            GeometryAndStyle::MultiPoint(m) => m.bounding_rect(),
+            GeometryAndStyle::MultiLineString(m) => m.bounding_rect(),
+            GeometryAndStyle::MultiPolygon(m) => m.bounding_rect(),
+            GeometryAndStyle::GeometryCollection(g) => g.bounding_rect(),
+            GeometryAndStyle::Rect(r) => Some(r.bounding_rect()),
+            GeometryAndStyle::Triangle(t) => Some(t.bounding_rect()),
+            GeometryAndStyle::Style(_) => None,
+        };
+        bbox = expand_to_fit(bbox, temp);
+    }
+    bbox
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::read_wkt_geometries_and_styles;
+
+    #[test]
+    fn test_parse_gradient() {
+        let gradient: Gradient = "grad1:linear:0,0:1,1:red,blue".parse().unwrap();
+        let expected = Gradient::Linear {
+            id: "grad1".into(),
+            start: Coord { x: 0.0, y: 0.0 },
+            end: Coord { x: 1.0, y: 1.0 },
+            colors: vec!["red".into(), "blue".into()],
+        };
+        assert_eq!(gradient, expected);
+
+        let gradient: Gradient = "Glow:radial:0.5,0.5:0.5:white,yellow,black"
+            .parse()
+            .unwrap();
+        let expected = Gradient::Radial {
+            id: "Glow".into(),
+            center: Coord { x: 0.5, y: 0.5 },
+            radius: 0.5,
+            colors: vec!["white".into(), "yellow".into(), "black".into()],
+        };
+        assert_eq!(gradient, expected);
+
+        assert!("grad1:linear:0,0:1,1:red".parse::<Gradient>().is_err());
+        assert!("grad1:conic:0,0:1,1:red,blue".parse::<Gradient>().is_err());
+        assert!("grad1:linear:0,0:red,blue".parse::<Gradient>().is_err());
+    }
+
+    #[test]
+    fn test_colormap() {
+        assert_eq!(Colormap::Grayscale.color(0.0), "#000000");
+        assert_eq!(Colormap::Grayscale.color(0.5), "#808080");
+        assert_eq!(Colormap::Grayscale.color(1.0), "#ffffff");
+        assert_eq!(Colormap::Grayscale.color(2.0), "#ffffff");
+        assert_eq!(Colormap::Viridis.color(0.0), "#440154");
+        assert_eq!(Colormap::Viridis.color(0.5), "#21918c");
+        assert_eq!(Colormap::Viridis.color(1.0), "#fde725");
+    }
+
+    #[test]
+    fn test_render_gradient_fill() {
+        let wkt = b"FILL(url(#Grad1))\nPOLYGON((0 0, 1 0, 1 1, 0 0))";
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new()
+            .screen_coordinates(true)
+            .gradients(vec!["Grad1:linear:0,0:1,0:red,blue".parse().unwrap()])
+            .render(geometries)
+            .to_string();
+
+        assert!(document.contains("<defs>"));
+        assert!(
+            document.contains("<linearGradient id=\"Grad1\" x1=\"0\" x2=\"1\" y1=\"0\" y2=\"0\">")
+        );
+        assert!(document.contains("<stop offset=\"0\" stop-color=\"red\"/>"));
+        assert!(document.contains("<stop offset=\"1\" stop-color=\"blue\"/>"));
+        assert!(document.contains("fill=\"url(#Grad1)\""));
+    }
+
+    #[test]
+    fn test_render_overrides_style() {
+        let wkt = b"POINT(0 0)\nSTROKE(red)\nLINESTRING(0 0, 1 1)";
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new().render(geometries).to_string();
+
+        assert!(document.contains("<circle"));
+        assert!(document.contains("<polyline"));
+        assert!(document.contains("stroke=\"red\""));
+        assert!(document.contains("stroke:black;"));
+    }
+
+    #[test]
+    fn test_render_overrides_every_geometry_type() {
+        let geometries = vec![
+            GeometryAndStyle::Style(SvgStyle::Stroke("red".into())),
+            GeometryAndStyle::Style(SvgStyle::StrokeWidth(3.0)),
+            GeometryAndStyle::Style(SvgStyle::StrokeDasharray("1 2".into())),
+            GeometryAndStyle::Style(SvgStyle::StrokeDashoffset(0.5)),
+            GeometryAndStyle::Style(SvgStyle::Fill("blue".into())),
+            GeometryAndStyle::Point(Point::new(0.0, 0.0)),
+            GeometryAndStyle::Line(Line::new((0.0, 0.0), (1.0, 1.0))),
+            GeometryAndStyle::LineString(LineString::from(vec![
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (1.0, 1.0),
+            ])),
+            GeometryAndStyle::Polygon(Polygon::new(
+                LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]),
+                vec![],
+            )),
+            GeometryAndStyle::Rect(Rect::new((0.0, 0.0), (1.0, 1.0))),
+            GeometryAndStyle::Triangle(Triangle::new(
+                (0.0, 0.0).into(),
+                (1.0, 0.0).into(),
+                (1.0, 1.0).into(),
+            )),
+        ];
+        let document = SvgRenderer::new().render(geometries).to_string();
+
+        for style in [
+            "stroke=\"red\"",
+            "stroke-width=",
+            "stroke-dasharray=\"1 2\"",
+            "stroke-dashoffset=",
+            "fill=\"blue\"",
+        ] {
+            assert_eq!(document.matches(style).count(), 6, "{style} in {document}");
+        }
+    }
+
+    #[test]
+    fn test_render_global_style_is_not_overridden() {
+        let wkt = b"STROKE(black)\nPOINT(0 0)";
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new().render(geometries).to_string();
+
+        assert!(!document.contains("stroke=\"black\""));
+    }
+
+    #[test]
+    fn test_render_scale_stroke() {
+        let wkt = b"POINT(0 0)\nSTROKEWIDTH(3)\nPOINTRADIUS(4)\nPOINT(1 1)";
+
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new()
+            .scale(10.0)
+            .render(geometries)
+            .to_string();
+        assert!(document.contains("stroke-width:2;"));
+        assert!(document.contains("r=\"1\""));
+        assert!(document.contains("stroke-width=\"3\""));
+        assert!(document.contains("r=\"4\""));
+
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new()
+            .scale(10.0)
+            .scale_stroke(true)
+            .render(geometries)
+            .to_string();
+        assert!(document.contains("stroke-width:20;"));
+        assert!(document.contains("r=\"10\""));
+        assert!(document.contains("stroke-width=\"30\""));
+        assert!(document.contains("r=\"40\""));
+    }
+
+    #[test]
+    fn test_render_dash_cycle() {
+        let wkt = b"LINESTRING(0 0, 1 1)\nLINESTRING(0 1, 1 0)\nSTROKEDASHOFFSET(3)\nLINESTRING(0 0, 1 0)\nSTROKEDASHARRAY(1 1)\nLINESTRING(0 0, 0 1)";
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new()
+            .dash_cycle(vec!["4 2".into(), "8 4".into()])
+            .render(geometries)
+            .to_string();
+
+        let dashes: Vec<_> = document
+            .match_indices("stroke-dasharray=\"")
+            .map(|(i, m)| &document[i + m.len()..][..3])
+            .collect();
+        assert_eq!(dashes, ["4 2", "8 4", "4 2", "1 1"]);
+        assert_eq!(document.matches("stroke-dashoffset=\"3\"").count(), 2);
+    }
+
+    #[test]
+    fn test_render_labels() {
+        let wkt = b"LABEL(first)\nPOINT(0 0)\nPOINT(4 4)\nSTROKE(red)\nLABEL(a < b)\nLINESTRING(0 4, 2 4)";
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new()
+            .label_offset(Coord { x: 1.0, y: 1.0 })
+            .render(geometries)
+            .to_string();
+
+        // Only the geometry right after each label is labeled
+        assert_eq!(document.matches("<text").count(), 2);
+        // The y axis is flipped, so POINT(0 0) is drawn at y=4, and the label above it
+        let first = document
+            .find(r#"<text fill="black" font-size="12" stroke="none" x="1" y="3">"#)
+            .unwrap();
+        assert!(first < document.find("first").unwrap());
+        let second = document
+            .find(r#"<text fill="red" font-size="12" stroke="none" x="2" y="-1">"#)
+            .unwrap();
+        assert!(second < document.find("a &lt; b").unwrap());
+    }
+
+    #[test]
+    fn test_render_precision() {
+        let wkt = b"LABEL(third)\nPOINT(0.333333 -0.0001)\nLINESTRING(0.123456 1.5, 2 2.0049)";
+        let document = SvgRenderer::new()
+            .screen_coordinates(true)
+            .label_offset(Coord { x: 0.0, y: 0.0 })
+            .precision(Some(2))
+            .render(read_wkt_geometries_and_styles(&wkt[..]))
+            .to_string();
+        assert!(document.contains(r#"cx="0.33" cy="0""#));
+        assert!(document.contains(r#"points="0.12 1.5 2 2""#));
+        assert!(document.contains(r#"x="0.33" y="0""#));
+        // The viewBox isn't rounded
+        assert!(document.contains(r#"viewBox="-2.876544 -3.0001 7.876544 8.005""#));
+
+        let document = SvgRenderer::new()
+            .screen_coordinates(true)
+            .render(read_wkt_geometries_and_styles(&wkt[..]))
+            .to_string();
+        assert!(document.contains(r#"cx="0.333333""#));
+    }
+
+    #[test]
+    fn test_render_flips_y_axis() {
+        let wkt = b"POINT(0 0)\nPOINT(1 3)";
+
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new().render(geometries).to_string();
+        assert!(!document.contains("transform"));
+        // Flipped about the viewbox centerline y=1.5, so the first point is at the bottom
+        let first = document.find("cy=\"3\"").unwrap();
+        let second = document.find("cy=\"0\"").unwrap();
+        assert!(first < second);
+
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new()
+            .screen_coordinates(true)
+            .render(geometries)
+            .to_string();
+        let first = document.find("cy=\"0\"").unwrap();
+        let second = document.find("cy=\"3\"").unwrap();
+        assert!(first < second);
+    }
+
+    #[test]
+    fn test_render_metadata() {
+        let wkt = b"POINT(0 0)";
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new()
+            .comment(Some("generated by wkt2svg --scale 10".into()))
+            .title(Some("Points".into()))
+            .description(Some("seed < 42".into()))
+            .render(geometries)
+            .to_string();
+
+        assert!(document.contains("<!-- generated by wkt2svg - -scale 10 -->"));
+        assert!(document.contains("<title>Points</title>"));
+        assert!(document.contains("<desc>seed &lt; 42</desc>"));
+    }
+
+    #[test]
+    fn test_render_group_by_type() {
+        let wkt = b"POINT(0 0)\nSTROKE(red)\nLINESTRING(0 0, 1 1)\nPOLYGON((0 0, 1 0, 1 1, 0 0))\nPOINT(1 1)";
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new()
+            .group_by_type(true)
+            .render(geometries)
+            .to_string();
+
+        let polygons = document.find("<g class=\"polygons\">").unwrap();
+        let lines = document.find("<g class=\"lines\">").unwrap();
+        let points = document.find("<g class=\"points\">").unwrap();
+        assert!(polygons < document.find("<path").unwrap());
+        assert!(polygons < lines && lines < document.find("<polyline").unwrap());
+        assert!(lines < points && points < document.find("<circle").unwrap());
+        assert_eq!(document.matches("<circle").count(), 2);
+        // The style applies to the geometries after it, regardless of their group
+        assert_eq!(document.matches("stroke=\"red\"").count(), 3);
+
+        // Empty groups are left out
+        let wkt = b"POINT(0 0)";
+        let geometries = read_wkt_geometries_and_styles(&wkt[..]);
+        let document = SvgRenderer::new()
+            .group_by_type(true)
+            .render(geometries)
+            .to_string();
+        assert!(document.contains("<g class=\"points\">"));
+        assert!(!document.contains("<g class=\"lines\">"));
+    }
+
+    #[test]
+    fn test_render_nothing() {
+        let document = SvgRenderer::new().render(std::iter::empty()).to_string();
+        assert!(!document.contains("viewBox"));
+    }
+}
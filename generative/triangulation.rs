@@ -1,9 +1,15 @@
-use geo::Point;
+use geo::{Centroid, Contains, Coord, Intersects, Line, Point, Winding};
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
 use petgraph::{Directed, Undirected};
 
 use crate::graph::{GeometryGraph, NodeIndex};
 
 /// Calculate the Delaunay triangulation of the given point cloud
+///
+/// Returns `None` if there are fewer than three points. If the points are all collinear or
+/// coincident, the triangulation has no triangles, and its hull degrades to the segment between
+/// the two furthest apart points.
 pub fn triangulate(points: impl Iterator<Item = geo::Point>) -> Option<Triangulation> {
     let points: Vec<delaunator::Point> = points
         .map(|gp| delaunator::Point {
@@ -14,6 +20,20 @@ pub fn triangulate(points: impl Iterator<Item = geo::Point>) -> Option<Triangula
     if points.len() < 3 {
         return None;
     }
+    if let Some(hull) = degenerate_hull(&points) {
+        log::warn!(
+            "Can't triangulate {} points that are all collinear or coincident",
+            points.len()
+        );
+        return Some(Triangulation {
+            points,
+            triangulation: delaunator::Triangulation {
+                triangles: Vec::new(),
+                halfedges: Vec::new(),
+                hull,
+            },
+        });
+    }
     let triangulation = delaunator::triangulate(&points);
 
     Some(Triangulation {
@@ -22,6 +42,250 @@ pub fn triangulate(points: impl Iterator<Item = geo::Point>) -> Option<Triangula
     })
 }
 
+/// If the points are all collinear or coincident, get the indices of the extreme points
+///
+/// The extreme points are the ends of the segment through every point, or a single point if they
+/// all coincide.
+fn degenerate_hull(points: &[delaunator::Point]) -> Option<Vec<usize>> {
+    let (min_x, max_x) = extremes(points, |p| p.x);
+    let (min_y, max_y) = extremes(points, |p| p.y);
+    let (a, b) = if points[max_x].x - points[min_x].x >= points[max_y].y - points[min_y].y {
+        (min_x, max_x)
+    } else {
+        (min_y, max_y)
+    };
+
+    let a_point = &points[a];
+    let (dx, dy) = (points[b].x - a_point.x, points[b].y - a_point.y);
+    let length2 = dx * dx + dy * dy;
+    if length2 == 0.0 {
+        return Some(vec![a]);
+    }
+
+    // The cross product is the distance from the line scaled by the segment length
+    let collinear = points.iter().all(|p| {
+        let cross = dx * (p.y - a_point.y) - dy * (p.x - a_point.x);
+        cross.abs() <= 1e-12 * length2
+    });
+    collinear.then(|| vec![a, b])
+}
+
+/// The indices of the points with the smallest and largest values of the given coordinate
+fn extremes(points: &[delaunator::Point], f: impl Fn(&delaunator::Point) -> f64) -> (usize, usize) {
+    let mut min = 0;
+    let mut max = 0;
+    for (i, point) in points.iter().enumerate() {
+        if f(point) < f(&points[min]) {
+            min = i;
+        }
+        if f(point) > f(&points[max]) {
+            max = i;
+        }
+    }
+    (min, max)
+}
+
+/// Refine the triangulation with Ruppert's algorithm until no triangle has an angle smaller than
+/// `min_angle` degrees
+///
+/// Steiner points are inserted at the circumcenters of the bad triangles, worst first. The convex
+/// hull is the boundary to preserve, so when a point would encroach on a hull segment (lie inside
+/// the circle with the segment as its diameter) the segment is split at its midpoint instead.
+///
+/// Refinement is guaranteed to terminate for angles up to about 20.7 degrees, and usually does for
+/// larger angles up to about 33 degrees. Hull corners sharper than `min_angle` can't be fixed, so
+/// refinement stops early once the triangulation has `max_points` points.
+pub fn refine(
+    mut triangulation: Triangulation,
+    min_angle: f64,
+    max_points: usize,
+) -> Triangulation {
+    if triangulation.triangulation.triangles.is_empty() {
+        return triangulation;
+    }
+    let min_sin = min_angle.to_radians().sin();
+    let hull = triangulation.hull();
+    let mut segments: Vec<Line> = hull.exterior().lines().collect();
+    let mut points: Vec<Coord> = (0..triangulation.points.len())
+        .map(|i| triangulation.point(i))
+        .collect();
+
+    loop {
+        // The points inserted this pass, indexed to skip the bad triangles they've already destroyed
+        let mut new_points = Vec::new();
+        let mut inserted = KdTree::new(2);
+        let mut insert = |point: Coord, inserted: &mut KdTree<f64, (), [f64; 2]>| {
+            inserted
+                .add([point.x, point.y], ())
+                .expect("Failed to add point to kdtree");
+            new_points.push(point);
+        };
+
+        // Ruppert's algorithm splits the segments encroached by existing points before anything else
+        let mut index = KdTree::new(2);
+        for point in &points {
+            index
+                .add([point.x, point.y], *point)
+                .expect("Failed to add point to kdtree");
+        }
+        // Only split each segment once per pass, so a point lying on a segment can't make its halves
+        // split forever. Going backwards, swap_remove only moves segments that were already checked.
+        for i in (0..segments.len()).rev() {
+            let segment = segments[i];
+            let midpoint = (segment.start + segment.end) / 2.0;
+            let half = segment.end - midpoint;
+            let radius2 = half.x * half.x + half.y * half.y;
+            let encroached = index
+                .within(&[midpoint.x, midpoint.y], radius2, &squared_euclidean)
+                .unwrap_or_default()
+                .iter()
+                .any(|(_, point)| encroaches(&segment, **point));
+            if encroached {
+                insert(split_segment(&mut segments, i), &mut inserted);
+            }
+        }
+
+        let mut bad: Vec<_> = triangulation
+            .triangle_points()
+            .filter_map(|(a, b, c)| {
+                let (center, radius) = circumcircle(a, b, c)?;
+                let shortest = [a - b, b - c, c - a]
+                    .iter()
+                    .map(|e| e.x.hypot(e.y))
+                    .fold(f64::INFINITY, f64::min);
+                // The sine of the smallest angle, which is opposite the shortest edge
+                let quality = shortest / (2.0 * radius);
+                (quality < min_sin).then_some((quality, center, radius))
+            })
+            .collect();
+        bad.sort_by(|l, r| l.0.total_cmp(&r.0));
+
+        for (_, center, radius) in bad {
+            if points.len() + inserted.size() >= max_points {
+                break;
+            }
+            let destroyed = inserted
+                .within(&[center.x, center.y], radius * radius, &squared_euclidean)
+                .is_ok_and(|near| !near.is_empty());
+            if destroyed {
+                continue;
+            }
+
+            let encroached: Vec<usize> = (0..segments.len())
+                .filter(|i| encroaches(&segments[*i], center))
+                .collect();
+            if encroached.is_empty() {
+                // Slivers along the hull can have their circumcenters far outside of it
+                if hull.intersects(&center) {
+                    insert(center, &mut inserted);
+                }
+                continue;
+            }
+            // Highest index first, so that swap_remove doesn't move the other encroached segments
+            for i in encroached.into_iter().rev() {
+                insert(split_segment(&mut segments, i), &mut inserted);
+            }
+        }
+
+        if new_points.is_empty() {
+            break;
+        }
+        points.extend(new_points);
+        log::debug!("Refined triangulation to {} points", points.len());
+        triangulation =
+            triangulate(points.iter().map(|c| Point(*c))).expect("Refinement only adds points");
+        if points.len() >= max_points {
+            log::warn!("Stopped refining the triangulation after reaching {max_points} points");
+            break;
+        }
+    }
+
+    triangulation
+}
+
+/// Replace the segment with its two halves, and return the midpoint it was split at
+fn split_segment(segments: &mut Vec<Line>, i: usize) -> Coord {
+    let segment = segments.swap_remove(i);
+    let midpoint = (segment.start + segment.end) / 2.0;
+    segments.push(Line::new(segment.start, midpoint));
+    segments.push(Line::new(midpoint, segment.end));
+    midpoint
+}
+
+/// Whether the point lies strictly inside the circle with the segment as its diameter
+fn encroaches(segment: &Line, point: Coord) -> bool {
+    let a = segment.start - point;
+    let b = segment.end - point;
+    a.x * b.x + a.y * b.y < 0.0
+}
+
+/// The center and radius of the triangle's circumcircle, or `None` if the triangle is degenerate
+fn circumcircle(a: Coord, b: Coord, c: Coord) -> Option<(Coord, f64)> {
+    let ab = b - a;
+    let ac = c - a;
+    let d = 2.0 * (ab.x * ac.y - ab.y * ac.x);
+    let longest2 = [ab, ac, c - b]
+        .iter()
+        .map(|e| e.x * e.x + e.y * e.y)
+        .fold(0.0, f64::max);
+    if d.abs() <= 1e-12 * longest2 {
+        return None;
+    }
+
+    let ab2 = ab.x * ab.x + ab.y * ab.y;
+    let ac2 = ac.x * ac.x + ac.y * ac.y;
+    let offset = geo::coord! {
+        x: (ac.y * ab2 - ab.y * ac2) / d,
+        y: (ab.x * ac2 - ac.x * ab2) / d,
+    };
+    Some((a + offset, offset.x.hypot(offset.y)))
+}
+
+/// Clip the open convex cell to the half-plane closer to `point` than to `neighbor`
+///
+/// The edge along the bisector is labeled with the neighbor.
+fn clip_by_bisector(
+    cell: &[(geo::Coord, Option<usize>)],
+    point: geo::Coord,
+    neighbor: geo::Coord,
+    label: usize,
+) -> Vec<(geo::Coord, Option<usize>)> {
+    let normal = neighbor - point;
+    let midpoint = (point + neighbor) / 2.0;
+    let side = |c: geo::Coord| (c - midpoint).x * normal.x + (c - midpoint).y * normal.y;
+
+    let mut clipped: Vec<(geo::Coord, Option<usize>)> = Vec::with_capacity(cell.len() + 1);
+    let mut push = |coord: geo::Coord, label: Option<usize>| match clipped.last_mut() {
+        // A zero-length edge; the later label belongs to the edge leaving this vertex
+        Some(last) if last.0 == coord => last.1 = label,
+        _ => clipped.push((coord, label)),
+    };
+
+    for (k, (curr, curr_label)) in cell.iter().enumerate() {
+        let (next, _) = cell[(k + 1) % cell.len()];
+        let (curr_side, next_side) = (side(*curr), side(next));
+        let crossing = || {
+            let t = curr_side / (curr_side - next_side);
+            *curr + (next - *curr) * t
+        };
+
+        match (curr_side <= 0.0, next_side <= 0.0) {
+            (true, true) => push(*curr, *curr_label),
+            (true, false) => {
+                push(*curr, *curr_label);
+                push(crossing(), Some(label));
+            }
+            (false, true) => push(crossing(), *curr_label),
+            (false, false) => {}
+        }
+    }
+
+    if clipped.len() > 1 && clipped.first().map(|f| f.0) == clipped.last().map(|l| l.0) {
+        clipped.pop();
+    }
+    clipped
+}
+
 pub struct Triangulation {
     points: Vec<delaunator::Point>,
     triangulation: delaunator::Triangulation,
@@ -64,17 +328,50 @@ impl Triangulation {
         })
     }
 
+    /// Get the convex hull of the triangulation
+    ///
+    /// The exterior ring is explicitly closed, and wound counter-clockwise. The starting vertex is
+    /// unspecified.
     pub fn hull(&self) -> geo::Polygon {
-        let hull: Vec<geo::Coord> = self
+        let mut hull: geo::LineString = self
             .triangulation
             .hull
             .iter()
             .map(|i| geo::coord! {x: self.points[*i].x, y: self.points[*i].y})
             .collect();
-        let hull = geo::LineString::new(hull);
+        hull.close();
+        // delaunator gives the hull clockwise, but don't rely on it
+        hull.make_ccw_winding();
         geo::Polygon::new(hull, vec![])
     }
 
+    /// The raw delaunator triangle vertex indices, three per triangle
+    ///
+    /// Triangle `t` is made of the halfedges `3 * t`, `3 * t + 1`, and `3 * t + 2`, and
+    /// `triangles_raw()[e]` is the index of the [point](Self::point) that halfedge `e` starts at.
+    pub fn triangles_raw(&self) -> &[usize] {
+        &self.triangulation.triangles
+    }
+
+    /// The raw delaunator halfedges
+    ///
+    /// `halfedges_raw()[e]` is the index of the opposite halfedge in the adjacent triangle, or
+    /// [delaunator::EMPTY] if halfedge `e` is on the convex hull.
+    pub fn halfedges_raw(&self) -> &[usize] {
+        &self.triangulation.halfedges
+    }
+
+    /// The raw delaunator convex hull, as clockwise [point](Self::point) indices
+    pub fn hull_raw(&self) -> &[usize] {
+        &self.triangulation.hull
+    }
+
+    /// Get the point with the given index, as used by the raw triangles and hull
+    pub fn point(&self, i: usize) -> geo::Coord {
+        let point = &self.points[i];
+        geo::coord! {x: point.x, y: point.y}
+    }
+
     pub fn digraph(&self) -> GeometryGraph<Directed> {
         let nodes = self.points.len();
         let edges = self.triangulation.halfedges.len();
@@ -96,11 +393,9 @@ impl Triangulation {
 
             graph.add_edge(curr.into(), next.into(), ());
         }
-        // NOTE: The hull is open and needs to be closed in order to capture the last edge!
-        if let (Some(first), Some(last)) = (
-            self.triangulation.hull.first(),
-            self.triangulation.hull.last(),
-        ) {
+        // NOTE: The hull is open and needs to be closed in order to capture the last edge! A hull
+        // with a single point from coincident input has no edges.
+        if let [first, .., last] = self.triangulation.hull.as_slice() {
             graph.add_edge(
                 petgraph::graph::NodeIndex::new(*last),
                 petgraph::graph::NodeIndex::new(*first),
@@ -131,8 +426,8 @@ impl Triangulation {
         let digraph = self.digraph();
         let nodes = self.points.len();
         let directed_edges = self.triangulation.halfedges.len();
-        let mut graph =
-            GeometryGraph::with_capacity(nodes, directed_edges - self.triangulation.hull.len());
+        let edges = directed_edges.saturating_sub(self.triangulation.hull.len());
+        let mut graph = GeometryGraph::with_capacity(nodes, edges);
 
         // Add the nodes
         for (_i, node) in digraph.raw_nodes().iter().enumerate() {
@@ -149,6 +444,126 @@ impl Triangulation {
         graph
     }
 
+    /// Get the graph of only the triangles whose centroids are inside the boundary
+    ///
+    /// Triangles inside the boundary's holes are discarded too. This is a cheap way to triangulate
+    /// a concave region, but unlike a constrained triangulation, the triangle edges don't follow
+    /// the boundary. Nodes that aren't on any of the kept triangles are removed, and the rest keep
+    /// their relative order.
+    pub fn clipped_graph(&self, boundary: &geo::MultiPolygon) -> GeometryGraph<Undirected> {
+        let mut graph = GeometryGraph::with_capacity(self.points.len(), 0);
+        for point in &self.points {
+            graph.add_node(Point::new(point.x, point.y));
+        }
+
+        let mut used = vec![false; self.points.len()];
+        for ((a, b, c), triangle) in self.triangle_indices().zip(self.triangles()) {
+            if !boundary.contains(&triangle.centroid()) {
+                continue;
+            }
+            for (src, dst) in [(a, b), (b, c), (c, a)] {
+                used[src] = true;
+                let src = petgraph::graph::NodeIndex::new(src);
+                let dst = petgraph::graph::NodeIndex::new(dst);
+                graph.update_edge(src, dst, ());
+            }
+        }
+
+        graph.filter_map(
+            |node, point| used[node.index()].then_some(*point),
+            |_, _| Some(()),
+        )
+    }
+
+    /// Get the Voronoi cell of each point, clipped to the given bounds
+    ///
+    /// The cells are indexed like the [points](Self::point), and each is the part of the bounds
+    /// closer to its point than to any other. Points that aren't on any triangle, like coincident
+    /// or collinear points, and points whose cell is entirely outside the bounds, have no cell.
+    /// The exterior rings are explicitly closed, and wound counter-clockwise.
+    pub fn voronoi(&self, bounds: geo::Rect) -> Vec<Option<geo::Polygon>> {
+        self.voronoi_cells(bounds)
+            .into_iter()
+            .map(|cell| {
+                let cell = cell?;
+                let mut exterior: geo::LineString = cell.iter().map(|(c, _)| *c).collect();
+                exterior.close();
+                Some(geo::Polygon::new(exterior, vec![]))
+            })
+            .collect()
+    }
+
+    /// Get the edges between adjacent Voronoi cells, clipped to the given bounds
+    ///
+    /// Each edge is the part of the perpendicular bisector between two neighboring points that
+    /// is closer to them than to any other point. The bounds themselves aren't included.
+    pub fn voronoi_edges(&self, bounds: geo::Rect) -> Vec<geo::Line> {
+        let cells = self.voronoi_cells(bounds);
+        let mut edges = Vec::new();
+        for (i, cell) in cells.iter().enumerate() {
+            let Some(cell) = cell else {
+                continue;
+            };
+            for (k, (start, label)) in cell.iter().enumerate() {
+                // Each edge is on two cells, so only take it from the lower indexed one
+                let Some(neighbor) = label else {
+                    continue;
+                };
+                if *neighbor < i {
+                    continue;
+                }
+                let (end, _) = cell[(k + 1) % cell.len()];
+                edges.push(geo::Line::new(*start, end));
+            }
+        }
+        edges
+    }
+
+    /// Clip the bounds around each point by the bisectors between it and its Delaunay neighbors
+    ///
+    /// Each open cell vertex is labeled with the neighbor whose bisector the edge starting at that
+    /// vertex lies on, or `None` if the edge is on the bounds.
+    #[allow(clippy::type_complexity)]
+    fn voronoi_cells(&self, bounds: geo::Rect) -> Vec<Option<Vec<(geo::Coord, Option<usize>)>>> {
+        let mut neighbors = vec![Vec::new(); self.points.len()];
+        let triangles = &self.triangulation.triangles;
+        for (e, src) in triangles.iter().enumerate() {
+            let dst = triangles[delaunator::next_halfedge(e)];
+            neighbors[*src].push(dst);
+            neighbors[dst].push(*src);
+        }
+
+        let (min, max) = (bounds.min(), bounds.max());
+        let rect = [
+            min,
+            geo::coord! {x: max.x, y: min.y},
+            max,
+            geo::coord! {x: min.x, y: max.y},
+        ];
+
+        neighbors
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut neighbors)| {
+                if neighbors.is_empty() {
+                    return None;
+                }
+                neighbors.sort_unstable();
+                neighbors.dedup();
+
+                let point = self.point(i);
+                let mut cell: Vec<_> = rect.iter().map(|c| (*c, None)).collect();
+                for j in neighbors {
+                    cell = clip_by_bisector(&cell, point, self.point(j), j);
+                    if cell.len() < 3 {
+                        return None;
+                    }
+                }
+                Some(cell)
+            })
+            .collect()
+    }
+
     #[allow(non_snake_case)]
     fn longest_edge(&self, a: usize, b: usize, c: usize) -> (usize, usize) {
         let A = &self.points[a];
@@ -197,11 +612,98 @@ impl Triangulation {
 #[cfg(test)]
 mod tests {
     use delaunator::EMPTY;
+    use geo::Area;
 
     use super::*;
     use crate::flatten::flatten_geometries_into_points_ref;
     use crate::io::read_wkt_geometries;
 
+    /// The smallest angle of the triangle, in degrees
+    fn min_angle(triangle: &geo::Triangle) -> f64 {
+        let [a, b, c] = triangle.to_array();
+        [(a, b, c), (b, c, a), (c, a, b)]
+            .iter()
+            .map(|(p, q, r)| {
+                let u = *q - *p;
+                let v = *r - *p;
+                (u.x * v.y - u.y * v.x)
+                    .abs()
+                    .atan2(u.x * v.x + u.y * v.y)
+                    .to_degrees()
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn test_clipped_graph() {
+        // A sheared 3x3 grid, so that the grid cells aren't cocircular
+        let points = (0..3)
+            .flat_map(|y| (0..3).map(move |x| Point::new(x as f64 + 0.1 * y as f64, y as f64)));
+        let triangulation = triangulate(points).unwrap();
+        assert_eq!(triangulation.triangles().count(), 8);
+
+        // An L shape, without the top right grid cell
+        let wkt = b"MULTIPOLYGON(((0 0, 2 0, 2.1 1, 1.1 1, 1.2 2, 0.2 2, 0 0)))";
+        let boundary = read_wkt_geometries(&wkt[..]).next().unwrap();
+        let boundary: geo::MultiPolygon = boundary.try_into().unwrap();
+        let graph = triangulation.clipped_graph(&boundary);
+        // The top right corner is dropped
+        assert_eq!(graph.node_count(), 8);
+        assert!(graph.node_weights().all(|p| *p != Point::new(2.2, 2.0)));
+        // 5 horizontal, 5 vertical, and 3 diagonal edges
+        assert_eq!(graph.edge_count(), 13);
+
+        // A hole over the bottom left grid cell
+        let wkt = b"MULTIPOLYGON(((0 0, 2 0, 2.2 2, 0.2 2, 0 0), (0.11 0.1, 0.91 0.1, 0.99 0.9, 0.19 0.9, 0.11 0.1)))";
+        let boundary = read_wkt_geometries(&wkt[..]).next().unwrap();
+        let boundary: geo::MultiPolygon = boundary.try_into().unwrap();
+        let graph = triangulation.clipped_graph(&boundary);
+        // Both of the cell's triangles are dropped, and with them the bottom left corner
+        assert_eq!(graph.node_count(), 8);
+        assert!(graph.node_weights().all(|p| *p != Point::new(0.0, 0.0)));
+        assert_eq!(graph.edge_count(), 13);
+    }
+
+    #[test]
+    fn test_refine() {
+        // A long thin rectangle, with a point right next to one of its long sides
+        let points = [
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 1.0),
+            (0.0, 1.0),
+            (5.0, 0.05),
+        ];
+        let triangulation = triangulate(points.into_iter().map(Point::from)).unwrap();
+        assert!(triangulation.triangles().any(|t| min_angle(&t) < 20.0));
+
+        let refined = refine(triangulation, 20.0, 10_000);
+        assert!(refined.points.len() > 5);
+        assert!(refined.points.len() < 10_000);
+        for triangle in refined.triangles() {
+            assert!(min_angle(&triangle) >= 20.0 - 1e-6, "{triangle:?}");
+        }
+        // The refinement stays inside the original hull
+        let hull = geo::Rect::new((0.0, 0.0), (10.0, 1.0)).to_polygon();
+        assert!(refined.triangles().all(|t| hull.intersects(&t.centroid())));
+        assert!((refined.triangles().map(|t| t.unsigned_area()).sum::<f64>() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_refine_stops_at_max_points() {
+        let points = [
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 1.0),
+            (0.0, 1.0),
+            (5.0, 0.05),
+        ];
+        let triangulation = triangulate(points.into_iter().map(Point::from)).unwrap();
+
+        let refined = refine(triangulation, 20.0, 8);
+        assert!(refined.points.len() >= 8);
+    }
+
     #[test]
     fn test_triangulate_unit_square() {
         let wkt = b"POLYGON((0 0, 0 1, 1 1, 1 0, 0 0))";
@@ -210,31 +712,161 @@ mod tests {
 
         let triangulation = triangulate(points).unwrap();
 
-        let triangles: Vec<_> = triangulation.triangles().collect();
-        assert_eq!(triangles.len(), 2);
-        assert_eq!(
-            triangles[0],
-            geo::Triangle::new(
-                geo::Coord { x: 0., y: 0. },
-                geo::Coord { x: 0., y: 1. },
-                geo::Coord { x: 1., y: 1. },
-            )
-        );
+        // Neither the order of the triangles, nor the starting vertex or winding of each triangle
+        // is specified, so compare each triangle's sorted vertices
+        let sorted = |t: geo::Triangle| {
+            let mut coords = t.to_array().map(|c| (c.x, c.y));
+            coords.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            coords
+        };
+        let mut triangles: Vec<_> = triangulation.triangles().map(sorted).collect();
+        triangles.sort_by(|a, b| a.partial_cmp(b).unwrap());
         assert_eq!(
-            triangles[1],
-            geo::Triangle::new(
-                geo::Coord { x: 1., y: 1. },
-                geo::Coord { x: 1., y: 0. },
-                geo::Coord { x: 0., y: 0. },
-            )
+            triangles,
+            [
+                [(0., 0.), (0., 1.), (1., 1.)],
+                [(0., 0.), (1., 0.), (1., 1.)],
+            ]
         );
 
         assert_eq!(triangulation.lines().count(), 6);
 
-        let wkt = b"POLYGON((1 1, 1 0, 0 0, 0 1, 1 1))"; // same polygon, same winding, different starting point
+        let hull = triangulation.hull();
+        let exterior = hull.exterior();
+        assert!(exterior.is_closed());
+        assert!(exterior.is_ccw());
+        assert_eq!(exterior.0.len(), 5);
+
+        // The starting vertex is unspecified, so rotate the expected ring to match
+        let mut expected = vec![
+            geo::Coord { x: 0., y: 0. },
+            geo::Coord { x: 1., y: 0. },
+            geo::Coord { x: 1., y: 1. },
+            geo::Coord { x: 0., y: 1. },
+        ];
+        let start = expected.iter().position(|c| *c == exterior.0[0]).unwrap();
+        expected.rotate_left(start);
+        assert_eq!(exterior.0[..4], expected);
+    }
+
+    #[test]
+    fn test_voronoi() {
+        let wkt = b"POINT(0 0)\nPOINT(2 0)\nPOINT(2 2)\nPOINT(0 2)\nPOINT(1 1)";
         let geometries: Vec<_> = read_wkt_geometries(&wkt[..]).collect();
+        let points = flatten_geometries_into_points_ref(geometries.iter());
+
+        let triangulation = triangulate(points).unwrap();
+        let bounds = geo::Rect::new(geo::coord! {x: -1.0, y: -1.0}, geo::coord! {x: 3.0, y: 3.0});
+        let cells = triangulation.voronoi(bounds);
+        assert_eq!(cells.len(), 5);
+
+        let cells: Vec<_> = cells.into_iter().map(Option::unwrap).collect();
+        let total: f64 = cells.iter().map(|c| c.unsigned_area()).sum();
+        assert!((total - 16.0).abs() < 1e-9);
+
+        // The center point's cell is the diamond between it and the corners
+        let center = (0..5)
+            .find(|i| triangulation.point(*i) == geo::coord! {x: 1.0, y: 1.0})
+            .unwrap();
+        let diamond = &cells[center];
+        assert_eq!(diamond.exterior().0.len(), 5);
+        assert!((diamond.signed_area() - 2.0).abs() < 1e-9);
+        for (i, cell) in cells.iter().enumerate() {
+            assert!(cell.contains(&Point::from(triangulation.point(i))));
+        }
+
+        // The four diamond edges, and the four edges between adjacent corners
+        let edges = triangulation.voronoi_edges(bounds);
+        assert_eq!(edges.len(), 8);
+        let length: f64 = edges.iter().map(|e| e.dx().hypot(e.dy())).sum();
+        assert!((length - (4.0 * 2.0_f64.sqrt() + 4.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_voronoi_degenerate() {
+        let wkt = b"LINESTRING(1 1, 0 0, 3 3, 2 2)";
+        let geometries: Vec<_> = read_wkt_geometries(&wkt[..]).collect();
+        let points = flatten_geometries_into_points_ref(geometries.iter());
+
+        let triangulation = triangulate(points).unwrap();
+        let bounds = geo::Rect::new(geo::coord! {x: -1.0, y: -1.0}, geo::coord! {x: 4.0, y: 4.0});
+        assert!(triangulation.voronoi(bounds).iter().all(Option::is_none));
+        assert!(triangulation.voronoi_edges(bounds).is_empty());
+    }
+
+    #[test]
+    fn test_triangulate_collinear_points() {
+        let wkt = b"LINESTRING(1 1, 0 0, 3 3, 2 2)";
+        let geometries: Vec<_> = read_wkt_geometries(&wkt[..]).collect();
+        let points = flatten_geometries_into_points_ref(geometries.iter());
+
+        let triangulation = triangulate(points).unwrap();
+        assert_eq!(triangulation.triangles().count(), 0);
+        assert_eq!(triangulation.lines().count(), 0);
+
         let hull = triangulation.hull();
-        assert_eq!(geo::Geometry::Polygon(hull), geometries[0]);
+        let mut ends = hull.exterior().0.clone();
+        ends.sort_by(|a, b| a.x.total_cmp(&b.x));
+        ends.dedup();
+        assert_eq!(
+            ends,
+            [geo::Coord { x: 0., y: 0. }, geo::Coord { x: 3., y: 3. }]
+        );
+
+        let graph = triangulation.graph();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_triangulate_coincident_points() {
+        let wkt = b"POINT(1 1)\nPOINT(1 1)\nPOINT(1 1)";
+        let geometries: Vec<_> = read_wkt_geometries(&wkt[..]).collect();
+        let points = flatten_geometries_into_points_ref(geometries.iter());
+
+        let triangulation = triangulate(points).unwrap();
+        assert_eq!(triangulation.triangles().count(), 0);
+        assert_eq!(triangulation.lines().count(), 0);
+        assert!(triangulation
+            .hull()
+            .exterior()
+            .coords()
+            .all(|c| *c == geo::Coord { x: 1., y: 1. }));
+
+        let graph = triangulation.graph();
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(triangulation.urquhart().edge_count(), 0);
+    }
+
+    #[test]
+    fn test_raw_halfedges() {
+        let wkt = b"POLYGON((0 0, 0 1, 1 1, 1 0, 0 0))";
+        let geometries: Vec<_> = read_wkt_geometries(&wkt[..]).collect();
+        let points = flatten_geometries_into_points_ref(geometries.iter());
+
+        let triangulation = triangulate(points).unwrap();
+        let triangles = triangulation.triangles_raw();
+        let halfedges = triangulation.halfedges_raw();
+        assert_eq!(triangles.len(), 6);
+        assert_eq!(halfedges.len(), 6);
+        assert_eq!(triangulation.hull_raw().len(), 4);
+
+        // The two triangles share a single pair of opposite halfedges
+        let shared: Vec<_> = (0..halfedges.len())
+            .filter(|e| halfedges[*e] != EMPTY)
+            .collect();
+        assert_eq!(shared.len(), 2);
+        for e in shared {
+            let opposite = halfedges[e];
+            assert_eq!(halfedges[opposite], e);
+            // Opposite halfedges run between the same points in opposite directions
+            assert_eq!(triangles[e], triangles[delaunator::next_halfedge(opposite)]);
+            assert_eq!(
+                triangulation.point(triangles[opposite]),
+                triangulation.point(triangles[delaunator::next_halfedge(e)])
+            );
+        }
     }
 
     #[test]
@@ -272,6 +904,11 @@ mod tests {
         let hull = [1, 0, 3, 5, 4, 2];
         assert_eq!(triangulation.triangulation.hull, hull);
 
+        let hull = triangulation.hull();
+        assert!(hull.exterior().is_closed());
+        assert!(hull.exterior().is_ccw());
+        assert_eq!(hull.exterior().0.len(), 7);
+
         let graph = triangulation.digraph();
         // It's not necessary that the edges be compared in order, but that's easiest to implement
         // here.
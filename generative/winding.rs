@@ -0,0 +1,124 @@
+use clap::ValueEnum;
+use geo::orient::Direction;
+use geo::{Geometry, Orient, Triangle, Winding};
+
+/// The winding order of polygon exterior rings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Orientation {
+    /// Counter-clockwise exteriors, and clockwise interiors
+    Ccw,
+    /// Clockwise exteriors, and counter-clockwise interiors
+    Cw,
+}
+
+impl std::fmt::Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            // important: Should match clap::ValueEnum format
+            Orientation::Ccw => write!(f, "ccw"),
+            Orientation::Cw => write!(f, "cw"),
+        }
+    }
+}
+
+/// Force a consistent winding order on the polygons in the given geometry
+///
+/// Recurses through MULTIPOLYGONs and GEOMETRYCOLLECTIONs. TRIANGLEs are wound like polygon
+/// exteriors. Other geometries, including closed LINESTRINGs, are left unchanged.
+pub fn normalize_winding(geom: Geometry, orientation: Orientation) -> Geometry {
+    let direction = match orientation {
+        Orientation::Ccw => Direction::Default,
+        Orientation::Cw => Direction::Reversed,
+    };
+
+    match geom {
+        Geometry::Polygon(p) => Geometry::Polygon(p.orient(direction)),
+        Geometry::MultiPolygon(m) => Geometry::MultiPolygon(m.orient(direction)),
+        Geometry::Triangle(t) => Geometry::Triangle(normalize_triangle(t, orientation)),
+        Geometry::GeometryCollection(c) => Geometry::GeometryCollection(
+            c.into_iter()
+                .map(|g| normalize_winding(g, orientation))
+                .collect(),
+        ),
+        _ => geom,
+    }
+}
+
+fn normalize_triangle(triangle: Triangle, orientation: Orientation) -> Triangle {
+    let ring = triangle.to_polygon().into_inner().0;
+    let flip = match orientation {
+        Orientation::Ccw => ring.is_cw(),
+        Orientation::Cw => ring.is_ccw(),
+    };
+    if flip {
+        Triangle::new(triangle.v1(), triangle.v3(), triangle.v2())
+    } else {
+        triangle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Area, GeometryCollection, Point};
+
+    use super::*;
+    use crate::io::read_wkt_geometries;
+
+    fn exterior_is_ccw(geom: &Geometry) -> bool {
+        match geom {
+            Geometry::Polygon(p) => p.exterior().is_ccw(),
+            _ => panic!("Expected a POLYGON"),
+        }
+    }
+
+    #[test]
+    fn test_cw_polygon_flipped_to_ccw() {
+        let wkt = b"POLYGON((0 0, 0 1, 1 1, 1 0, 0 0))";
+        let geom = read_wkt_geometries(&wkt[..]).next().unwrap();
+        assert!(!exterior_is_ccw(&geom));
+        assert!(geom.signed_area() < 0.0);
+
+        let normalized = normalize_winding(geom.clone(), Orientation::Ccw);
+        assert!(exterior_is_ccw(&normalized));
+        assert_eq!(normalized.signed_area(), 1.0);
+
+        let normalized = normalize_winding(normalized, Orientation::Cw);
+        assert_eq!(normalized, geom);
+    }
+
+    #[test]
+    fn test_interiors_are_wound_opposite() {
+        let wkt = b"POLYGON((0 0, 4 0, 4 4, 0 4, 0 0), (1 1, 3 1, 3 3, 1 3, 1 1))";
+        let geom = read_wkt_geometries(&wkt[..]).next().unwrap();
+
+        let Geometry::Polygon(normalized) = normalize_winding(geom, Orientation::Ccw) else {
+            panic!("Expected a POLYGON");
+        };
+        assert!(normalized.exterior().is_ccw());
+        assert!(normalized.interiors()[0].is_cw());
+    }
+
+    #[test]
+    fn test_recurses_through_collections() {
+        let wkt = b"MULTIPOLYGON(((0 0, 0 1, 1 1, 0 0)))";
+        let multi = read_wkt_geometries(&wkt[..]).next().unwrap();
+        let triangle = Triangle::from([(0.0, 0.0), (0.0, 1.0), (1.0, 1.0)]);
+        let point = Point::new(0.0, 0.0);
+        let geom = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            multi,
+            Geometry::Triangle(triangle),
+            Geometry::Point(point),
+        ]));
+
+        let Geometry::GeometryCollection(normalized) = normalize_winding(geom, Orientation::Ccw)
+        else {
+            panic!("Expected a GEOMETRYCOLLECTION");
+        };
+        let Geometry::MultiPolygon(multi) = &normalized[0] else {
+            panic!("Expected a MULTIPOLYGON");
+        };
+        assert!(multi.0.iter().all(|p| p.exterior().is_ccw()));
+        assert!(normalized[1].signed_area() > 0.0);
+        assert_eq!(normalized[2], Geometry::Point(point));
+    }
+}
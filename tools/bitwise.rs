@@ -1,5 +1,5 @@
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::{Parser, ValueEnum};
 use generative::io::{get_output_writer, write_geometries, GeometryFormat};
@@ -19,7 +19,7 @@ struct CmdlineOptions {
     log_level: log::Level,
 
     /// Output file to write result to. Defaults to stdout.
-    #[clap(short, long)]
+    #[clap(short, long, conflicts_with = "frames")]
     output: Option<PathBuf>,
 
     /// Output geometry format.
@@ -54,15 +54,34 @@ struct CmdlineOptions {
     )]
     neighbor_search_order: Vec<Neighbor>,
 
-    /// A valid Rust expression taking 'x' and 'y', and returning an i64
+    /// Evaluate the expression for each frame 't' in 0..N, for making animations.
+    #[clap(long, requires = "frame_dir")]
+    frames: Option<i64>,
+
+    /// The directory to write the --frames outputs to. Will be created if it doesn't exist.
+    ///
+    /// Each frame is written to a numbered file in the --output-format format.
+    #[clap(long, requires = "frames")]
+    frame_dir: Option<PathBuf>,
+
+    /// A valid Rust expression taking 'x', 'y', and the frame 't', and returning an i64
+    ///
+    /// Without --frames, 't' is always 0.
     #[clap(default_value = "(x & y) & (x ^ y) % 13")]
     expression: String,
 }
 
-fn expression(engine: &Engine, ast: &AST, x: i64, y: i64) -> Result<i64, Box<EvalAltResult>> {
+fn expression(
+    engine: &Engine,
+    ast: &AST,
+    x: i64,
+    y: i64,
+    t: i64,
+) -> Result<i64, Box<EvalAltResult>> {
     let mut scope = Scope::new();
     scope.push("x", x);
     scope.push("y", y);
+    scope.push("t", t);
 
     engine.eval_ast_with_scope::<i64>(&mut scope, ast)
 }
@@ -129,35 +148,34 @@ fn neighbor(x: i64, y: i64, n: Neighbor) -> (i64, i64) {
     }
 }
 
-fn main() -> Result<(), Box<EvalAltResult>> {
-    let args = CmdlineOptions::parse();
-
-    stderrlog::new()
-        .verbosity(args.log_level)
-        .color(ColorChoice::Auto)
-        .init()
-        .expect("Failed to initialize stderrlog");
-
-    let engine = Engine::new();
-    let ast = engine.compile_expression(&args.expression)?;
-
+/// Write the pattern for the given frame 't'
+fn write_pattern<W>(
+    mut writer: W,
+    engine: &Engine,
+    ast: &AST,
+    args: &CmdlineOptions,
+    t: i64,
+) -> Result<(), Box<EvalAltResult>>
+where
+    W: Write,
+{
     let xs = args.x_min..args.x_max;
     let ys = args.y_min..args.y_max;
     let cross = xs.cartesian_product(ys);
 
-    let mut writer = get_output_writer(&args.output).unwrap();
     if args.points {
         let geometries = cross.filter_map(|(x, y)| {
-            if let Ok(value) = expression(&engine, &ast, x, y) {
+            if let Ok(value) = expression(engine, ast, x, y, t) {
                 if value > 0 {
                     return Some(Geometry::Point(Point::new(x as f64, y as f64)));
                 }
             } else {
                 log::error!(
-                    "Failed to evaluate expression '{}' given x={}, y={}",
+                    "Failed to evaluate expression '{}' given x={}, y={}, t={}",
                     args.expression,
                     x,
-                    y
+                    y,
+                    t
                 );
             }
             None
@@ -165,16 +183,12 @@ fn main() -> Result<(), Box<EvalAltResult>> {
 
         write_geometries(writer, geometries, args.output_format);
     } else {
-        log::info!(
-            "Searching neighbors in order: {:?}",
-            args.neighbor_search_order
-        );
         for (x, y) in cross {
-            if expression(&engine, &ast, x, y)? > 0 {
+            if expression(engine, ast, x, y, t)? > 0 {
                 let mut wrote_line = false;
                 for n in args.neighbor_search_order.iter() {
                     let (x2, y2) = neighbor(x, y, n.clone());
-                    if expression(&engine, &ast, x2, y2)? > 0 {
+                    if expression(engine, ast, x2, y2, t)? > 0 {
                         write_line(&mut writer, args.output_format, x, y, x2, y2);
                         wrote_line = true;
                         break;
@@ -189,3 +203,52 @@ fn main() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+/// Write one file per frame, each containing the pattern for that frame's 't'
+fn write_frames(
+    frame_dir: &Path,
+    frames: i64,
+    engine: &Engine,
+    ast: &AST,
+    args: &CmdlineOptions,
+) -> Result<(), Box<EvalAltResult>> {
+    if let Err(why) = std::fs::create_dir_all(frame_dir) {
+        panic!("Couldn't create: {} because: {}", frame_dir.display(), why);
+    }
+
+    log::debug!("Writing {frames} frames to {}", frame_dir.display());
+    for t in 0..frames {
+        let path = frame_dir.join(format!("frame-{t:06}.{}", args.output_format));
+        let writer = get_output_writer(&Some(path)).unwrap();
+        write_pattern(writer, engine, ast, args, t)?;
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<(), Box<EvalAltResult>> {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    let engine = Engine::new();
+    let ast = engine.compile_expression(&args.expression)?;
+
+    if !args.points {
+        log::info!(
+            "Searching neighbors in order: {:?}",
+            args.neighbor_search_order
+        );
+    }
+
+    if let (Some(frames), Some(frame_dir)) = (args.frames, &args.frame_dir) {
+        write_frames(frame_dir, frames, &engine, &ast, &args)
+    } else {
+        let writer = get_output_writer(&args.output).unwrap();
+        write_pattern(writer, &engine, &ast, &args, 0)
+    }
+}
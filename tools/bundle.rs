@@ -2,7 +2,8 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use generative::io::{
-    get_input_reader, get_output_writer, read_geometries, write_geometries, GeometryFormat,
+    get_input_reader, get_output_writer, read_geometries, write_crs_header, write_geometries,
+    GeometryFormat, GeometryInputFormat,
 };
 use stderrlog::ColorChoice;
 
@@ -19,8 +20,12 @@ struct CmdlineOptions {
     input: Option<PathBuf>,
 
     /// Input geometry format.
-    #[clap(short = 'I', long, default_value_t = GeometryFormat::Wkt)]
-    input_format: GeometryFormat,
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
 
     /// Output file to write result to. Defaults to stdout.
     #[clap(short, long)]
@@ -29,6 +34,12 @@ struct CmdlineOptions {
     /// Output geometry format.
     #[clap(short = 'O', long, default_value_t = GeometryFormat::Wkt)]
     output_format: GeometryFormat,
+
+    /// Prepend a '# CRS: <CRS>' header (like EPSG:4326) to the WKT output
+    ///
+    /// The header is ignored when reading WKT.
+    #[clap(long)]
+    crs: Option<String>,
 }
 
 fn main() {
@@ -41,11 +52,15 @@ fn main() {
         .expect("Failed to initialize stderrlog");
 
     let reader = get_input_reader(&args.input).unwrap();
-    let geometries = read_geometries(reader, &args.input_format);
+    let geometries =
+        read_geometries(reader, &args.input_format).take(args.limit.unwrap_or(usize::MAX));
 
     let bundle: geo::GeometryCollection = geometries.collect();
     let geometries = std::iter::once(geo::Geometry::GeometryCollection(bundle));
 
-    let writer = get_output_writer(&args.output).unwrap();
+    let mut writer = get_output_writer(&args.output).unwrap();
+    if let Some(crs) = &args.crs {
+        write_crs_header(&mut writer, crs, args.output_format);
+    }
     write_geometries(writer, geometries, args.output_format);
 }
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use generative::graph::{centrality, largest_component, sampled_centrality, GeometryGraph};
+use generative::io::{
+    get_input_reader, get_output_writer, read_graph, write_tgf_graph_with_node_labels, TgfOptions,
+};
+use petgraph::Undirected;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use stderrlog::ColorChoice;
+
+/// Annotate the nodes of the given TGF graph with their degree and betweenness centrality
+///
+/// The output is a TGF graph, where each node is labeled with its WKT POINT, followed by its
+/// degree and betweenness, separated by the --tgf-separator.
+#[derive(Debug, Parser)]
+#[clap(name = "centrality", verbatim_doc_comment)]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// Input file to read input from. Defaults to stdin.
+    #[clap(short, long)]
+    input: Option<PathBuf>,
+
+    /// Output file to write result to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Approximate the betweenness using the shortest paths from this many random nodes
+    ///
+    /// Finding the exact betweenness is slow for large graphs.
+    #[clap(long)]
+    samples: Option<usize>,
+
+    /// The random seed to use for --samples. Use zero to let the tool pick its own random seed.
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Divide the betweenness by the number of pairs of other nodes, so that it's between 0 and 1
+    #[clap(long)]
+    normalize: bool,
+
+    /// Only keep the largest connected component of the graph, discarding any stray fragments
+    #[clap(long)]
+    largest_component: bool,
+
+    #[clap(flatten)]
+    tgf: TgfOptions,
+}
+
+fn generate_random_seed_if_not_specified(seed: u64) -> u64 {
+    if seed == 0 {
+        let mut rng = rand::thread_rng();
+        rng.gen()
+    } else {
+        seed
+    }
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    let reader = get_input_reader(&args.input).unwrap();
+    let mut graph: GeometryGraph<Undirected> = read_graph(reader);
+    if args.largest_component {
+        graph = largest_component(&graph);
+    }
+
+    let centralities = match args.samples {
+        Some(samples) => {
+            let seed = generate_random_seed_if_not_specified(args.seed);
+            log::info!("Seeding RNG with: {}", seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+            sampled_centrality(&graph, samples, &mut rng)
+        }
+        None => centrality(&graph),
+    };
+
+    let n = graph.node_count() as f64;
+    let pairs = ((n - 1.0) * (n - 2.0) / 2.0).max(1.0);
+
+    let sep = &args.tgf.separator;
+    let mut labels = vec![String::new(); graph.node_count()];
+    for (node, centrality) in centralities {
+        let betweenness = if args.normalize {
+            centrality.betweenness / pairs
+        } else {
+            centrality.betweenness
+        };
+        labels[node] = format!("{}{sep}{betweenness}", centrality.degree);
+    }
+
+    let mut writer = get_output_writer(&args.output).unwrap();
+    write_tgf_graph_with_node_labels(&mut writer, &graph, &args.tgf, &labels);
+}
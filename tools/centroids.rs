@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use generative::io::{
+    get_input_reader, get_output_writer, read_geometries, read_labeled_wkt_geometries,
+    write_geometries, write_labeled_wkt_geometries, GeometryFormat, GeometryInputFormat,
+};
+use geo::{Centroid, Geometry, GeometryCollection};
+use stderrlog::ColorChoice;
+
+/// Replace each geometry with its centroid POINT
+///
+/// Geometries without a centroid, like empty geometries, are skipped.
+#[derive(Debug, Parser)]
+#[clap(name = "centroids", verbatim_doc_comment)]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// Input file to read input from. Defaults to stdin.
+    #[clap(short, long)]
+    input: Option<PathBuf>,
+
+    /// Input geometry format.
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// Output file to write result to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output geometry format.
+    #[clap(short = 'O', long, default_value_t = GeometryFormat::Wkt)]
+    output_format: GeometryFormat,
+
+    /// Write a single centroid of all of the geometries combined, instead of one per geometry
+    ///
+    /// Only the highest dimension geometries contribute to the combined centroid. That is, if
+    /// there are any polygons, the points and lines are ignored. This has to load all of the
+    /// geometries into RAM.
+    #[clap(short, long)]
+    whole_collection: bool,
+
+    /// Pass through an optional 'id<TAB>' label before each WKT geometry to its centroid
+    ///
+    /// Only supported for WKT input and output.
+    #[clap(long, conflicts_with = "whole_collection")]
+    labeled: bool,
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    if args.labeled
+        && !(matches!(args.input_format, GeometryInputFormat::Wkt)
+            && matches!(args.output_format, GeometryFormat::Wkt))
+    {
+        eprintln!("--labeled requires WKT input and output");
+        std::process::exit(1);
+    }
+
+    let reader = get_input_reader(&args.input).unwrap();
+    let limit = args.limit.unwrap_or(usize::MAX);
+    let writer = get_output_writer(&args.output).unwrap();
+
+    if args.labeled {
+        let geometries = read_labeled_wkt_geometries(reader).take(limit);
+        let centroids = geometries.filter_map(|(label, g)| match g.centroid() {
+            Some(centroid) => Some((label, Geometry::Point(centroid))),
+            None => {
+                log::debug!("Skipping geometry without a centroid: {g:?}");
+                None
+            }
+        });
+        write_labeled_wkt_geometries(writer, centroids);
+        return;
+    }
+
+    let geometries = read_geometries(reader, &args.input_format).take(limit);
+    if args.whole_collection {
+        let collection: GeometryCollection = geometries.collect();
+        log::debug!("Finding the centroid of {} geometries", collection.len());
+        let centroid = collection.centroid().map(Geometry::Point);
+        if centroid.is_none() {
+            log::warn!("The geometries have no centroid");
+        }
+        write_geometries(writer, centroid, args.output_format);
+    } else {
+        let centroids = geometries.filter_map(|g| match g.centroid() {
+            Some(centroid) => Some(Geometry::Point(centroid)),
+            None => {
+                log::debug!("Skipping geometry without a centroid: {g:?}");
+                None
+            }
+        });
+        write_geometries(writer, centroids, args.output_format);
+    }
+}
@@ -1,15 +1,17 @@
 use std::fs::File;
 use std::io::{BufWriter, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::{Parser, ValueEnum};
-use generative::dla::{format_tgf, format_wkt, Model};
-use log::trace;
+use generative::dla::{format_tgf, format_wkt, DlaStats, Model, ModelParams, SeedPattern};
+use generative::io::write_wkt_geometries;
+use geo::Geometry;
+use log::{debug, trace};
 use stderrlog::ColorChoice;
 
 /// Specifies the plaintext output format.
 /// In all cases, the seed points will be written first.
-#[derive(Debug, Clone, ValueEnum)]
+#[derive(Debug, Clone, Copy, ValueEnum)]
 enum OutputFormat {
     /// Use TGF graph output format, labelling each node with the WKT point.
     /// Edges will be unlabeled with weight, as that can be done in a post-processing
@@ -19,6 +21,19 @@ enum OutputFormat {
     Wkt,
 }
 
+/// How to lay out the seed particles
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SeedPatternKind {
+    /// One seed at the origin, or seeds uniformly spread around the origin
+    Random,
+    /// Seeds evenly spaced along a horizontal line of length --seed-line-length
+    Line,
+    /// Seeds evenly spaced around a circle of radius --seed-circle-radius
+    Circle,
+    /// Seeds on a square grid with spacing --seed-grid-spacing
+    Grid,
+}
+
 /// Off-lattice diffusion limited aggregation
 #[derive(Debug, Parser)]
 #[clap(name = "dla")]
@@ -59,10 +74,38 @@ struct CmdlineOptions {
 
     /// Number of seed particles.
     /// If one seed particle is used, it will be placed at the origin.
-    /// Otherwise, the seed particles will be uniformly spread around the origin.
+    /// Otherwise, the seed particles will be laid out in the --seed-pattern.
     #[clap(long, default_value = "1")]
     seeds: usize,
 
+    /// The pattern to lay out the seed particles in.
+    /// The line and circle patterns grow symmetric or wall-like structures.
+    #[clap(long, default_value = "random")]
+    seed_pattern: SeedPatternKind,
+
+    /// The length of the line of seeds, centered on the origin.
+    #[clap(long, default_value = "10")]
+    seed_line_length: f64,
+
+    /// The radius of the circle of seeds, centered on the origin.
+    #[clap(long, default_value = "10")]
+    seed_circle_radius: f64,
+
+    /// The spacing between the seeds on the grid, centered on the origin.
+    #[clap(long, default_value = "5")]
+    seed_grid_spacing: f64,
+
+    /// Number of colonies to split the seed particles into.
+    /// Each particle belongs to the colony of the particle it joined, and the colony id is written
+    /// after the WKT POINT in the TGF node labels.
+    #[clap(long, default_value = "1")]
+    colonies: usize,
+
+    /// Allow particles to join one colony while touching another, letting the colonies merge.
+    /// Otherwise the colonies are kept apart.
+    #[clap(long)]
+    allow_merge: bool,
+
     /// The random seed to use, for reproducibility. Zero for a random seed.
     #[clap(long, default_value = "0")]
     seed: u64,
@@ -75,6 +118,86 @@ struct CmdlineOptions {
     /// Number of particles to add.
     #[clap(short, long, default_value = "10000")]
     particles: usize,
+
+    /// Thicken the particle graph into overlapping POLYGONs with the given width, instead of
+    /// writing the graph.
+    ///
+    /// Each edge is buffered into a capsule around the edge. The polygons are written as WKT.
+    #[clap(long, conflicts_with = "format")]
+    thickness: Option<f64>,
+
+    /// Taper the --thickness by this fraction, from the full width at the seed particles, to
+    /// (1 - taper) times the width at the particles furthest from the seeds.
+    #[clap(long, default_value = "0", requires = "thickness")]
+    taper: f64,
+
+    /// Draw each particle as a circle POLYGON with the given radius, instead of writing the graph.
+    ///
+    /// The render radius is independent of the --particle-spacing the particles grew with, so a
+    /// tightly packed cluster can be drawn as overlapping bubbles. The polygons are written as WKT.
+    #[clap(long, conflicts_with_all = ["format", "thickness"])]
+    render_radius: Option<f64>,
+
+    /// Write each particle to the output as soon as it attaches, instead of writing the whole
+    /// model at the end.
+    ///
+    /// The seed particles are written as WKT POINTs, followed by a WKT LINESTRING from each
+    /// particle's parent to the new particle. The output is flushed after each particle, so that
+    /// the growth can be followed with 'tail -f'.
+    #[clap(long, conflicts_with_all = ["format", "thickness", "render_radius"])]
+    stream: bool,
+
+    /// Wrap the particles around a width,height rectangle centered on the origin, for making
+    /// seamless tiles.
+    ///
+    /// Particles that leave one side of the rectangle re-enter on the opposite side. The edges
+    /// between particles on opposite sides of the rectangle will cross the whole rectangle.
+    #[clap(long, value_delimiter = ',')]
+    periodic: Option<Vec<f64>>,
+
+    /// Pull the random walk toward the x,y attractor point, for radially biased growth.
+    ///
+    /// The pull is inversely proportional to the distance to the attractor, and is scaled by
+    /// --attractor-strength.
+    #[clap(long, value_delimiter = ',', allow_hyphen_values = true)]
+    attractor: Option<Vec<f64>>,
+
+    /// The strength of the --attractor pull.
+    ///
+    /// Positive strengths pull the particles toward the attractor, and negative strengths push
+    /// them away from it. The larger the strength, the farther from the attractor the pull
+    /// dominates the random walk.
+    #[clap(
+        long,
+        default_value = "1",
+        allow_hyphen_values = true,
+        requires = "attractor"
+    )]
+    attractor_strength: f64,
+
+    /// Write a snapshot of the model to --frame-dir every N particles, for making animations.
+    #[clap(long, requires = "frame_dir")]
+    frame_every: Option<usize>,
+
+    /// The directory to write the --frame-every snapshots to. Will be created if it doesn't exist.
+    ///
+    /// Each frame is a complete snapshot of the model in the --format output format.
+    #[clap(long, requires = "frame_every")]
+    frame_dir: Option<PathBuf>,
+
+    /// Write the regions of the kd-tree spatial index to the given file as WKT POLYGONs, to see
+    /// how it subdivides space.
+    ///
+    /// Each region is the bounding box of a node in the tree, from the root down to the leaves.
+    #[clap(long)]
+    dump_index: Option<PathBuf>,
+
+    /// Print structural metrics of the finished model to stderr, for tuning the parameters.
+    ///
+    /// Reports the number of branches, their mean length, the mean branching and curl angles, and
+    /// a box-counting estimate of the fractal dimension.
+    #[clap(long)]
+    report: bool,
 }
 
 impl CmdlineOptions {
@@ -95,6 +218,54 @@ impl CmdlineOptions {
         }
     }
 }
+
+fn write_model(writer: &mut BufWriter<Box<dyn Write>>, model: &Model, args: &CmdlineOptions) {
+    if let Some(thickness) = args.thickness {
+        let polygons = model.thicken(thickness, args.taper);
+        write_wkt_geometries(writer, polygons.into_iter().map(Geometry::Polygon));
+        return;
+    }
+    if let Some(radius) = args.render_radius {
+        let circles = model.circles(radius);
+        write_wkt_geometries(writer, circles.into_iter().map(Geometry::Polygon));
+        return;
+    }
+
+    match args.format {
+        OutputFormat::Tgf => {
+            format_tgf(writer, &model.particle_graph);
+        }
+        OutputFormat::Wkt => {
+            format_wkt(writer, &model.particle_graph);
+        }
+    };
+}
+
+fn print_report(stats: &DlaStats) {
+    eprintln!("particles: {}", stats.particles);
+    eprintln!("branches: {}", stats.branches);
+    eprintln!("mean branch length: {:.3}", stats.mean_branch_length);
+    eprintln!("mean branch angle: {:.1} degrees", stats.mean_branch_angle);
+    eprintln!("mean curl: {:.1} degrees", stats.mean_curl);
+    eprintln!("fractal dimension: {:.3}", stats.fractal_dimension);
+}
+
+fn write_frame(dir: &Path, frame: usize, model: &Model, args: &CmdlineOptions) {
+    let extension = match args.format {
+        _ if args.thickness.is_some() || args.render_radius.is_some() => "wkt",
+        OutputFormat::Tgf => "tgf",
+        OutputFormat::Wkt => "wkt",
+    };
+    let path = dir.join(format!("frame-{frame:06}.{extension}"));
+    trace!("Writing frame {}", path.display());
+    let file = match File::create(&path) {
+        Err(why) => panic!("Couldn't create: {} because: {}", path.display(), why),
+        Ok(file) => file,
+    };
+    let mut writer: BufWriter<Box<dyn Write>> = BufWriter::new(Box::new(file));
+    write_model(&mut writer, model, args);
+}
+
 fn main() {
     let args = CmdlineOptions::parse();
 
@@ -104,29 +275,103 @@ fn main() {
         .init()
         .unwrap();
 
-    let mut model = Model::new(
-        args.dimensions,
-        // TODO: Seed type.
-        args.seeds,
-        args.seed,
-        args.particle_spacing,
-        args.attraction_distance,
-        args.min_move_distance,
-        args.stubbornness,
-        args.stickiness,
-    );
+    if args.thickness.is_some_and(|t| t <= 0.0) || !(0.0..=1.0).contains(&args.taper) {
+        eprintln!("--thickness must be positive, and --taper must be between 0 and 1");
+        std::process::exit(1);
+    }
+    if args.render_radius.is_some_and(|r| r <= 0.0) {
+        eprintln!("--render-radius must be positive");
+        std::process::exit(1);
+    }
+
+    let periodic = args.periodic.as_ref().map(|p| match p.as_slice() {
+        [width, height] if *width > 0.0 && *height > 0.0 => [*width, *height],
+        _ => {
+            eprintln!("--periodic must be given as a positive width,height, got: {p:?}");
+            std::process::exit(1);
+        }
+    });
 
-    model.run(args.particles);
+    let attractor = args.attractor.as_ref().map(|a| match a.as_slice() {
+        [x, y] => ([*x, *y], args.attractor_strength),
+        _ => {
+            eprintln!("--attractor must be given as x,y, got: {a:?}");
+            std::process::exit(1);
+        }
+    });
 
-    trace!("Model {:?}", model);
+    let seed_pattern = match args.seed_pattern {
+        SeedPatternKind::Random => SeedPattern::Random,
+        SeedPatternKind::Line => SeedPattern::Line(args.seed_line_length),
+        SeedPatternKind::Circle => SeedPattern::Circle(args.seed_circle_radius),
+        SeedPatternKind::Grid => SeedPattern::Grid(args.seed_grid_spacing),
+    };
+
+    let mut model = Model::new(ModelParams {
+        dimensions: args.dimensions,
+        seeds: args.seeds,
+        seed_pattern,
+        colonies: args.colonies,
+        allow_merge: args.allow_merge,
+        seed: args.seed,
+        particle_spacing: args.particle_spacing,
+        attraction_distance: args.attraction_distance,
+        min_move_distance: args.min_move_distance,
+        stubbornness: args.stubbornness,
+        stickiness: args.stickiness,
+        periodic,
+        attractor,
+        record_index: args.dump_index.is_some(),
+    });
 
     let mut writer = args.get_output_writer();
-    match args.format {
-        OutputFormat::Tgf => {
-            format_tgf(&mut writer, model.particle_graph);
+    if args.stream {
+        write_wkt_geometries(&mut writer, model.seed_points().map(Geometry::Point));
+        writer.flush().expect("Failed to flush output");
+    }
+
+    let frames = args.frame_every.zip(args.frame_dir.as_ref());
+    if let Some((_, frame_dir)) = frames {
+        if let Err(why) = std::fs::create_dir_all(frame_dir) {
+            panic!("Couldn't create: {} because: {}", frame_dir.display(), why);
         }
-        OutputFormat::Wkt => {
-            format_wkt(&mut writer, model.particle_graph);
+    }
+    model.run_with_callback(args.particles, |model, i| {
+        if args.stream {
+            if let Some(edge) = model.newest_edge() {
+                write_wkt_geometries(&mut writer, std::iter::once(Geometry::Line(edge)));
+                writer.flush().expect("Failed to flush output");
+            }
         }
-    };
+        if let Some((frame_every, frame_dir)) = frames {
+            let frame_every = frame_every.max(1);
+            let added = i + 1;
+            if added % frame_every == 0 {
+                write_frame(frame_dir, added / frame_every, model, &args);
+            }
+        }
+    });
+
+    trace!("Model {:?}", model);
+
+    if !args.stream {
+        write_model(&mut writer, &model, &args);
+    }
+
+    if let Some(path) = &args.dump_index {
+        let file = match File::create(path) {
+            Err(why) => panic!("Couldn't create: {} because: {}", path.display(), why),
+            Ok(file) => file,
+        };
+        let regions = model.index_regions();
+        debug!("Writing {} spatial index regions", regions.len());
+        let regions = regions
+            .into_iter()
+            .map(|r| Geometry::Polygon(r.to_polygon()));
+        write_wkt_geometries(BufWriter::new(file), regions);
+    }
+
+    if args.report {
+        print_report(&model.statistics());
+    }
 }
@@ -1,13 +1,16 @@
 use std::path::PathBuf;
 
 use clap::{Parser, ValueEnum};
-use generative::graph::GeometryGraph;
+use generative::graph::{largest_component, simplify_multigraph, GeometryGraph};
 use generative::io::{
-    get_input_reader, get_output_writer, read_geometries, read_tgf_graph, write_geometries,
-    write_graph, GeometryFormat, GraphFormat,
+    get_input_reader, get_output_writer, read_geometries, read_graph, write_geometries,
+    write_graph_with_options, GeometryFormat, GraphFormat, Progress, TgfOptions,
 };
 use generative::noding::{node, polygonize};
-use generative::snap::{snap_geoms, snap_graph, SnappingStrategy};
+use generative::snap::{
+    estimate_graph_tolerance, estimate_tolerance, snap_geoms, snap_graph,
+    CollectionSnappingStrategy, Snapping, SnappingStrategy, Tolerance,
+};
 use geo::Geometry;
 use stderrlog::ColorChoice;
 
@@ -15,6 +18,8 @@ use stderrlog::ColorChoice;
 enum CliSnappingStrategy {
     ClosestPoint,
     RegularGrid,
+    VertexToEdge,
+    EndpointsOnly,
 }
 
 impl std::fmt::Display for CliSnappingStrategy {
@@ -23,6 +28,8 @@ impl std::fmt::Display for CliSnappingStrategy {
             // important: Should match clap::ValueEnum format
             CliSnappingStrategy::ClosestPoint => write!(f, "closest-point"),
             CliSnappingStrategy::RegularGrid => write!(f, "regular-grid"),
+            CliSnappingStrategy::VertexToEdge => write!(f, "vertex-to-edge"),
+            CliSnappingStrategy::EndpointsOnly => write!(f, "endpoints-only"),
         }
     }
 }
@@ -39,6 +46,14 @@ struct CmdlineOptions {
     #[clap(short, long)]
     input: Option<PathBuf>,
 
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// Periodically log how many geometries have been processed to stderr
+    #[clap(long)]
+    progress: bool,
+
     /// Output file to write result to. Defaults to stdout.
     #[clap(short, long)]
     output: Option<PathBuf>,
@@ -51,6 +66,9 @@ struct CmdlineOptions {
     #[clap(long, default_value_t = GraphFormat::Tgf)]
     graph_format: GraphFormat,
 
+    #[clap(flatten)]
+    tgf: TgfOptions,
+
     /// Convert the given geometries to a graph. The default
     #[clap(long, conflicts_with = "graph2geom")]
     geom2graph: bool,
@@ -59,13 +77,29 @@ struct CmdlineOptions {
     #[clap(long, conflicts_with = "geom2graph")]
     graph2geom: bool,
 
-    /// Snap together vertices closer than the given tolerance
+    /// Snap together vertices closer than the given tolerance, or 'auto' to estimate it
+    ///
+    /// The tolerance is a distance, in the same units as the coordinates, for every strategy. The
+    /// estimate is a tenth of the median distance between neighboring vertices.
     #[clap(short, long)]
-    tolerance: Option<f64>,
+    tolerance: Option<Tolerance>,
 
     /// The strategy to use for snapping
     #[clap(long, default_value_t = CliSnappingStrategy::ClosestPoint)]
     snap_strategy: CliSnappingStrategy,
+
+    /// Remove any self-loops and parallel edges from the graph
+    ///
+    /// Applies after snapping when converting geometries to a graph, and before polygonizing when
+    /// converting a graph to geometries.
+    #[clap(long)]
+    clean: bool,
+
+    /// Only keep the largest connected component of the graph, discarding any stray fragments
+    ///
+    /// Applies after --clean.
+    #[clap(long)]
+    largest_component: bool,
 }
 
 fn main() {
@@ -80,37 +114,73 @@ fn main() {
     let reader = get_input_reader(&args.input).unwrap();
     let writer = get_output_writer(&args.output).unwrap();
 
-    let strategy = match args.snap_strategy {
+    let strategy = |tolerance| match args.snap_strategy {
         CliSnappingStrategy::ClosestPoint => {
-            SnappingStrategy::ClosestPoint(args.tolerance.unwrap_or_default())
+            Snapping::Vertex(SnappingStrategy::ClosestPoint(tolerance))
         }
         CliSnappingStrategy::RegularGrid => {
-            SnappingStrategy::RegularGrid(args.tolerance.unwrap_or_default())
+            Snapping::Vertex(SnappingStrategy::RegularGrid(tolerance))
+        }
+        CliSnappingStrategy::VertexToEdge => {
+            Snapping::Collection(CollectionSnappingStrategy::VertexToEdge(tolerance))
         }
+        CliSnappingStrategy::EndpointsOnly => {
+            Snapping::Collection(CollectionSnappingStrategy::EndpointsOnly(tolerance))
+        }
+    };
+    let estimated = |tolerance| {
+        log::info!("Estimated snapping tolerance {tolerance}");
+        tolerance
     };
 
     if args.geom2graph || !args.graph2geom {
-        let geometries = read_geometries(reader, &args.geometry_format);
+        let geometries = read_geometries(reader, &args.geometry_format.into())
+            .take(args.limit.unwrap_or(usize::MAX));
+        let geometries = Progress::new(geometries, args.progress);
         let graph = node::<_, petgraph::Undirected>(geometries);
 
-        let graph = if args.tolerance.is_some() {
-            snap_graph(graph, strategy)
-        } else {
-            graph
+        let mut graph = match args.tolerance {
+            Some(Tolerance::Fixed(tolerance)) => snap_graph(graph, strategy(tolerance)),
+            Some(Tolerance::Auto) => {
+                let tolerance = estimated(estimate_graph_tolerance(&graph));
+                snap_graph(graph, strategy(tolerance))
+            }
+            None => graph,
         };
+        if args.clean {
+            simplify_multigraph(&mut graph);
+        }
+        if args.largest_component {
+            graph = largest_component(&graph);
+        }
 
-        write_graph(writer, &graph, &args.graph_format);
+        write_graph_with_options(writer, &graph, &args.graph_format, &args.tgf);
     } else {
-        let graph: GeometryGraph<petgraph::Undirected> = read_tgf_graph(reader);
-        let (polygons, dangles) = polygonize(&graph);
+        let mut graph: GeometryGraph<petgraph::Undirected> = read_graph(reader);
+        if args.clean {
+            simplify_multigraph(&mut graph);
+        }
+        if args.largest_component {
+            graph = largest_component(&graph);
+        }
+        let (polygons, dangles, cut_edges, invalid_ring_lines) = polygonize(&graph);
+        log::debug!(
+            "Polygonizing found {} cut edges and {} invalid ring lines",
+            cut_edges.len(),
+            invalid_ring_lines.len()
+        );
         let polygons = polygons.into_iter().map(Geometry::Polygon);
         let dangles = dangles.into_iter().map(Geometry::LineString);
         let geometries = polygons.chain(dangles);
 
-        let geometries = if args.tolerance.is_some() {
-            snap_geoms(geometries, strategy)
-        } else {
-            Box::new(geometries)
+        let geometries = match args.tolerance {
+            Some(Tolerance::Fixed(tolerance)) => snap_geoms(geometries, strategy(tolerance), false),
+            Some(Tolerance::Auto) => {
+                let geometries: Vec<_> = geometries.collect();
+                let tolerance = estimated(estimate_tolerance(&geometries));
+                snap_geoms(geometries.into_iter(), strategy(tolerance), false)
+            }
+            None => Box::new(geometries),
         };
 
         write_geometries(writer, geometries, args.geometry_format);
@@ -1,12 +1,14 @@
 use std::path::PathBuf;
 
 use clap::{Parser, ValueEnum};
-use generative::graph::GeometryGraph;
+use generative::graph::{dual_graph, faces, GeometryGraph};
 use generative::io::{
-    get_output_writer, write_geometries, write_graph, GeometryFormat, GraphFormat,
+    get_output_writer, write_geometries, write_graph_with_options,
+    write_tgf_graph_with_node_labels, GeometryFormat, GraphFormat, TgfOptions,
 };
 #[cfg(feature = "cxx-bindings")]
 use generative::noding::{node, polygonize};
+use generative::penrose::{p3_tiling, PenroseConfiguration};
 use generative::snap::{snap_geoms, SnappingStrategy};
 use geo::{Coord, CoordsIter, Geometry, LineString, Point, Polygon};
 use petgraph::Undirected;
@@ -20,8 +22,10 @@ enum GridFormat {
     Lines,
     /// Output the grid points in WKT
     Points,
+    /// Output the dual graph in TGF, with a node at the center of each cell, and an edge between
+    /// each pair of cells that share a side
+    Dual,
     /// Output the grid cells as WKT POLYGONs
-    #[cfg(feature = "cxx-bindings")]
     Cells,
 }
 
@@ -32,7 +36,7 @@ impl std::fmt::Display for GridFormat {
             GridFormat::Graph => write!(f, "graph"),
             GridFormat::Lines => write!(f, "lines"),
             GridFormat::Points => write!(f, "points"),
-            #[cfg(feature = "cxx-bindings")]
+            GridFormat::Dual => write!(f, "dual"),
             GridFormat::Cells => write!(f, "cells"),
         }
     }
@@ -46,6 +50,8 @@ enum GridType {
     Ragged,
     Hexagon,
     Radial,
+    /// An aperiodic Penrose P3 tiling of thick and thin rhombi
+    Penrose,
 }
 impl std::fmt::Display for GridType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -56,6 +62,7 @@ impl std::fmt::Display for GridType {
             GridType::Ragged => write!(f, "ragged"),
             GridType::Hexagon => write!(f, "hexagon"),
             GridType::Radial => write!(f, "radial"),
+            GridType::Penrose => write!(f, "penrose"),
         }
     }
 }
@@ -76,15 +83,30 @@ struct CmdlineOptions {
     #[clap(short = 'O', long, default_value_t = GridFormat::Points)]
     output_format: GridFormat,
 
+    #[clap(flatten)]
+    tgf: TgfOptions,
+
+    /// Label each node in the graph output with its i,j grid indices, after its WKT POINT.
+    ///
+    /// The indices are the column and row of the node. For hex grids, they're the column and row
+    /// of the node in a brick-like layout, where each row zig-zags along the top or bottom of a
+    /// row of hexagons. Not supported for radial grids or Penrose tilings.
+    #[clap(long, conflicts_with = "no_labels")]
+    with_indices: bool,
+
     /// The type of grid to generate
     #[clap(short, long, default_value_t = GridType::Quad)]
     grid_type: GridType,
 
     /// The number of cells along the x-axis, or angular division if using radial grids.
+    ///
+    /// Ignored for Penrose tilings.
     #[clap(short = 'W', long, default_value_t = 5)]
     width: usize,
 
     /// The number of cells along the y-axis, or radius if using radial grids.
+    ///
+    /// Ignored for Penrose tilings.
     #[clap(short = 'H', long, default_value_t = 5)]
     height: usize,
 
@@ -92,7 +114,8 @@ struct CmdlineOptions {
     #[clap(short = 's', long)]
     size: Option<f64>,
 
-    /// The width of each grid cell. Ignored for radial grids.
+    /// The width of each grid cell. Ignored for radial grids. The rhombus side length for Penrose
+    /// tilings.
     #[clap(long)]
     size_x: Option<f64>,
 
@@ -114,6 +137,18 @@ struct CmdlineOptions {
     /// Only used for radial grids
     #[clap(long, conflicts_with = "ring_fill_points")]
     ring_fill_ratio: Option<f64>,
+
+    /// The number of times to subdivide the initial Penrose configuration
+    ///
+    /// Only used for Penrose tilings
+    #[clap(long, default_value_t = 5)]
+    generations: usize,
+
+    /// The initial configuration to subdivide
+    ///
+    /// Only used for Penrose tilings
+    #[clap(long, default_value_t = PenroseConfiguration::Sun)]
+    configuration: PenroseConfiguration,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -140,6 +175,7 @@ fn grid(
         GridType::Ragged => ragged_grid(width, height, size_x, size_y),
         GridType::Hexagon => hex_grid(width, height, size_x, size_y),
         GridType::Radial => unreachable!("Radial grid types are implemented differently"),
+        GridType::Penrose => unreachable!("Penrose tilings are implemented differently"),
     }
 }
 
@@ -431,7 +467,7 @@ fn hex_grid(width: usize, height: usize, size_x: f64, _size_y: f64) -> GeometryG
         }
     }
 
-    let cols_is_even = width % 2 == 0;
+    let cols_is_even = width.is_multiple_of(2);
     let adjacency_offset = width + 1;
     let mut n = 0;
     for row in 0..rows {
@@ -470,14 +506,48 @@ fn hex_grid(width: usize, height: usize, size_x: f64, _size_y: f64) -> GeometryG
 
     // Remove the two dangling extra nodes that were added to make the indexing math suck
     // (slightly) less.
-    let nodes_to_remove = if cols_is_even {
+    let nodes_to_remove = hex_extra_nodes(width, height);
+    graph.retain_nodes(move |_, idx| !nodes_to_remove.contains(&idx.index()));
+
+    graph
+}
+
+/// The indices of the two dangling nodes that [hex_grid] removes
+fn hex_extra_nodes(width: usize, height: usize) -> [usize; 2] {
+    let cols = width + 1;
+    let nodes = (2 * height + 2) * cols;
+    if width.is_multiple_of(2) {
         [width, nodes - cols]
     } else {
         [nodes - cols, nodes - 1]
-    };
-    graph.retain_nodes(move |_, idx| !nodes_to_remove.contains(&idx.index()));
+    }
+}
 
-    graph
+/// The (i, j) column and row of each node in the graph created by [grid], in node index order
+fn grid_indices(width: usize, height: usize, grid_type: GridType) -> Vec<(usize, usize)> {
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    let (cols, rows) = match grid_type {
+        GridType::Triangle | GridType::Quad => (width + 1, height + 1),
+        GridType::Ragged => (width + 2, height + 1),
+        GridType::Hexagon => (width + 1, 2 * height + 2),
+        GridType::Radial | GridType::Penrose => {
+            unreachable!("{grid_type} grids don't have grid indices")
+        }
+    };
+    let mut indices: Vec<_> = (0..rows)
+        .flat_map(|j| (0..cols).map(move |i| (i, j)))
+        .collect();
+    if grid_type == GridType::Hexagon {
+        // Graph::retain_nodes removes the nodes back to front, swapping the last node into the
+        // removed node's place, so do the same
+        for n in hex_extra_nodes(width, height).into_iter().rev() {
+            indices.swap_remove(n);
+        }
+    }
+    indices
 }
 
 fn from_polar(r: f64, theta: f64) -> Coord {
@@ -585,16 +655,29 @@ fn main() {
     // Exit early with a nice error message here, so that I can use unreachable!() later
     if !cfg!(feature = "cxx-bindings")
         && args.grid_type == GridType::Radial
-        && args.output_format == GridFormat::Graph
+        && matches!(
+            args.output_format,
+            GridFormat::Graph | GridFormat::Dual | GridFormat::Cells
+        )
     {
-        // No need to check if args.output_format == GridFormat::Cells, because that's hidden
-        // behind the cxx-bindings feature already.
         eprintln!(
             "Using the {} output format with radial grids requires the 'cxx-bindings' feature",
             args.output_format
         );
         std::process::exit(1);
     }
+    if args.with_indices
+        && (args.output_format != GridFormat::Graph
+            || matches!(args.grid_type, GridType::Radial | GridType::Penrose))
+    {
+        eprintln!(
+            "--with-indices requires the {} output format, and a grid type other than {} or {}",
+            GridFormat::Graph,
+            GridType::Radial,
+            GridType::Penrose
+        );
+        std::process::exit(1);
+    }
 
     let (mut size_x, mut size_y) = if let Some(size) = args.size {
         (size, size)
@@ -608,7 +691,7 @@ fn main() {
         size_y = size;
     }
 
-    let writer = get_output_writer(&args.output).unwrap();
+    let mut writer = get_output_writer(&args.output).unwrap();
 
     // Radial grids need to be created as geometry-first instead of grid-first, to enable
     // outputting the radial spokes as LINESTRINGs, and the concentric rings as POLYGONs, which
@@ -638,16 +721,27 @@ fn main() {
                     points.extend(new_points);
                 }
                 // Snap points as a way of deduplicating vertices
-                let points = snap_geoms(points.into_iter(), SnappingStrategy::ClosestPoint(0.0));
+                let points = snap_geoms(
+                    points.into_iter(),
+                    SnappingStrategy::ClosestPoint(0.0),
+                    false,
+                );
                 write_geometries(writer, points, GeometryFormat::Wkt);
             }
             #[cfg(feature = "cxx-bindings")]
-            GridFormat::Graph | GridFormat::Cells => {
+            GridFormat::Graph | GridFormat::Dual | GridFormat::Cells => {
                 let graph: GeometryGraph = node(geoms);
                 if args.output_format == GridFormat::Graph {
-                    write_graph(writer, &graph, &GraphFormat::Tgf);
+                    write_graph_with_options(writer, &graph, &GraphFormat::Tgf, &args.tgf);
+                } else if args.output_format == GridFormat::Dual {
+                    write_graph_with_options(
+                        writer,
+                        &dual_graph(&graph),
+                        &GraphFormat::Tgf,
+                        &args.tgf,
+                    );
                 } else {
-                    let (polygons, dangles) = polygonize(&graph);
+                    let (polygons, dangles, _, _) = polygonize(&graph);
                     let polygons = polygons.into_iter().map(Geometry::Polygon);
                     let dangles = dangles.into_iter().map(Geometry::LineString);
                     let geoms = polygons.chain(dangles);
@@ -655,28 +749,43 @@ fn main() {
                 }
             }
             #[cfg(not(feature = "cxx-bindings"))]
-            GridFormat::Graph => {
-                unreachable!("Graph and Cells format not possible without cxx-bindings feature")
+            GridFormat::Graph | GridFormat::Dual | GridFormat::Cells => {
+                unreachable!(
+                    "Graph, Dual, and Cells formats not possible without cxx-bindings feature"
+                )
             }
         }
     } else {
-        let graph = grid(args.width, args.height, size_x, size_y, args.grid_type);
+        let graph = if args.grid_type == GridType::Penrose {
+            p3_tiling(args.configuration, args.generations, size_x)
+        } else {
+            grid(args.width, args.height, size_x, size_y, args.grid_type)
+        };
 
         match args.output_format {
-            GridFormat::Graph => write_graph(writer, &graph, &GraphFormat::Tgf),
-            GridFormat::Lines => write_graph(writer, &graph, &GraphFormat::Wkt),
+            GridFormat::Graph if args.with_indices => {
+                let indices = grid_indices(args.width, args.height, args.grid_type);
+                debug_assert_eq!(indices.len(), graph.node_count());
+                let labels: Vec<_> = indices.iter().map(|(i, j)| format!("{i},{j}")).collect();
+                write_tgf_graph_with_node_labels(&mut writer, &graph, &args.tgf, &labels);
+            }
+            GridFormat::Graph => {
+                write_graph_with_options(writer, &graph, &GraphFormat::Tgf, &args.tgf)
+            }
+            GridFormat::Lines => {
+                write_graph_with_options(writer, &graph, &GraphFormat::Wkt, &args.tgf)
+            }
+            GridFormat::Dual => {
+                write_graph_with_options(writer, &dual_graph(&graph), &GraphFormat::Tgf, &args.tgf)
+            }
             GridFormat::Points => write_geometries(
                 writer,
                 graph.node_weights().map(|p| Geometry::Point(*p)),
                 GeometryFormat::Wkt,
             ),
-            #[cfg(feature = "cxx-bindings")]
             GridFormat::Cells => {
-                let (polygons, dangles) = polygonize(&graph);
-                let polygons = polygons.into_iter().map(Geometry::Polygon);
-                let dangles = dangles.into_iter().map(Geometry::LineString);
-                let geoms = polygons.chain(dangles);
-                write_geometries(writer, geoms, GeometryFormat::Wkt);
+                let cells = faces(&graph).into_iter().map(Geometry::Polygon);
+                write_geometries(writer, cells, GeometryFormat::Wkt);
             }
         }
     }
@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use generative::flatten::flatten_nested_geometries;
+use generative::hatch::hatch;
+use generative::io::{
+    get_input_reader, get_output_writer, read_geometries, write_crs_header, write_geometries,
+    GeometryFormat, GeometryInputFormat,
+};
+use geo::{Geometry, Polygon};
+use stderrlog::ColorChoice;
+
+/// Fill polygons with parallel hatch lines
+///
+/// Each POLYGON is replaced by a MULTILINESTRING of its hatch lines, clipped to the polygon's
+/// interior. Non-polygonal geometries are passed through unchanged.
+#[derive(Debug, Parser)]
+#[clap(name = "hatch", verbatim_doc_comment)]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// Input file to read input from. Defaults to stdin.
+    #[clap(short, long)]
+    input: Option<PathBuf>,
+
+    /// Input geometry format.
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// Output file to write result to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output geometry format.
+    #[clap(short = 'O', long, default_value_t = GeometryFormat::Wkt)]
+    output_format: GeometryFormat,
+
+    /// Prepend a '# CRS: <CRS>' header (like EPSG:4326) to the WKT output
+    ///
+    /// The header is ignored when reading WKT.
+    #[clap(long)]
+    crs: Option<String>,
+
+    /// The angle of the hatch lines in degrees, counter clockwise from the x-axis
+    #[clap(short, long, default_value_t = 45.0, allow_hyphen_values = true)]
+    angle: f64,
+
+    /// The distance between adjacent hatch lines
+    #[clap(short, long, default_value_t = 1.0)]
+    spacing: f64,
+
+    /// Cross-hatch with a second set of lines at this angle in degrees
+    #[clap(short, long, allow_hyphen_values = true)]
+    cross: Option<f64>,
+
+    /// Also output each polygon, so that the hatching has an outline
+    #[clap(long)]
+    outline: bool,
+}
+
+fn hatch_polygon(polygon: Polygon, args: &CmdlineOptions) -> Vec<Geometry> {
+    let mut lines = hatch(&polygon, args.angle, args.spacing);
+    if let Some(cross) = args.cross {
+        lines.0.extend(hatch(&polygon, cross, args.spacing));
+    }
+
+    let mut geometries = vec![Geometry::MultiLineString(lines)];
+    if args.outline {
+        geometries.push(Geometry::Polygon(polygon));
+    }
+    geometries
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    if args.spacing <= 0.0 {
+        eprintln!("--spacing must be positive, got {}", args.spacing);
+        std::process::exit(1);
+    }
+
+    let reader = get_input_reader(&args.input).unwrap();
+    let geometries =
+        read_geometries(reader, &args.input_format).take(args.limit.unwrap_or(usize::MAX));
+    let geometries = flatten_nested_geometries(geometries);
+    let geometries = geometries.flat_map(|g| match g {
+        Geometry::Polygon(p) => hatch_polygon(p, &args),
+        Geometry::Rect(r) => hatch_polygon(r.to_polygon(), &args),
+        Geometry::Triangle(t) => hatch_polygon(t.to_polygon(), &args),
+        _ => vec![g],
+    });
+
+    let mut writer = get_output_writer(&args.output).unwrap();
+    if let Some(crs) = &args.crs {
+        write_crs_header(&mut writer, crs, args.output_format);
+    }
+    write_geometries(writer, geometries, args.output_format);
+}
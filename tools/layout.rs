@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+use generative::graph::{largest_component, GeometryGraph};
+use generative::io::{
+    get_input_reader, get_output_writer, read_graph, write_graph_with_options, GraphFormat,
+    TgfOptions,
+};
+use generative::layout::{force_directed, spectral, ForceDirectedParams};
+use petgraph::Undirected;
+use stderrlog::ColorChoice;
+
+#[derive(Debug, Clone, ValueEnum)]
+enum Method {
+    /// Relax the input node positions, with edges as springs and repulsion between every node
+    ForceDirected,
+    /// Place the nodes using the eigenvectors of the graph Laplacian, ignoring the input positions
+    Spectral,
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            // important: Should match clap::ValueEnum format
+            Method::ForceDirected => write!(f, "force-directed"),
+            Method::Spectral => write!(f, "spectral"),
+        }
+    }
+}
+
+/// Lay out the node positions of a graph with a force-directed or spectral layout
+///
+/// Edges act like springs pulling their nodes together, and every node repels every other node.
+/// The spectral layout ignores the input positions, and gives a reproducible drawing of graphs
+/// without meaningful coordinates. Useful for turning abstract graphs into plottable drawings.
+///
+/// Examples:
+///     grid --output-format graph | layout --iterations 200 | wkt2svg
+///     grid --output-format graph | layout --method spectral | wkt2svg
+#[derive(Debug, Parser)]
+#[clap(name = "layout", verbatim_doc_comment)]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// Input file to read input from. Defaults to stdin.
+    ///
+    /// Input format is expected to be Trivial Graph Format.
+    #[clap(short, long)]
+    input: Option<PathBuf>,
+
+    /// Output file to write result to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output format
+    #[clap(short = 'O', long, default_value_t = GraphFormat::Tgf)]
+    output_format: GraphFormat,
+
+    #[clap(flatten)]
+    tgf: TgfOptions,
+
+    /// The layout method to use
+    #[clap(short, long, default_value_t = Method::ForceDirected)]
+    method: Method,
+
+    /// The number of force-directed relaxation iterations to perform
+    #[clap(short = 'n', long, default_value_t = 100)]
+    iterations: usize,
+
+    /// The ideal distance between adjacent nodes
+    ///
+    /// The spectral layout is scaled so that the average edge has this length.
+    #[clap(short, long, default_value_t = 1.0)]
+    edge_length: f64,
+
+    /// The maximum distance a force-directed node may move in the first iteration. Cools linearly
+    /// to zero.
+    #[clap(short, long, default_value_t = 1.0)]
+    temperature: f64,
+
+    /// Only keep the largest connected component of the graph, discarding any stray fragments
+    #[clap(long)]
+    largest_component: bool,
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    let reader = get_input_reader(&args.input).unwrap();
+    let mut graph: GeometryGraph<Undirected> = read_graph(reader);
+    if args.largest_component {
+        graph = largest_component(&graph);
+    }
+
+    log::info!(
+        "Laying out graph with {} nodes and {} edges using the {} method",
+        graph.node_count(),
+        graph.edge_count(),
+        args.method
+    );
+    match args.method {
+        Method::ForceDirected => {
+            let params = ForceDirectedParams {
+                iterations: args.iterations,
+                edge_length: args.edge_length,
+                initial_temperature: args.temperature,
+            };
+            force_directed(&mut graph, &params);
+        }
+        Method::Spectral => {
+            graph = spectral(&graph);
+            for node in graph.node_weights_mut() {
+                node.0 = node.0 * args.edge_length;
+            }
+        }
+    }
+
+    let writer = get_output_writer(&args.output).unwrap();
+    write_graph_with_options(writer, &graph, &args.output_format, &args.tgf);
+}
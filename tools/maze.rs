@@ -0,0 +1,260 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+use generative::graph::{largest_component, GeometryGraph};
+use generative::io::{
+    get_input_reader, get_output_writer, read_graph, write_geometries, GeometryFormat,
+};
+use geo::{Geometry, LineString};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::unionfind::UnionFind;
+use petgraph::visit::EdgeRef;
+use petgraph::Undirected;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use stderrlog::ColorChoice;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum MazeAlgorithm {
+    /// Randomized depth-first search. Makes long winding passages with few dead ends.
+    Dfs,
+    /// Randomized Kruskal's algorithm. Makes lots of short dead ends.
+    Kruskal,
+    /// Wilson's algorithm. Picks a spanning tree uniformly at random.
+    Wilson,
+}
+
+impl std::fmt::Display for MazeAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            // important: Should match clap::ValueEnum format
+            MazeAlgorithm::Dfs => write!(f, "dfs"),
+            MazeAlgorithm::Kruskal => write!(f, "kruskal"),
+            MazeAlgorithm::Wilson => write!(f, "wilson"),
+        }
+    }
+}
+
+/// Carve a maze out of the given graph
+///
+/// The maze is a random spanning tree of the graph (or a spanning forest, if the graph is
+/// disconnected). The edges in the spanning tree are the passages of the maze.
+///
+/// Examples:
+///     grid --output-format dual --width 20 --height 20 | maze | wkt2svg
+///     grid --output-format graph --grid-type hexagon | maze --walls | wkt2svg
+#[derive(Debug, Parser)]
+#[clap(name = "maze", verbatim_doc_comment)]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// Input file to read input from. Defaults to stdin.
+    ///
+    /// Input format is expected to be Trivial Graph Format.
+    #[clap(short, long)]
+    input: Option<PathBuf>,
+
+    /// Output file to write result to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// The output geometry format
+    #[clap(short = 'O', long, default_value_t = GeometryFormat::Wkt)]
+    output_format: GeometryFormat,
+
+    /// The random seed to use. Use zero to let the tool pick its own random seed.
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+
+    /// The algorithm to use to carve the maze
+    #[clap(short, long, default_value_t = MazeAlgorithm::Dfs)]
+    algorithm: MazeAlgorithm,
+
+    /// Output the walls (the edges not in the maze) instead of the passages
+    #[clap(short, long, default_value_t = false)]
+    walls: bool,
+
+    /// Only keep the largest connected component of the graph, discarding any stray fragments
+    #[clap(long)]
+    largest_component: bool,
+}
+
+fn generate_random_seed_if_not_specified(seed: u64) -> u64 {
+    if seed == 0 {
+        let mut rng = rand::thread_rng();
+        rng.gen()
+    } else {
+        seed
+    }
+}
+
+fn other_end(
+    edge: &impl EdgeRef<NodeId = NodeIndex<usize>>,
+    node: NodeIndex<usize>,
+) -> NodeIndex<usize> {
+    if edge.source() == node {
+        edge.target()
+    } else {
+        edge.source()
+    }
+}
+
+/// Randomized depth-first search; returns whether each edge is a passage
+fn dfs(graph: &GeometryGraph<Undirected>, rng: &mut StdRng) -> Vec<bool> {
+    let mut passages = vec![false; graph.edge_count()];
+    let mut visited = vec![false; graph.node_count()];
+
+    let mut starts: Vec<_> = graph.node_indices().collect();
+    starts.shuffle(rng);
+
+    let mut buffer = Vec::new();
+    for start in starts {
+        if visited[start.index()] {
+            continue;
+        }
+        visited[start.index()] = true;
+        let mut stack = vec![start];
+
+        while let Some(&current) = stack.last() {
+            buffer.clear();
+            buffer.extend(graph.edges(current).filter_map(|e| {
+                let next = other_end(&e, current);
+                (!visited[next.index()]).then_some((e.id(), next))
+            }));
+
+            if let Some(&(edge, next)) = buffer.choose(rng) {
+                passages[edge.index()] = true;
+                visited[next.index()] = true;
+                stack.push(next);
+            } else {
+                stack.pop();
+            }
+        }
+    }
+
+    passages
+}
+
+/// Randomized Kruskal's algorithm; returns whether each edge is a passage
+fn kruskal(graph: &GeometryGraph<Undirected>, rng: &mut StdRng) -> Vec<bool> {
+    let mut passages = vec![false; graph.edge_count()];
+    let mut sets = UnionFind::<usize>::new(graph.node_count());
+
+    let mut edges: Vec<_> = graph.edge_indices().collect();
+    edges.shuffle(rng);
+
+    for edge in edges {
+        let (a, b) = graph.edge_endpoints(edge).unwrap();
+        if sets.union(a.index(), b.index()) {
+            passages[edge.index()] = true;
+        }
+    }
+
+    passages
+}
+
+/// Wilson's algorithm; returns whether each edge is a passage
+///
+/// Builds the tree out of loop-erased random walks, which picks uniformly from all possible
+/// spanning trees.
+fn wilson(graph: &GeometryGraph<Undirected>, rng: &mut StdRng) -> Vec<bool> {
+    let mut passages = vec![false; graph.edge_count()];
+    let mut in_tree = vec![false; graph.node_count()];
+
+    let mut nodes: Vec<_> = graph.node_indices().collect();
+    nodes.shuffle(rng);
+
+    // A random walk will never reach a tree in a different connected component, so each
+    // component needs its own root.
+    let mut components = UnionFind::<usize>::new(graph.node_count());
+    for edge in graph.raw_edges() {
+        components.union(edge.source().index(), edge.target().index());
+    }
+    let mut has_root = vec![false; graph.node_count()];
+    for node in &nodes {
+        let component = components.find(node.index());
+        if !has_root[component] {
+            has_root[component] = true;
+            in_tree[node.index()] = true;
+        }
+    }
+
+    // The most recent step taken out of each node. Overwriting a step when the walk revisits a
+    // node erases the loop.
+    let mut steps: Vec<Option<(EdgeIndex<usize>, NodeIndex<usize>)>> =
+        vec![None; graph.node_count()];
+    let mut buffer = Vec::new();
+    for start in nodes {
+        let mut current = start;
+        while !in_tree[current.index()] {
+            buffer.clear();
+            buffer.extend(
+                graph
+                    .edges(current)
+                    .map(|e| (e.id(), other_end(&e, current)))
+                    .filter(|(_, next)| *next != current),
+            );
+            let step = *buffer.choose(rng).unwrap();
+            steps[current.index()] = Some(step);
+            current = step.1;
+        }
+
+        let mut current = start;
+        while !in_tree[current.index()] {
+            in_tree[current.index()] = true;
+            let (edge, next) = steps[current.index()].unwrap();
+            passages[edge.index()] = true;
+            current = next;
+        }
+    }
+
+    passages
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    let seed = generate_random_seed_if_not_specified(args.seed);
+    log::info!("Seeding RNG with: {}", seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let reader = get_input_reader(&args.input).unwrap();
+    let mut graph: GeometryGraph<Undirected> = read_graph(reader);
+    if args.largest_component {
+        graph = largest_component(&graph);
+    }
+
+    let passages = match args.algorithm {
+        MazeAlgorithm::Dfs => dfs(&graph, &mut rng),
+        MazeAlgorithm::Kruskal => kruskal(&graph, &mut rng),
+        MazeAlgorithm::Wilson => wilson(&graph, &mut rng),
+    };
+    log::debug!(
+        "Carved {} passages out of {} edges",
+        passages.iter().filter(|p| **p).count(),
+        graph.edge_count()
+    );
+
+    let edges = graph
+        .raw_edges()
+        .iter()
+        .zip(passages)
+        .filter(|(_, passage)| *passage != args.walls)
+        .map(|(edge, _)| {
+            let src = graph[edge.source()];
+            let dst = graph[edge.target()];
+            Geometry::LineString(LineString::from(vec![src.0, dst.0]))
+        });
+
+    let writer = get_output_writer(&args.output).unwrap();
+    write_geometries(writer, edges, args.output_format);
+}
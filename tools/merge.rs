@@ -0,0 +1,97 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::Parser;
+use generative::io::{
+    get_input_reader, get_output_writer, read_wkt_geometries_and_styles, write_wkt_geometries,
+    GeometryAndStyle, SvgStyle,
+};
+use stderrlog::ColorChoice;
+
+/// An input file, and the stroke color to draw its geometries with
+#[derive(Debug, Clone)]
+struct Source {
+    path: PathBuf,
+    color: Option<String>,
+}
+
+impl FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Split on the last ':' so that the path may contain colons, but the color can't
+        match s.rsplit_once(':') {
+            Some((path, color)) if !path.is_empty() && !color.is_empty() => Ok(Source {
+                path: PathBuf::from(path),
+                color: Some(color.to_string()),
+            }),
+            Some(_) => Err(format!("Expected 'file:color', got '{s}'")),
+            None => Ok(Source {
+                path: PathBuf::from(s),
+                color: None,
+            }),
+        }
+    }
+}
+
+/// Concatenate WKT files into one stream, coloring each file's geometries differently
+///
+/// A STROKE(color) style is written before each file's geometries, so that wkt2svg draws them in
+/// that color. Any styles in the input files are passed through, and since styles apply to every
+/// geometry after them, they carry over into the later files unless overridden.
+///
+/// Examples:
+///     merge --input points.wkt:red --input lines.wkt:blue | wkt2svg
+#[derive(Debug, Parser)]
+#[clap(name = "merge", verbatim_doc_comment)]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// An input file, and the color to draw its geometries with, given as 'file:color'.
+    ///
+    /// May be given multiple times. The files are concatenated in the order they're given. The
+    /// color may be left off to leave the file's stroke color unchanged. Use '-' for stdin.
+    #[clap(short, long, required = true, allow_hyphen_values = true)]
+    input: Vec<Source>,
+
+    /// Output file to write result to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    let mut writer = get_output_writer(&args.output).unwrap();
+    for source in args.input {
+        log::debug!("Merging {source:?}");
+        let reader = match get_input_reader(&Some(source.path.clone())) {
+            Ok(reader) => reader,
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        };
+
+        if let Some(color) = source.color {
+            writeln!(writer, "{}", SvgStyle::Stroke(color)).expect("Failed to write style");
+        }
+        for geometry in read_wkt_geometries_and_styles(reader) {
+            match geometry {
+                GeometryAndStyle::Style(style) => {
+                    writeln!(writer, "{style}").expect("Failed to write style")
+                }
+                geometry => write_wkt_geometries(&mut writer, std::iter::once(geometry.into())),
+            }
+        }
+    }
+}
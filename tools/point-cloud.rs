@@ -2,7 +2,8 @@ use std::io::Write;
 use std::path::PathBuf;
 
 use clap::{Parser, ValueEnum};
-use generative::io::get_output_writer;
+use generative::io::{get_output_writer, write_geometries, GeometryFormat};
+use geo::{Geometry, MultiPoint, Point};
 use rand::distributions::{Distribution, Uniform};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
@@ -10,12 +11,49 @@ use rand_distr::Binomial;
 use stderrlog::ColorChoice;
 
 #[derive(Debug, Clone, ValueEnum)]
+#[allow(clippy::enum_variant_names)]
 enum RandomDomain {
     UnitSquare,
     UnitCircle,
+    UnitCube,
+    /// The surface of the unit sphere
+    UnitSphere,
+    /// The interior of the unit sphere
+    UnitBall,
 }
 
-/// Generate random point clouds in a unit square or circle
+impl RandomDomain {
+    fn dimensions(&self) -> u8 {
+        match self {
+            RandomDomain::UnitSquare | RandomDomain::UnitCircle => 2,
+            RandomDomain::UnitCube | RandomDomain::UnitSphere | RandomDomain::UnitBall => 3,
+        }
+    }
+
+    /// Get the 3D analog of a 2D domain
+    fn to_3d(&self) -> RandomDomain {
+        match self {
+            RandomDomain::UnitSquare => RandomDomain::UnitCube,
+            RandomDomain::UnitCircle => RandomDomain::UnitBall,
+            other => other.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Sequence {
+    /// Uniformly random points, which tend to clump together
+    Random,
+    /// The deterministic Halton low-discrepancy sequence, using bases 2, 3, and 5
+    Halton,
+    /// The deterministic Sobol low-discrepancy sequence
+    Sobol,
+}
+
+/// Generate random point clouds in a unit square, circle, cube, sphere, or ball
+///
+/// The 3D domains generate POINT Z geometries. Passing '--dimensions 3' with a 2D domain uses its
+/// 3D analog instead; unit-square becomes unit-cube, and unit-circle becomes unit-ball.
 #[derive(Debug, Parser)]
 #[clap(name = "point-cloud")]
 struct CmdlineOptions {
@@ -27,6 +65,14 @@ struct CmdlineOptions {
     #[clap(short, long)]
     output: Option<PathBuf>,
 
+    /// Output geometry format. The 3D domains only support WKT.
+    #[clap(short = 'O', long, default_value_t = GeometryFormat::Wkt)]
+    output_format: GeometryFormat,
+
+    /// Write all of the points as a single MULTIPOINT, instead of one POINT per line
+    #[clap(long)]
+    multipoint: bool,
+
     /// The random seed to use. Use zero to let the tool pick its own random seed.
     #[clap(long, default_value = "0")]
     seed: u64,
@@ -39,35 +85,51 @@ struct CmdlineOptions {
     #[clap(short, long)]
     random_number: bool,
 
+    /// The sequence to generate points from
+    ///
+    /// The low-discrepancy sequences cover the domain more evenly than random points, and don't
+    /// use the random seed.
+    #[clap(long, default_value = "random", value_enum)]
+    sequence: Sequence,
+
     /// The random domain to generate points inside.
     #[clap(short, long, default_value = "unit-circle", value_enum)]
     domain: RandomDomain,
 
+    /// The number of dimensions to generate points in.
+    #[clap(short = 'D', long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(2..=3))]
+    dimensions: u8,
+
     /// Scale the generated points.
     #[clap(short, long, default_value = "1.0")]
     scale: f64,
 }
 
-struct Double2 {
+struct Double3 {
     x: f64,
     y: f64,
+    z: f64,
 }
 
-fn generate(points: usize, domain: RandomDomain, rng: &mut StdRng) -> Vec<Double2> {
+fn generate(points: usize, domain: RandomDomain, rng: &mut StdRng) -> Vec<Double3> {
     match domain {
         RandomDomain::UnitSquare => generate_square(points, rng),
         RandomDomain::UnitCircle => generate_circle(points, rng),
+        RandomDomain::UnitCube => generate_cube(points, rng),
+        RandomDomain::UnitSphere => generate_sphere(points, rng),
+        RandomDomain::UnitBall => generate_ball(points, rng),
     }
 }
 
-fn generate_square(points: usize, rng: &mut StdRng) -> Vec<Double2> {
+fn generate_square(points: usize, rng: &mut StdRng) -> Vec<Double3> {
     let mut v = Vec::with_capacity(points);
     let dist = Uniform::from(0.0..1.0);
 
     for _ in 0..points {
-        let point = Double2 {
+        let point = Double3 {
             x: dist.sample(rng),
             y: dist.sample(rng),
+            z: 0.0,
         };
         v.push(point);
     }
@@ -75,7 +137,7 @@ fn generate_square(points: usize, rng: &mut StdRng) -> Vec<Double2> {
     v
 }
 
-fn generate_circle(points: usize, rng: &mut StdRng) -> Vec<Double2> {
+fn generate_circle(points: usize, rng: &mut StdRng) -> Vec<Double3> {
     let mut v = Vec::with_capacity(points);
 
     let r_dist = Uniform::from(0.0..1.0);
@@ -85,9 +147,26 @@ fn generate_circle(points: usize, rng: &mut StdRng) -> Vec<Double2> {
         let r = r_dist.sample(rng);
         let theta = theta_dist.sample(rng);
 
-        let point = Double2 {
+        let point = Double3 {
             x: r * theta.cos(),
             y: r * theta.sin(),
+            z: 0.0,
+        };
+        v.push(point);
+    }
+
+    v
+}
+
+fn generate_cube(points: usize, rng: &mut StdRng) -> Vec<Double3> {
+    let mut v = Vec::with_capacity(points);
+    let dist = Uniform::from(0.0..1.0);
+
+    for _ in 0..points {
+        let point = Double3 {
+            x: dist.sample(rng),
+            y: dist.sample(rng),
+            z: dist.sample(rng),
         };
         v.push(point);
     }
@@ -95,6 +174,160 @@ fn generate_circle(points: usize, rng: &mut StdRng) -> Vec<Double2> {
     v
 }
 
+/// Sample a uniformly distributed direction on the surface of the unit sphere
+///
+/// By Archimedes' hat-box theorem, the area of a spherical slice depends only on its height, so a
+/// uniform height and a uniform angle around the z axis give a uniform point on the sphere.
+fn sample_sphere(rng: &mut StdRng) -> Double3 {
+    let z_dist = Uniform::from(-1.0..1.0);
+    let theta_dist = Uniform::from(0.0..2.0 * std::f64::consts::PI);
+
+    let z: f64 = z_dist.sample(rng);
+    let theta = theta_dist.sample(rng);
+    let r = (1.0 - z * z).sqrt();
+
+    Double3 {
+        x: r * theta.cos(),
+        y: r * theta.sin(),
+        z,
+    }
+}
+
+fn generate_sphere(points: usize, rng: &mut StdRng) -> Vec<Double3> {
+    (0..points).map(|_| sample_sphere(rng)).collect()
+}
+
+fn generate_ball(points: usize, rng: &mut StdRng) -> Vec<Double3> {
+    let mut v = Vec::with_capacity(points);
+    let r_dist = Uniform::from(0.0..1.0);
+
+    for _ in 0..points {
+        let direction = sample_sphere(rng);
+        // The volume of a ball grows with r^3, so take the cube root for a uniform density
+        let r = f64::cbrt(r_dist.sample(rng));
+
+        let point = Double3 {
+            x: r * direction.x,
+            y: r * direction.y,
+            z: r * direction.z,
+        };
+        v.push(point);
+    }
+
+    v
+}
+
+/// The radical inverse of i in the given base; its digits mirrored about the decimal point
+fn radical_inverse(mut i: u64, base: u64) -> f64 {
+    let mut digit_scale = 1.0;
+    let mut inverse = 0.0;
+    while i > 0 {
+        digit_scale /= base as f64;
+        inverse += digit_scale * (i % base) as f64;
+        i /= base;
+    }
+    inverse
+}
+
+fn halton(points: usize) -> Vec<[f64; 3]> {
+    // Skip the first point, which is always the origin
+    (1..=points as u64)
+        .map(|i| {
+            [
+                radical_inverse(i, 2),
+                radical_inverse(i, 3),
+                radical_inverse(i, 5),
+            ]
+        })
+        .collect()
+}
+
+/// The Sobol direction numbers for the dimension with the given primitive polynomial degree s,
+/// coefficients a, and initial direction numbers m
+fn sobol_directions(s: usize, a: u32, m: &[u32]) -> [u32; 32] {
+    let mut v = [0; 32];
+    for k in 0..32 {
+        v[k] = if k < s {
+            m[k] << (31 - k)
+        } else {
+            let mut v_k = v[k - s] ^ (v[k - s] >> s);
+            for j in 1..s {
+                if (a >> (s - 1 - j)) & 1 == 1 {
+                    v_k ^= v[k - j];
+                }
+            }
+            v_k
+        };
+    }
+    v
+}
+
+fn sobol(points: usize) -> Vec<[f64; 3]> {
+    // The first three dimensions from Joe and Kuo's direction numbers
+    let mut directions = [[0; 32]; 3];
+    for (k, v) in directions[0].iter_mut().enumerate() {
+        *v = 1 << (31 - k);
+    }
+    directions[1] = sobol_directions(1, 0, &[1]);
+    directions[2] = sobol_directions(2, 1, &[1, 3]);
+
+    // Use the Gray code ordering, so that each point differs from the last by a single direction
+    // number. Skip the first point, which is always the origin.
+    let mut x = [0u32; 3];
+    (1..=points as u32)
+        .map(|i| {
+            let c = i.trailing_zeros() as usize;
+            for (x, v) in x.iter_mut().zip(&directions) {
+                *x ^= v[c];
+            }
+            x.map(|x| x as f64 / (1u64 << 32) as f64)
+        })
+        .collect()
+}
+
+/// Map samples from the unit cube onto the given domain, preserving their even spacing
+fn map_to_domain(samples: Vec<[f64; 3]>, domain: RandomDomain) -> Vec<Double3> {
+    let tau = 2.0 * std::f64::consts::PI;
+    let to_sphere = |u: f64, v: f64| {
+        let z = 2.0 * u - 1.0;
+        let theta = tau * v;
+        let r = (1.0 - z * z).sqrt();
+        Double3 {
+            x: r * theta.cos(),
+            y: r * theta.sin(),
+            z,
+        }
+    };
+
+    samples
+        .into_iter()
+        .map(|[u, v, w]| match domain {
+            RandomDomain::UnitSquare => Double3 { x: u, y: v, z: 0.0 },
+            RandomDomain::UnitCircle => {
+                // The area of a disk grows with r^2, so take the square root for a uniform density
+                let r = u.sqrt();
+                let theta = tau * v;
+                Double3 {
+                    x: r * theta.cos(),
+                    y: r * theta.sin(),
+                    z: 0.0,
+                }
+            }
+            RandomDomain::UnitCube => Double3 { x: u, y: v, z: w },
+            RandomDomain::UnitSphere => to_sphere(u, v),
+            RandomDomain::UnitBall => {
+                let direction = to_sphere(u, v);
+                let r = f64::cbrt(w);
+                Double3 {
+                    x: r * direction.x,
+                    y: r * direction.y,
+                    z: r * direction.z,
+                }
+            }
+        })
+        .collect()
+}
+
 fn generate_random_seed_if_not_specified(seed: u64) -> u64 {
     if seed == 0 {
         let mut rng = rand::thread_rng();
@@ -126,17 +359,68 @@ fn main() {
         args.points
     };
 
-    log::info!("Generating {} points with seed {}", num_points, seed);
+    let domain = if args.dimensions == 3 {
+        args.domain.to_3d()
+    } else {
+        args.domain
+    };
+    let dimensions = domain.dimensions();
+    if dimensions == 3 && !matches!(args.output_format, GeometryFormat::Wkt) {
+        eprintln!(
+            "3D points can only be written as WKT, not {}",
+            args.output_format
+        );
+        std::process::exit(1);
+    }
 
-    let points = generate(num_points as usize, args.domain, &mut rng);
+    let points = match args.sequence {
+        Sequence::Random => {
+            log::info!("Generating {} points with seed {}", num_points, seed);
+            generate(num_points as usize, domain, &mut rng)
+        }
+        Sequence::Halton => {
+            log::info!("Generating {} Halton points", num_points);
+            map_to_domain(halton(num_points as usize), domain)
+        }
+        Sequence::Sobol => {
+            log::info!("Generating {} Sobol points", num_points);
+            map_to_domain(sobol(num_points as usize), domain)
+        }
+    };
     let mut writer = get_output_writer(&args.output).unwrap();
-    for point in points {
-        writeln!(
-            writer,
-            "POINT ({} {})",
-            point.x * args.scale,
-            point.y * args.scale
-        )
-        .expect("Failed to write random point");
+    if dimensions == 3 {
+        // The geo geometries are 2D, so the 3D points have to be formatted by hand
+        let coords: Vec<_> = points
+            .iter()
+            .map(|p| {
+                format!(
+                    "{} {} {}",
+                    p.x * args.scale,
+                    p.y * args.scale,
+                    p.z * args.scale
+                )
+            })
+            .collect();
+        if args.multipoint && coords.is_empty() {
+            writeln!(writer, "MULTIPOINT Z EMPTY").expect("Failed to write random points");
+        } else if args.multipoint {
+            writeln!(writer, "MULTIPOINT Z(({}))", coords.join("),("))
+                .expect("Failed to write random points");
+        } else {
+            for coord in coords {
+                writeln!(writer, "POINT Z({coord})").expect("Failed to write random point");
+            }
+        }
+        return;
+    }
+
+    let points = points
+        .into_iter()
+        .map(|p| Point::new(p.x * args.scale, p.y * args.scale));
+    if args.multipoint {
+        let multipoint = Geometry::MultiPoint(MultiPoint::from_iter(points));
+        write_geometries(writer, std::iter::once(multipoint), args.output_format);
+    } else {
+        write_geometries(writer, points.map(Geometry::Point), args.output_format);
     }
 }
@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use generative::io::{
+    get_input_reader, get_output_writer, read_geometries, write_crs_header, write_geometries,
+    GeometryFormat, GeometryInputFormat,
+};
+use generative::MapCoordsInPlaceMut;
+use geo::{coord, Geometry};
+use proj::{Proj, ProjError};
+use stderrlog::ColorChoice;
+
+/// Reproject geometries from one coordinate reference system to another
+///
+/// Each coordinate is transformed with PROJ. The CRSs can be given in any form PROJ understands,
+/// like 'EPSG:4326', a PROJ string, or WKT.
+///
+/// Coordinates are always in (x, y) order, even for CRSs whose official axis order is (latitude,
+/// longitude) like EPSG:4326. That is, geographic coordinates are given as (longitude, latitude).
+#[derive(Debug, Parser)]
+#[clap(name = "reproject", verbatim_doc_comment)]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// Input file to read input from. Defaults to stdin.
+    #[clap(short, long)]
+    input: Option<PathBuf>,
+
+    /// Input geometry format.
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// Output file to write result to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output geometry format.
+    ///
+    /// WKT output is prefixed with a '# CRS: <CRS>' header of the --to CRS.
+    #[clap(short = 'O', long, default_value_t = GeometryFormat::Wkt)]
+    output_format: GeometryFormat,
+
+    /// The CRS of the input geometries, like EPSG:4326
+    #[clap(short, long)]
+    from: String,
+
+    /// The CRS to reproject the geometries to, like EPSG:3857
+    #[clap(short, long)]
+    to: String,
+}
+
+/// Reproject each coordinate of the geometry, failing if any coordinate fails
+fn reproject(mut geometry: Geometry, proj: &Proj) -> Result<Geometry, ProjError> {
+    let mut result = Ok(());
+    geometry.map_coords_in_place_mut(|c| match proj.convert((c.x, c.y)) {
+        Ok((x, y)) => coord! {x: x, y: y},
+        Err(e) => {
+            result = Err(e);
+            c
+        }
+    });
+    result.map(|_| geometry)
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    let proj = match Proj::new_known_crs(&args.from, &args.to, None) {
+        Ok(proj) => proj,
+        Err(e) => {
+            eprintln!(
+                "Failed to create projection from '{}' to '{}': {e}",
+                args.from, args.to
+            );
+            std::process::exit(1);
+        }
+    };
+
+    let reader = get_input_reader(&args.input).unwrap();
+    let geometries =
+        read_geometries(reader, &args.input_format).take(args.limit.unwrap_or(usize::MAX));
+    let geometries = geometries.filter_map(|g| match reproject(g, &proj) {
+        Ok(g) => Some(g),
+        Err(e) => {
+            log::warn!("Failed to reproject geometry, skipping: {e}");
+            None
+        }
+    });
+
+    let mut writer = get_output_writer(&args.output).unwrap();
+    if let GeometryFormat::Wkt = args.output_format {
+        write_crs_header(&mut writer, &args.to, args.output_format);
+    }
+    write_geometries(writer, geometries, args.output_format);
+}
@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+
+use clap::{ArgGroup, Parser};
+use generative::flatten::flatten_nested_geometries;
+use generative::io::{
+    get_input_reader, get_output_writer, read_geometries, write_crs_header, write_geometries,
+    GeometryFormat, GeometryInputFormat,
+};
+use generative::sample::{sample_linestring, sample_linestring_count};
+use geo::{Geometry, LineString, Polygon};
+use stderrlog::ColorChoice;
+
+/// Sample POINTs at intervals along each line, like beads on a string
+///
+/// Each LINESTRING and POLYGON ring is replaced by the POINTs sampled along it. POINTs are passed
+/// through unchanged.
+#[derive(Debug, Parser)]
+#[clap(name = "sample-along", verbatim_doc_comment)]
+#[clap(group(ArgGroup::new("sampling").required(true)))]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// Input file to read input from. Defaults to stdin.
+    #[clap(short, long)]
+    input: Option<PathBuf>,
+
+    /// Input geometry format.
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// Output file to write result to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output geometry format.
+    #[clap(short = 'O', long, default_value_t = GeometryFormat::Wkt)]
+    output_format: GeometryFormat,
+
+    /// Prepend a '# CRS: <CRS>' header (like EPSG:4326) to the WKT output
+    ///
+    /// The header is ignored when reading WKT.
+    #[clap(long)]
+    crs: Option<String>,
+
+    /// Sample a point every this many units along each line, starting at its first vertex
+    ///
+    /// Mutually exclusive with --count
+    #[clap(short, long, group = "sampling")]
+    spacing: Option<f64>,
+
+    /// Sample this many evenly spaced points along each line
+    ///
+    /// Open lines are sampled at both of their ends. Mutually exclusive with --spacing
+    #[clap(short, long, group = "sampling")]
+    count: Option<usize>,
+}
+
+fn sample(linestring: &LineString, args: &CmdlineOptions) -> Vec<Geometry> {
+    let points = match (args.spacing, args.count) {
+        (Some(spacing), _) => sample_linestring(linestring, spacing),
+        (_, Some(count)) => sample_linestring_count(linestring, count),
+        _ => unreachable!("--spacing or --count is required, as enforced by clap"),
+    };
+    points.into_iter().map(Geometry::Point).collect()
+}
+
+fn sample_polygon(polygon: &Polygon, args: &CmdlineOptions) -> Vec<Geometry> {
+    std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .flat_map(|ring| sample(ring, args))
+        .collect()
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    if let Some(spacing) = args.spacing {
+        if spacing <= 0.0 {
+            eprintln!("--spacing must be positive, got {spacing}");
+            std::process::exit(1);
+        }
+    }
+
+    let reader = get_input_reader(&args.input).unwrap();
+    let geometries =
+        read_geometries(reader, &args.input_format).take(args.limit.unwrap_or(usize::MAX));
+    let geometries = flatten_nested_geometries(geometries);
+    let geometries = geometries.flat_map(|g| match g {
+        Geometry::Line(l) => sample(&l.into(), &args),
+        Geometry::LineString(l) => sample(&l, &args),
+        Geometry::Polygon(p) => sample_polygon(&p, &args),
+        Geometry::Rect(r) => sample_polygon(&r.to_polygon(), &args),
+        Geometry::Triangle(t) => sample_polygon(&t.to_polygon(), &args),
+        _ => vec![g],
+    });
+
+    let mut writer = get_output_writer(&args.output).unwrap();
+    if let Some(crs) = &args.crs {
+        write_crs_header(&mut writer, crs, args.output_format);
+    }
+    write_geometries(writer, geometries, args.output_format);
+}
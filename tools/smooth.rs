@@ -3,7 +3,8 @@ use std::path::PathBuf;
 use clap::Parser;
 use generative::flatten::flatten_nested_geometries;
 use generative::io::{
-    get_input_reader, get_output_writer, read_geometries, write_geometries, GeometryFormat,
+    get_input_reader, get_output_writer, read_geometries, write_crs_header, write_geometries,
+    GeometryFormat, GeometryInputFormat,
 };
 use geo::{ChaikinSmoothing, Geometry};
 use stderrlog::ColorChoice;
@@ -21,8 +22,12 @@ struct CmdlineOptions {
     input: Option<PathBuf>,
 
     /// Input geometry format.
-    #[clap(short = 'I', long, default_value_t = GeometryFormat::Wkt)]
-    input_format: GeometryFormat,
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
 
     /// Output file to write result to. Defaults to stdout.
     #[clap(short, long)]
@@ -32,6 +37,12 @@ struct CmdlineOptions {
     #[clap(short = 'O', long, default_value_t = GeometryFormat::Wkt)]
     output_format: GeometryFormat,
 
+    /// Prepend a '# CRS: <CRS>' header (like EPSG:4326) to the WKT output
+    ///
+    /// The header is ignored when reading WKT.
+    #[clap(long)]
+    crs: Option<String>,
+
     /// Number of iterations to run Chaikins smoothing algorithm
     #[clap(short = 'n', long, default_value_t = 10)]
     iterations: usize,
@@ -47,7 +58,8 @@ fn main() {
         .expect("Failed to initialize stderrlog");
 
     let reader = get_input_reader(&args.input).unwrap();
-    let geometries = read_geometries(reader, &args.input_format);
+    let geometries =
+        read_geometries(reader, &args.input_format).take(args.limit.unwrap_or(usize::MAX));
     let geometries = flatten_nested_geometries(geometries);
     let geometries = geometries.map(|g| match g {
         Geometry::Point(_)
@@ -64,6 +76,9 @@ fn main() {
         Geometry::MultiPolygon(g) => Geometry::MultiPolygon(g.chaikin_smoothing(args.iterations)),
     });
 
-    let writer = get_output_writer(&args.output).unwrap();
+    let mut writer = get_output_writer(&args.output).unwrap();
+    if let Some(crs) = &args.crs {
+        write_crs_header(&mut writer, crs, args.output_format);
+    }
     write_geometries(writer, geometries, args.output_format);
 }
@@ -1,12 +1,15 @@
 use std::path::PathBuf;
 
 use clap::{Parser, ValueEnum};
-use generative::graph::GeometryGraph;
+use generative::graph::{largest_component, simplify_multigraph, GeometryGraph};
 use generative::io::{
-    get_input_reader, get_output_writer, read_geometries, read_tgf_graph, write_geometries,
-    write_tgf_graph, GeometryFormat,
+    get_input_reader, get_output_writer, read_geometries, read_graph, write_crs_header,
+    write_geometries, write_tgf_graph_with_options, GeometryFormat, Progress, TgfOptions,
+};
+use generative::snap::{
+    estimate_graph_tolerance, estimate_tolerance, remove_collinear_vertices, snap_geoms,
+    snap_graph, CollectionSnappingStrategy, Snapping, SnappingStrategy, Tolerance,
 };
-use generative::snap::{snap_geoms, snap_graph, SnappingStrategy};
 use petgraph::Undirected;
 use stderrlog::ColorChoice;
 
@@ -49,6 +52,8 @@ impl From<InputFormat> for GeometryFormat {
 enum CliSnappingStrategy {
     ClosestPoint,
     RegularGrid,
+    VertexToEdge,
+    EndpointsOnly,
 }
 
 impl std::fmt::Display for CliSnappingStrategy {
@@ -57,6 +62,8 @@ impl std::fmt::Display for CliSnappingStrategy {
             // important: Should match clap::ValueEnum format
             CliSnappingStrategy::ClosestPoint => write!(f, "closest-point"),
             CliSnappingStrategy::RegularGrid => write!(f, "regular-grid"),
+            CliSnappingStrategy::VertexToEdge => write!(f, "vertex-to-edge"),
+            CliSnappingStrategy::EndpointsOnly => write!(f, "endpoints-only"),
         }
     }
 }
@@ -77,17 +84,62 @@ struct CmdlineOptions {
     #[clap(short = 'I', long, default_value_t = InputFormat::Wkt)]
     input_format: InputFormat,
 
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// Periodically log how many geometries have been processed to stderr
+    #[clap(long)]
+    progress: bool,
+
     /// Output file to write result to. Defaults to stdout.
     #[clap(short, long)]
     output: Option<PathBuf>,
 
+    /// Prepend a '# CRS: <CRS>' header (like EPSG:4326) to the WKT output
+    ///
+    /// The header is ignored when reading WKT.
+    #[clap(long)]
+    crs: Option<String>,
+
     /// The strategy to use for snapping
     #[clap(short, long, default_value_t = CliSnappingStrategy::ClosestPoint)]
     strategy: CliSnappingStrategy,
 
-    /// The tolerance to use when snapping
-    #[clap(short, long, default_value_t = 0.001)]
-    tolerance: f64,
+    /// The tolerance to use when snapping, or 'auto' to estimate it from the input
+    ///
+    /// The tolerance is a distance, in the same units as the coordinates, for every strategy. The
+    /// estimate is a tenth of the median distance between neighboring vertices.
+    #[clap(short, long, default_value_t = Tolerance::Fixed(0.001))]
+    tolerance: Tolerance,
+
+    /// Leave geometries unsnapped if snapping would collapse their dimension
+    ///
+    /// E.g., a POLYGON that would collapse into a LINESTRING, or a LINESTRING into a POINT. Only
+    /// applies to geometries, not graphs.
+    #[clap(short, long)]
+    preserve_dimension: bool,
+
+    /// After snapping, remove vertices whose angle is within this many degrees of 180
+    ///
+    /// Only applies to geometries, not graphs.
+    #[clap(long)]
+    collinear_tolerance: Option<f64>,
+
+    /// After snapping, remove any self-loops and parallel edges from the graph
+    ///
+    /// Only applies to graphs, not geometries.
+    #[clap(long)]
+    clean: bool,
+
+    /// After snapping, only keep the largest connected component of the graph
+    ///
+    /// Only applies to graphs, not geometries.
+    #[clap(long)]
+    largest_component: bool,
+
+    #[clap(flatten)]
+    tgf: TgfOptions,
 }
 
 fn main() {
@@ -101,21 +153,69 @@ fn main() {
 
     let reader = get_input_reader(&args.input).unwrap();
     let mut writer = get_output_writer(&args.output).unwrap();
-    let strategy = match args.strategy {
-        CliSnappingStrategy::ClosestPoint => SnappingStrategy::ClosestPoint(args.tolerance),
-        CliSnappingStrategy::RegularGrid => SnappingStrategy::RegularGrid(args.tolerance),
+    let strategy = |tolerance| match args.strategy {
+        CliSnappingStrategy::ClosestPoint => {
+            Snapping::Vertex(SnappingStrategy::ClosestPoint(tolerance))
+        }
+        CliSnappingStrategy::RegularGrid => {
+            Snapping::Vertex(SnappingStrategy::RegularGrid(tolerance))
+        }
+        CliSnappingStrategy::VertexToEdge => {
+            Snapping::Collection(CollectionSnappingStrategy::VertexToEdge(tolerance))
+        }
+        CliSnappingStrategy::EndpointsOnly => {
+            Snapping::Collection(CollectionSnappingStrategy::EndpointsOnly(tolerance))
+        }
+    };
+    let estimated = |tolerance| {
+        log::info!("Estimated snapping tolerance {tolerance}");
+        tolerance
     };
 
     match args.input_format {
         InputFormat::Wkt | InputFormat::WkbHex | InputFormat::WkbRaw => {
-            let geometries = read_geometries(reader, &args.input_format.clone().into());
-            let geometries = snap_geoms(geometries, strategy);
+            let geometries = read_geometries(
+                reader,
+                &GeometryFormat::from(args.input_format.clone()).into(),
+            )
+            .take(args.limit.unwrap_or(usize::MAX));
+            let geometries = Progress::new(geometries, args.progress);
+            let geometries = match args.tolerance {
+                Tolerance::Fixed(tolerance) => {
+                    snap_geoms(geometries, strategy(tolerance), args.preserve_dimension)
+                }
+                Tolerance::Auto => {
+                    // Estimating the tolerance has to load all of the geometries into RAM, but
+                    // snap_geoms() does anyways
+                    let geometries: Vec<_> = geometries.collect();
+                    let tolerance = estimated(estimate_tolerance(&geometries));
+                    let strategy = strategy(tolerance);
+                    snap_geoms(geometries.into_iter(), strategy, args.preserve_dimension)
+                }
+            };
+            let geometries = geometries.map(|g| match args.collinear_tolerance {
+                Some(tolerance) => remove_collinear_vertices(g, tolerance),
+                None => g,
+            });
+            if let Some(crs) = &args.crs {
+                write_crs_header(&mut writer, crs, args.input_format.clone().into());
+            }
             write_geometries(writer, geometries, args.input_format.into());
         }
         InputFormat::Tgf => {
-            let graph: GeometryGraph<Undirected> = read_tgf_graph(reader);
-            let graph = snap_graph(graph, strategy);
-            write_tgf_graph(&mut writer, &graph);
+            let graph: GeometryGraph<Undirected> = read_graph(reader);
+            let tolerance = match args.tolerance {
+                Tolerance::Fixed(tolerance) => tolerance,
+                Tolerance::Auto => estimated(estimate_graph_tolerance(&graph)),
+            };
+            let mut graph = snap_graph(graph, strategy(tolerance));
+            if args.clean {
+                simplify_multigraph(&mut graph);
+            }
+            if args.largest_component {
+                graph = largest_component(&graph);
+            }
+            write_tgf_graph_with_options(&mut writer, &graph, &args.tgf);
         }
     }
 }
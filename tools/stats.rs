@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::Parser;
+use generative::io::{get_input_reader, get_output_writer, read_geometries, GeometryInputFormat};
+use geo::{Area, Euclidean, Geometry, Length};
+use stderrlog::ColorChoice;
+
+/// Summarize the given geometries
+///
+/// Counts the geometries by type, and reports the total, mean, min, and max length of the linear
+/// geometries, and area of the polygonal geometries. GEOMETRYCOLLECTIONs are counted as
+/// collections, but their members contribute to the length and area statistics.
+#[derive(Debug, Parser)]
+#[clap(name = "stats", verbatim_doc_comment)]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// Input file to read input from. Defaults to stdin.
+    #[clap(short, long)]
+    input: Option<PathBuf>,
+
+    /// Input geometry format.
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// Output file to write result to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output the summary as JSON instead of plain text
+    #[clap(short, long)]
+    json: bool,
+}
+
+#[derive(Debug, Default)]
+struct Summary {
+    count: usize,
+    total: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Summary {
+    fn add(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+        self.total += value;
+    }
+
+    fn mean(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total / self.count as f64
+        }
+    }
+
+    fn to_text(&self, name: &str) -> String {
+        if self.count == 0 {
+            return format!("{name}: count=0");
+        }
+        format!(
+            "{name}: count={} total={} mean={} min={} max={}",
+            self.count,
+            self.total,
+            self.mean(),
+            self.min,
+            self.max
+        )
+    }
+
+    fn to_json(&self) -> String {
+        if self.count == 0 {
+            return r#"{"count": 0, "total": 0, "mean": null, "min": null, "max": null}"#.into();
+        }
+        format!(
+            r#"{{"count": {}, "total": {}, "mean": {}, "min": {}, "max": {}}}"#,
+            self.count,
+            self.total,
+            self.mean(),
+            self.min,
+            self.max
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    counts: BTreeMap<&'static str, usize>,
+    length: Summary,
+    area: Summary,
+}
+
+impl Stats {
+    fn add(&mut self, geometry: &Geometry) {
+        *self.counts.entry(type_name(geometry)).or_default() += 1;
+        self.measure(geometry);
+    }
+
+    fn measure(&mut self, geometry: &Geometry) {
+        match geometry {
+            Geometry::Point(_) | Geometry::MultiPoint(_) => {}
+            Geometry::Line(l) => self.length.add(l.length::<Euclidean>()),
+            Geometry::LineString(l) => self.length.add(l.length::<Euclidean>()),
+            Geometry::MultiLineString(l) => self.length.add(l.length::<Euclidean>()),
+            Geometry::Polygon(_)
+            | Geometry::MultiPolygon(_)
+            | Geometry::Rect(_)
+            | Geometry::Triangle(_) => self.area.add(geometry.unsigned_area()),
+            Geometry::GeometryCollection(c) => {
+                for g in c {
+                    self.measure(g);
+                }
+            }
+        }
+    }
+
+    fn write_text<W: Write>(&self, writer: &mut W) {
+        for (name, count) in &self.counts {
+            writeln!(writer, "{name}: {count}").expect("Writing failed");
+        }
+        writeln!(writer, "{}", self.length.to_text("length")).expect("Writing failed");
+        writeln!(writer, "{}", self.area.to_text("area")).expect("Writing failed");
+    }
+
+    fn write_json<W: Write>(&self, writer: &mut W) {
+        let counts: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(name, count)| format!(r#""{name}": {count}"#))
+            .collect();
+        writeln!(
+            writer,
+            r#"{{"counts": {{{}}}, "length": {}, "area": {}}}"#,
+            counts.join(", "),
+            self.length.to_json(),
+            self.area.to_json()
+        )
+        .expect("Writing failed");
+    }
+}
+
+fn type_name(geometry: &Geometry) -> &'static str {
+    match geometry {
+        Geometry::Point(_) => "POINT",
+        Geometry::Line(_) => "LINE",
+        Geometry::LineString(_) => "LINESTRING",
+        Geometry::Polygon(_) => "POLYGON",
+        Geometry::MultiPoint(_) => "MULTIPOINT",
+        Geometry::MultiLineString(_) => "MULTILINESTRING",
+        Geometry::MultiPolygon(_) => "MULTIPOLYGON",
+        Geometry::GeometryCollection(_) => "GEOMETRYCOLLECTION",
+        Geometry::Rect(_) => "RECT",
+        Geometry::Triangle(_) => "TRIANGLE",
+    }
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    let reader = get_input_reader(&args.input).unwrap();
+    let geometries =
+        read_geometries(reader, &args.input_format).take(args.limit.unwrap_or(usize::MAX));
+
+    let mut stats = Stats::default();
+    for geometry in geometries {
+        stats.add(&geometry);
+    }
+
+    let mut writer = get_output_writer(&args.output).unwrap();
+    if args.json {
+        stats.write_json(&mut writer);
+    } else {
+        stats.write_text(&mut writer);
+    }
+}
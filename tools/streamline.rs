@@ -1,9 +1,10 @@
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::{Parser, ValueEnum};
 use generative::io::{
     get_input_reader, get_output_writer, read_geometries, write_geometries, GeometryFormat,
+    GeometryInputFormat,
 };
 use generative::MapCoordsInPlaceMut;
 use geo::{AffineOps, AffineTransform, Centroid, Coord, Geometry, Line, LineString};
@@ -51,8 +52,12 @@ struct CmdlineOptions {
     input: Option<PathBuf>,
 
     /// Input geometry format.
-    #[clap(short = 'I', long, default_value_t = GeometryFormat::Wkt)]
-    input_format: GeometryFormat,
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
 
     /// Output file to write result to. Defaults to stdout.
     #[clap(short, long)]
@@ -71,10 +76,23 @@ struct CmdlineOptions {
     ///     x = -sin(x) / temp;
     ///     y = y / temp;
     ///
-    /// I.e., the f64 x and y variables are both input and output.
+    /// I.e., the f64 x and y variables are both input and output. The f64 t variable is the
+    /// current --time-range time, or zero.
     #[clap(short, long)]
     function: Option<String>,
 
+    /// Render the output at each of the given times, for animating a time-varying --function
+    ///
+    /// Given as start,end,steps, where the number of steps includes both the start and end times.
+    /// The output for each time is written to a numbered file in --time-dir. The Perlin noise field
+    /// doesn't vary with time.
+    #[clap(long, value_parser = parse_time_range, requires = "time_dir")]
+    time_range: Option<(f64, f64, usize)>,
+
+    /// The directory to write the --time-range outputs to. Will be created if it doesn't exist.
+    #[clap(long, requires = "time_range", conflicts_with_all = ["output", "frames"])]
+    time_dir: Option<PathBuf>,
+
     /// The random seed to use. Use zero to let the tool pick its own random seed.
     #[clap(long, default_value_t = 0)]
     seed: u64,
@@ -123,6 +141,13 @@ struct CmdlineOptions {
     #[clap(short = 'k', long, default_value_t = StreamlineKind::PerCentroid)]
     streamline_kind: StreamlineKind,
 
+    /// Also trace each streamline backward in time from its seed, so that it extends both ways
+    ///
+    /// The backward trace has the same number of time steps as the forward trace. The geometries
+    /// are still only moved forward.
+    #[clap(short = 'b', long)]
+    bidirectional: bool,
+
     /// Disable drawing streamlines
     #[clap(short = 'n', long)]
     no_draw_streamlines: bool,
@@ -138,6 +163,14 @@ struct CmdlineOptions {
     /// WKT-like SVG styles to apply to the geometries. May be specified multiple times.
     #[clap(short = 'G', long)]
     geometry_style: Vec<String>,
+
+    /// Write the geometries at every timestep to numbered files in the given directory, for
+    /// making animations. Will be created if it doesn't exist.
+    ///
+    /// Each frame uses the --geometry-style styles. Geometries that stop moving early (because
+    /// they left the vector field, or had fewer random timesteps) stay put in the later frames.
+    #[clap(long)]
+    frames: Option<PathBuf>,
 }
 
 fn generate_random_seed_if_not_specified(seed: u64) -> u64 {
@@ -150,7 +183,9 @@ fn generate_random_seed_if_not_specified(seed: u64) -> u64 {
 }
 
 struct VectorField {
-    function: Box<dyn Fn(f64, f64) -> [f64; 2]>,
+    function: Box<dyn Fn(f64, f64, f64) -> [f64; 2]>,
+    /// The time to evaluate the function at
+    time: f64,
 
     min_x: f64,
     max_x: f64,
@@ -166,10 +201,11 @@ impl VectorField {
         min_y: f64,
         max_y: f64,
         stride: f64,
-        function: impl Fn(f64, f64) -> [f64; 2] + 'static,
+        function: impl Fn(f64, f64, f64) -> [f64; 2] + 'static,
     ) -> Self {
         Self {
             function: Box::new(function),
+            time: 0.0,
             min_x,
             max_x,
             min_y,
@@ -178,6 +214,10 @@ impl VectorField {
         }
     }
 
+    fn at(&self, x: f64, y: f64) -> [f64; 2] {
+        (self.function)(x, y, self.time)
+    }
+
     fn i2x(&self, i: usize) -> f64 {
         (i as f64) * self.stride + self.min_x
     }
@@ -210,7 +250,7 @@ impl VectorField {
                 let x1 = self.i2x(i);
                 let y1 = self.j2y(j);
 
-                let vector = (self.function)(x1, y1);
+                let vector = self.at(x1, y1);
 
                 // Vector field visualizations don't look good if the vectors use the same scale as the
                 // uniform grid they're drawn on. So we scale by the delta-h.
@@ -229,6 +269,16 @@ impl VectorField {
     }
 }
 
+/// The result of simulating a single geometry
+struct Simulation {
+    /// The geometry after the last timestep
+    geometry: Geometry,
+    /// The streamlines traced out by the geometry, if recording streamlines
+    streamlines: Vec<LineString>,
+    /// The geometry at each timestep (including the initial state), if recording frames
+    frames: Vec<Geometry>,
+}
+
 #[allow(clippy::too_many_arguments)]
 fn simulate<'v, G>(
     geometries: G,
@@ -238,8 +288,10 @@ fn simulate<'v, G>(
     random_timesteps: bool,
     rng: &'v mut StdRng,
     streamline_kind: StreamlineKind,
+    bidirectional: bool,
     record_streamlines: bool,
-) -> impl Iterator<Item = (Geometry, Vec<LineString>)> + 'v
+    record_frames: bool,
+) -> impl Iterator<Item = Simulation> + 'v
 where
     G: IntoIterator<Item = Geometry>,
     G: 'v,
@@ -259,20 +311,29 @@ where
             timestep,
             num_timesteps,
             streamline_kind,
+            bidirectional,
             record_streamlines,
+            record_frames,
         )
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 fn simulate_geometry(
     geometry: Geometry,
     field: &VectorField,
     timestep: f64,
     num_timesteps: usize,
     streamline_kind: StreamlineKind,
+    bidirectional: bool,
     record_streamlines: bool,
-) -> (Geometry, Vec<LineString>) {
-    match streamline_kind {
+    record_frames: bool,
+) -> Simulation {
+    // The frames are reconstructed from the streamlines, so they need to be recorded either way
+    let original = record_frames.then(|| geometry.clone());
+    let record_streamlines = record_streamlines || record_frames;
+
+    let (geometry, mut streamlines) = match streamline_kind {
         StreamlineKind::PerVertex => {
             simulate_geom_vertices(geometry, field, timestep, num_timesteps, record_streamlines)
         }
@@ -286,7 +347,57 @@ fn simulate_geometry(
             );
             (geom, vec![single_streamline])
         }
+    };
+
+    let frames = match original {
+        Some(original) => geometry_frames(original, &streamlines, streamline_kind),
+        None => Vec::new(),
+    };
+    // The frames only move forward, so extend the streamlines backward after reconstructing them
+    if bidirectional {
+        for streamline in &mut streamlines {
+            trace_backward(streamline, field, timestep, num_timesteps);
+        }
     }
+
+    Simulation {
+        geometry,
+        streamlines,
+        frames,
+    }
+}
+
+/// Reconstruct the geometry at each timestep from its streamlines
+fn geometry_frames(
+    original: Geometry,
+    streamlines: &[LineString],
+    streamline_kind: StreamlineKind,
+) -> Vec<Geometry> {
+    let num_frames = streamlines.iter().map(|s| s.0.len()).max().unwrap_or(0);
+    // A streamline stops early if it leaves the vector field
+    let position = |streamline: &LineString, t: usize| streamline.0[t.min(streamline.0.len() - 1)];
+
+    (0..num_frames)
+        .map(|t| {
+            let mut frame = original.clone();
+            match streamline_kind {
+                StreamlineKind::PerVertex => {
+                    // Relies on visiting the vertices in the same order as simulate_geom_vertices
+                    let mut streamlines = streamlines.iter();
+                    frame.map_coords_in_place_mut(|coord| match streamlines.next() {
+                        Some(streamline) if !streamline.0.is_empty() => position(streamline, t),
+                        _ => coord,
+                    });
+                }
+                StreamlineKind::PerCentroid => {
+                    let streamline = &streamlines[0];
+                    let offset = position(streamline, t) - streamline.0[0];
+                    frame.affine_transform_mut(&AffineTransform::translate(offset.x, offset.y));
+                }
+            }
+            frame
+        })
+        .collect()
 }
 
 fn simulate_coordinate(
@@ -310,7 +421,7 @@ fn simulate_coordinate(
             break;
         }
 
-        let current_vector = (field.function)(current.x, current.y);
+        let current_vector = field.at(current.x, current.y);
 
         current.x += timestep * current_vector[0];
         current.y += timestep * current_vector[1];
@@ -327,6 +438,25 @@ fn simulate_coordinate(
     (transform, LineString::new(streamline))
 }
 
+/// Prepend the streamline traced backward in time from the streamline's seed
+///
+/// Does nothing to empty streamlines, which weren't recorded.
+fn trace_backward(
+    streamline: &mut LineString,
+    field: &VectorField,
+    timestep: f64,
+    num_timesteps: usize,
+) {
+    let Some(seed) = streamline.0.first() else {
+        return;
+    };
+    let (_, backward) = simulate_coordinate(*seed, field, -timestep, num_timesteps, true);
+    // The backward streamline starts at the seed too, so skip it to avoid repeating the seed
+    let mut coords: Vec<_> = backward.0.into_iter().skip(1).rev().collect();
+    coords.append(&mut streamline.0);
+    streamline.0 = coords;
+}
+
 fn simulate_rigid_geometry(
     mut geometry: Geometry,
     vector_field: &VectorField,
@@ -377,6 +507,115 @@ fn simulate_geom_vertices(
     (geometry, streamlines)
 }
 
+/// Write one file per timestep, each containing every geometry at that timestep
+fn write_frames(
+    frame_dir: &Path,
+    frames: &[Vec<Geometry>],
+    styles: &[String],
+    format: GeometryFormat,
+) {
+    if let Err(why) = std::fs::create_dir_all(frame_dir) {
+        panic!("Couldn't create: {} because: {}", frame_dir.display(), why);
+    }
+
+    let num_frames = frames.iter().map(Vec::len).max().unwrap_or(0);
+    log::debug!("Writing {num_frames} frames to {}", frame_dir.display());
+    for t in 0..num_frames {
+        let path = frame_dir.join(format!("frame-{t:06}.{format}"));
+        let mut writer = get_output_writer(&Some(path)).unwrap();
+        for style in styles {
+            writeln!(&mut writer, "{style}").unwrap();
+        }
+        // Geometries with fewer frames stay at their final position
+        let geometries = frames
+            .iter()
+            .filter_map(|f| f.get(t).or_else(|| f.last()))
+            .cloned();
+        write_geometries(&mut writer, geometries, format);
+    }
+}
+
+fn parse_time_range(range: &str) -> Result<(f64, f64, usize), String> {
+    let [start, end, steps] = range.split(',').collect::<Vec<_>>()[..] else {
+        return Err(format!("Expected 'start,end,steps', got '{range}'"));
+    };
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<f64>()
+            .map_err(|e| format!("Failed to parse '{v}' in '{range}': {e}"))
+    };
+    let steps = steps
+        .trim()
+        .parse::<usize>()
+        .map_err(|e| format!("Failed to parse '{steps}' in '{range}': {e}"))?;
+    Ok((parse(start)?, parse(end)?, steps))
+}
+
+/// The times to render at, given the --time-range start,end,steps
+fn time_steps((start, end, steps): (f64, f64, usize)) -> Vec<f64> {
+    if steps <= 1 {
+        return vec![start];
+    }
+    (0..steps)
+        .map(|i| start + (end - start) * i as f64 / (steps - 1) as f64)
+        .collect()
+}
+
+/// Write the vector field, streamlines, and geometries, as requested by the commandline options
+fn write_output<W: Write>(
+    writer: &mut W,
+    args: &CmdlineOptions,
+    field: &VectorField,
+    geometries: Vec<Geometry>,
+    rng: &mut StdRng,
+) {
+    if args.draw_vector_field {
+        for style in &args.vector_field_style {
+            writeln!(writer, "{style}").unwrap();
+        }
+        field.write(writer, args.output_format);
+    }
+
+    let geoms_and_streamlines = simulate(
+        geometries,
+        field,
+        args.delta_t,
+        args.time_steps,
+        args.random_timesteps,
+        rng,
+        args.streamline_kind,
+        args.bidirectional,
+        !args.no_draw_streamlines,
+        args.frames.is_some(),
+    );
+    let mut geometries = Vec::new();
+    let mut streamlines = Vec::new();
+    let mut frames = Vec::new();
+    for simulation in geoms_and_streamlines {
+        geometries.push(simulation.geometry);
+        streamlines.extend(simulation.streamlines);
+        frames.push(simulation.frames);
+    }
+    let streamlines = streamlines.into_iter().map(Geometry::LineString);
+
+    if let Some(frame_dir) = &args.frames {
+        write_frames(frame_dir, &frames, &args.geometry_style, args.output_format);
+    }
+
+    if !args.no_draw_streamlines {
+        for style in &args.streamline_style {
+            writeln!(writer, "{style}").unwrap();
+        }
+        write_geometries(&mut *writer, streamlines, args.output_format);
+    }
+    if args.draw_geometries {
+        for style in &args.geometry_style {
+            writeln!(writer, "{style}").unwrap();
+        }
+        write_geometries(&mut *writer, geometries, args.output_format);
+    }
+}
+
 fn main() -> Result<(), Box<EvalAltResult>> {
     let args = CmdlineOptions::parse();
 
@@ -388,21 +627,21 @@ fn main() -> Result<(), Box<EvalAltResult>> {
 
     let seed = generate_random_seed_if_not_specified(args.seed);
     log::info!("Seeding RNG with: {}", seed);
-    let mut rng = StdRng::seed_from_u64(seed);
 
     // TODO: Add some of the noise generators as CLI options
     // let perlin = Billow::<Perlin>::new(seed as u32);
     let perlin = Perlin::new(seed as u32);
 
-    let function: Box<dyn Fn(f64, f64) -> [f64; 2]> = match args.function {
+    let function: Box<dyn Fn(f64, f64, f64) -> [f64; 2]> = match &args.function {
         Some(string) => {
             let engine = Engine::new();
             let ast = engine.compile(string)?;
 
-            let func = move |x: f64, y: f64| -> [f64; 2] {
+            let func = move |x: f64, y: f64, t: f64| -> [f64; 2] {
                 let mut scope = Scope::new();
                 scope.push("x", x);
                 scope.push("y", y);
+                scope.push("t", t);
 
                 engine.eval_ast_with_scope::<()>(&mut scope, &ast).unwrap();
 
@@ -414,13 +653,13 @@ fn main() -> Result<(), Box<EvalAltResult>> {
 
             Box::new(func)
         }
-        None => Box::new(move |x, y| {
+        None => Box::new(move |x, y, _t| {
             let angle = perlin.get([x, y]);
             [f64::cos(angle), f64::sin(angle)]
         }),
     };
 
-    let field = VectorField::new(
+    let mut field = VectorField::new(
         args.min_x,
         args.max_x,
         args.min_y,
@@ -430,41 +669,27 @@ fn main() -> Result<(), Box<EvalAltResult>> {
     );
 
     let reader = get_input_reader(&args.input).unwrap();
-    let mut writer = get_output_writer(&args.output).unwrap();
-
-    if args.draw_vector_field {
-        for style in args.vector_field_style {
-            writeln!(&mut writer, "{style}").unwrap();
-        }
-        field.write(&mut writer, args.output_format);
-    }
-
-    let geometries = read_geometries(reader, &args.input_format);
-
-    let geoms_and_streamlines = simulate(
-        geometries,
-        &field,
-        args.delta_t,
-        args.time_steps,
-        args.random_timesteps,
-        &mut rng,
-        args.streamline_kind,
-        !args.no_draw_streamlines,
-    );
-    let (geometries, streamlines): (Vec<_>, Vec<_>) = geoms_and_streamlines.unzip();
-    let streamlines = streamlines.into_iter().flatten().map(Geometry::LineString);
+    let geometries: Vec<_> = read_geometries(reader, &args.input_format)
+        .take(args.limit.unwrap_or(usize::MAX))
+        .collect();
 
-    if !args.no_draw_streamlines {
-        for style in args.streamline_style {
-            writeln!(&mut writer, "{style}").unwrap();
+    if let (Some(time_range), Some(time_dir)) = (args.time_range, &args.time_dir) {
+        if let Err(why) = std::fs::create_dir_all(time_dir) {
+            panic!("Couldn't create: {} because: {}", time_dir.display(), why);
         }
-        write_geometries(&mut writer, streamlines, args.output_format);
-    }
-    if args.draw_geometries {
-        for style in args.geometry_style {
-            writeln!(&mut writer, "{style}").unwrap();
+        for (i, time) in time_steps(time_range).into_iter().enumerate() {
+            field.time = time;
+            let path = time_dir.join(format!("time-{i:06}.{}", args.output_format));
+            log::debug!("Writing t={time} to {}", path.display());
+            let mut writer = get_output_writer(&Some(path)).unwrap();
+            // Reseed for each time, so that the --random-timesteps are the same in each output
+            let mut rng = StdRng::seed_from_u64(seed);
+            write_output(&mut writer, &args, &field, geometries.clone(), &mut rng);
         }
-        write_geometries(&mut writer, geometries, args.output_format);
+    } else {
+        let mut writer = get_output_writer(&args.output).unwrap();
+        let mut rng = StdRng::seed_from_u64(seed);
+        write_output(&mut writer, &args, &field, geometries, &mut rng);
     }
     Ok(())
 }
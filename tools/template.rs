@@ -2,7 +2,8 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use generative::io::{
-    get_input_reader, get_output_writer, read_geometries, write_geometries, GeometryFormat,
+    get_input_reader, get_output_writer, read_geometries, write_crs_header, write_geometries,
+    GeometryFormat, GeometryInputFormat,
 };
 use stderrlog::ColorChoice;
 
@@ -21,8 +22,12 @@ struct CmdlineOptions {
     input: Option<PathBuf>,
 
     /// Input geometry format.
-    #[clap(short = 'I', long, default_value_t = GeometryFormat::Wkt)]
-    input_format: GeometryFormat,
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
 
     /// Output file to write result to. Defaults to stdout.
     #[clap(short, long)]
@@ -31,6 +36,12 @@ struct CmdlineOptions {
     /// Output geometry format.
     #[clap(short = 'O', long, default_value_t = GeometryFormat::Wkt)]
     output_format: GeometryFormat,
+
+    /// Prepend a '# CRS: <CRS>' header (like EPSG:4326) to the WKT output
+    ///
+    /// The header is ignored when reading WKT.
+    #[clap(long)]
+    crs: Option<String>,
 }
 
 fn main() {
@@ -43,10 +54,14 @@ fn main() {
         .expect("Failed to initialize stderrlog");
 
     let reader = get_input_reader(&args.input).unwrap();
-    let geometries = read_geometries(reader, &args.input_format); // lazily loaded
+    let geometries = read_geometries(reader, &args.input_format) // lazily loaded
+        .take(args.limit.unwrap_or(usize::MAX));
 
     // Do some kind of transformation to the geometries here.
 
-    let writer = get_output_writer(&args.output).unwrap();
+    let mut writer = get_output_writer(&args.output).unwrap();
+    if let Some(crs) = &args.crs {
+        write_crs_header(&mut writer, crs, args.output_format);
+    }
     write_geometries(writer, geometries, args.output_format);
 }
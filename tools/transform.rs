@@ -1,14 +1,24 @@
+use std::io::Read;
 use std::path::PathBuf;
 
-use clap::{Parser, ValueEnum};
+use clap::{Args, Parser, ValueEnum};
+use generative::flatten::flatten_nested_geometries;
+#[cfg(feature = "parallel")]
+use generative::io::read_wkt_geometries_parallel;
 use generative::io::{
-    get_input_reader, get_output_writer, read_geometries, write_geometries, GeometryFormat,
+    get_input_reader, get_output_writer, read_geometries, read_labeled_wkt_geometries,
+    write_crs_header, write_geometries, write_labeled_wkt_geometries, GeometryFormat,
+    GeometryInputFormat, Progress,
 };
+use generative::winding::{normalize_winding, Orientation};
 use geo::{
-    coord, AffineOps, AffineTransform, BoundingRect, Coord, Geometry, MapCoordsInPlace, Rect,
+    coord, AffineOps, AffineTransform, BoundingRect, Centroid, Coord, Geometry, MapCoordsInPlace,
+    Rect,
 };
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use stderrlog::ColorChoice;
-use wkt::ToWkt;
 
 #[derive(Debug, Clone, ValueEnum)]
 enum TransformCenter {
@@ -24,13 +34,17 @@ enum TransformCenter {
 ///
 /// Transformations are applied in the order:
 ///
-/// 1. rotation
-/// 2. scale
-/// 3. offset
-/// 4. skew
+/// 0. exploding multi-geometries
+/// 1. random per-geometry rotation, scale, and offset
+/// 2. rotation
+/// 3. scale
+/// 4. offset
+/// 5. skew
+/// 6. isometric or dimetric projection
+/// 7. tiling
 ///
-/// If you want to apply transformations in any other order, you can chain invocations of this
-/// command, specifying only one transformation per invocation.
+/// If you want to apply transformations in any other order, use --ops to give the sequence of
+/// transformations, like --ops "rotate(30);translate(10,0);scale(2,2);skew(0,10)".
 ///
 /// If you want to apply transformations to 3D geometries, they must first be projected to 2D using
 /// the project.py tool.
@@ -49,18 +63,166 @@ struct CmdlineOptions {
     #[clap(short = 'O', long, default_value_t = GeometryFormat::Wkt)]
     output_format: GeometryFormat,
 
+    /// Prepend a '# CRS: <CRS>' header (like EPSG:4326) to the WKT output
+    ///
+    /// The header is ignored when reading WKT.
+    #[clap(long)]
+    crs: Option<String>,
+
     /// Input file to read input from. Defaults to stdin.
     #[clap(short, long)]
     input: Option<PathBuf>,
 
     /// Input geometry format.
-    #[clap(short = 'I', long, default_value_t = GeometryFormat::Wkt)]
-    input_format: GeometryFormat,
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// Periodically log how many geometries have been processed to stderr
+    #[clap(long)]
+    progress: bool,
+
+    /// Pass through an optional 'id<TAB>' label before each WKT geometry
+    ///
+    /// Lets geometries be identified across a pipeline. Only supported for WKT input and output.
+    /// Has to load all of the geometries into RAM.
+    #[clap(long, conflicts_with_all = ["tile_count", "explode"])]
+    labeled: bool,
+
+    /// Split MULTI-geometries and GEOMETRYCOLLECTIONs into their component geometries
+    ///
+    /// Nested collections are split recursively, so that each component is transformed as its own
+    /// geometry. Applied before any other transformation.
+    #[clap(long)]
+    explode: bool,
 
     /// How to center the affine transformation
     #[clap(long, default_value = "origin")]
     center: TransformCenter,
 
+    #[clap(flatten)]
+    transform: TransformParams,
+
+    /// Add an x offset of this fraction of the whole collection's bounding box width
+    ///
+    /// The bounding box is measured before any transformations. Added to any --offset-x.
+    #[clap(long, conflicts_with = "ops")]
+    offset_x_frac: Option<f64>,
+
+    /// Add a y offset of this fraction of the whole collection's bounding box height
+    ///
+    /// The bounding box is measured before any transformations. Added to any --offset-y.
+    #[clap(long, conflicts_with = "ops")]
+    offset_y_frac: Option<f64>,
+
+    /// Randomly rotate each geometry up to the given number of degrees CW or CCW
+    ///
+    /// The random transformations are applied to each geometry about its centroid, before any
+    /// other transformation.
+    #[clap(long)]
+    random_rotation: Option<f64>,
+
+    /// Randomly scale each geometry by a factor in the given (min, max) range
+    ///
+    /// The range may be given in either order.
+    #[clap(long, num_args = 2)]
+    random_scale: Vec<f64>,
+
+    /// Randomly offset each geometry up to the given distance along each axis
+    #[clap(long)]
+    random_offset: Option<f64>,
+
+    /// The random seed to use for the random transformations. Use zero to let the tool pick its
+    /// own random seed.
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Convert the input geometries from (x, y) to (r, theta)
+    ///
+    /// Any affine transformations are applied in the original coordinate space
+    #[clap(long, conflicts_with = "from_polar")]
+    to_polar: bool,
+
+    /// Convert the input geometries from (r, theta) to (x, y)
+    ///
+    /// Any affine transformations are applied in the original coordinate space
+    #[clap(long, conflicts_with = "to_polar")]
+    from_polar: bool,
+
+    /// What to center the --to-polar and --from-polar conversions on
+    ///
+    /// The coordinates are offset so that the center is at the origin, converted, and then offset
+    /// back. The bounding boxes are measured right before the conversion.
+    #[clap(long, default_value = "origin")]
+    polar_center: TransformCenter,
+
+    /// Scale coordinate 1 (x, or r) to fit in the given range
+    ///
+    /// If specified, will be applied regardless of whether polar conversion is performed
+    #[clap(long, num_args = 2)]
+    range1: Vec<f64>,
+
+    /// Scale coordinate 2 (y, or theta) to fit in the given range
+    ///
+    /// If specified, will be applied regardless of whether polar conversion is performed
+    #[clap(long, num_args = 2)]
+    range2: Vec<f64>,
+
+    /// Replicate the whole collection across a grid of 'cols,rows' tiles, like a wallpaper
+    ///
+    /// Each tile is a copy of the collection translated by (i * --tile-x, j * --tile-y). Applied
+    /// after the affine transformations, and before --range1, --range2, and --arc. Has to load all
+    /// of the geometries into RAM.
+    #[clap(long, value_parser = parse_tile_count)]
+    tile_count: Option<(usize, usize)>,
+
+    /// The x spacing between tiles. Defaults to the width of the collection's bounding box.
+    #[clap(long, requires = "tile_count")]
+    tile_x: Option<f64>,
+
+    /// The y spacing between tiles. Defaults to the height of the collection's bounding box.
+    #[clap(long, requires = "tile_count")]
+    tile_y: Option<f64>,
+
+    /// Randomly offset each tile up to the given distance along each axis
+    #[clap(long, requires = "tile_count")]
+    tile_jitter: Option<f64>,
+
+    /// Randomly rotate each tile up to the given number of degrees CW or CCW about its center
+    #[clap(long, requires = "tile_count")]
+    tile_rotation: Option<f64>,
+
+    /// Bend the geometries around an arc of the given 'radius,sweep' in degrees, like curved text
+    ///
+    /// The x range of the bounding box is mapped onto the sweep, centered on the top of the arc
+    /// and increasing clockwise. The y coordinate is the radial offset from the arc, so the bottom
+    /// of the bounding box lies on the arc. The arc is centered on the origin. A sweep of 360
+    /// wraps the geometries around a full circle. Applied after --range1 and --range2.
+    ///
+    /// Only the vertices are moved, so long segments should be subdivided first to bend smoothly.
+    #[clap(long, value_parser = parse_arc, conflicts_with_all = ["to_polar", "from_polar"])]
+    arc: Option<(f64, f64)>,
+
+    /// Round each coordinate to the nearest multiple of the given spacing
+    ///
+    /// Applied after all other transformations. Unlike snapping, doesn't merge or remove any
+    /// vertices.
+    #[clap(long)]
+    quantize: Option<f64>,
+
+    /// Force the winding order of polygon exterior rings, with interiors wound the opposite way
+    ///
+    /// Applied last, since mirroring and polar transformations can flip the winding order.
+    #[clap(long)]
+    orient: Option<Orientation>,
+}
+
+/// The affine transformation parameters, shared by the global and random per-geometry transforms
+#[derive(Debug, Clone, Default, Args)]
+struct TransformParams {
     /// Degrees CCW rotation, applied before any other transformation
     #[clap(short, long, default_value_t = 0.0)]
     rotation: f64,
@@ -93,33 +255,170 @@ struct CmdlineOptions {
     #[clap(long)]
     skew_y: Option<f64>,
 
-    /// Convert the input geometries from (x, y) to (r, theta)
+    /// A ';' separated sequence of transformations to apply in the given order
     ///
-    /// Any affine transformations are applied in the original coordinate space
-    #[clap(long, conflicts_with = "from_polar")]
-    to_polar: bool,
-
-    /// Convert the input geometries from (r, theta) to (x, y)
+    /// Supports rotate(degrees), scale(s), scale(x,y), translate(x,y), and skew(x,y), where
+    /// rotations, scales, and skews are about the --center. Can't be combined with the other
+    /// transformation options.
+    #[clap(
+        long,
+        value_parser = parse_ops,
+        conflicts_with_all = ["rotation", "scale", "scale_x", "scale_y", "offset_x", "offset_y", "skew_x", "skew_y"],
+    )]
+    ops: Option<TransformOps>,
+
+    /// Round the rotation and skew angles to the nearest multiple of this many degrees
     ///
-    /// Any affine transformations are applied in the original coordinate space
-    #[clap(long, conflicts_with = "to_polar")]
-    from_polar: bool,
+    /// Applies to the random rotations and the --ops rotations and skews too.
+    #[clap(long)]
+    angle_snap: Option<f64>,
 
-    /// Scale coordinate 1 (x, or r) to fit in the given range
+    /// Project the geometries to a true isometric view, after the other affine transformations
     ///
-    /// If specified, will be applied regardless of whether polar conversion is performed
-    #[clap(long, num_args = 2)]
-    range1: Vec<f64>,
+    /// Equivalent to rotate(45) followed by scale(1, tan(30)), so that the x and y axes are drawn
+    /// 30 degrees from horizontal. Composes with --ops, and is about the --center.
+    #[clap(long, conflicts_with = "dimetric")]
+    iso: bool,
 
-    /// Scale coordinate 2 (y, or theta) to fit in the given range
+    /// Project the geometries to the 2:1 dimetric view common in pixel art
     ///
-    /// If specified, will be applied regardless of whether polar conversion is performed
-    #[clap(long, num_args = 2)]
-    range2: Vec<f64>,
+    /// Like --iso, but scales y by 0.5 instead, so the x and y axes rise one unit for every two
+    /// across, or about 26.57 degrees from horizontal.
+    #[clap(long)]
+    dimetric: bool,
 }
 
-fn build_transform(args: &CmdlineOptions, center: Coord) -> AffineTransform {
-    let mut transform = AffineTransform::rotate(args.rotation, center);
+#[derive(Debug, Clone, PartialEq)]
+enum TransformOp {
+    Rotate(f64),
+    Scale(f64, f64),
+    Translate(f64, f64),
+    Skew(f64, f64),
+}
+
+impl TransformOp {
+    fn snap_angles(&self, snap: impl Fn(f64) -> f64) -> TransformOp {
+        match *self {
+            TransformOp::Rotate(degrees) => TransformOp::Rotate(snap(degrees)),
+            TransformOp::Skew(x, y) => TransformOp::Skew(snap(x), snap(y)),
+            ref other => other.clone(),
+        }
+    }
+
+    fn to_transform(&self, center: Coord) -> AffineTransform {
+        match *self {
+            TransformOp::Rotate(degrees) => AffineTransform::rotate(degrees, center),
+            TransformOp::Scale(x, y) => AffineTransform::scale(x, y, center),
+            TransformOp::Translate(x, y) => AffineTransform::translate(x, y),
+            TransformOp::Skew(x, y) => AffineTransform::skew(x, y, center),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TransformOps(Vec<TransformOp>);
+
+fn parse_op(op: &str) -> Result<TransformOp, String> {
+    let (name, params) = op
+        .strip_suffix(')')
+        .and_then(|op| op.split_once('('))
+        .ok_or_else(|| format!("Expected '<op>(<args>)', got '{op}'"))?;
+    let params = params
+        .split(',')
+        .map(|p| {
+            p.trim()
+                .parse::<f64>()
+                .map_err(|e| format!("Failed to parse '{p}' in '{op}': {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match (name.trim(), params.as_slice()) {
+        ("rotate", [degrees]) => Ok(TransformOp::Rotate(*degrees)),
+        ("scale", [s]) => Ok(TransformOp::Scale(*s, *s)),
+        ("scale", [x, y]) => Ok(TransformOp::Scale(*x, *y)),
+        ("translate", [x, y]) => Ok(TransformOp::Translate(*x, *y)),
+        ("skew", [x, y]) => Ok(TransformOp::Skew(*x, *y)),
+        ("rotate" | "scale" | "translate" | "skew", _) => {
+            Err(format!("Wrong number of arguments in '{op}'"))
+        }
+        (name, _) => Err(format!(
+            "Unknown transformation '{name}'; expected rotate, scale, translate, or skew"
+        )),
+    }
+}
+
+fn parse_ops(ops: &str) -> Result<TransformOps, String> {
+    ops.split(';')
+        .map(str::trim)
+        .filter(|op| !op.is_empty())
+        .map(parse_op)
+        .collect::<Result<Vec<_>, _>>()
+        .map(TransformOps)
+}
+
+fn parse_arc(arc: &str) -> Result<(f64, f64), String> {
+    let (radius, sweep) = arc
+        .split_once(',')
+        .ok_or_else(|| format!("Expected 'radius,sweep', got '{arc}'"))?;
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<f64>()
+            .map_err(|e| format!("Failed to parse '{v}' in '{arc}': {e}"))
+    };
+    Ok((parse(radius)?, parse(sweep)?))
+}
+
+fn parse_tile_count(count: &str) -> Result<(usize, usize), String> {
+    let (cols, rows) = count
+        .split_once(',')
+        .ok_or_else(|| format!("Expected 'cols,rows', got '{count}'"))?;
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<usize>()
+            .map_err(|e| format!("Failed to parse '{v}' in '{count}': {e}"))
+    };
+    Ok((parse(cols)?, parse(rows)?))
+}
+
+/// Exit with an error if the given option is negative or NaN, since it's sampled from [-v, v]
+fn check_non_negative(name: &str, value: Option<f64>) {
+    if let Some(value) = value {
+        if value.is_nan() || value < 0.0 {
+            eprintln!("--{name} must be non-negative, got {value}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Build the affine transform, followed by the --iso or --dimetric projection, if any
+fn build_transform(args: &TransformParams, center: Coord) -> AffineTransform {
+    let transform = build_affine_transform(args, center);
+    let y_scale = if args.iso {
+        30.0_f64.to_radians().tan()
+    } else if args.dimetric {
+        0.5
+    } else {
+        return transform;
+    };
+    transform.rotated(45.0, center).scaled(1.0, y_scale, center)
+}
+
+fn build_affine_transform(args: &TransformParams, center: Coord) -> AffineTransform {
+    let snap = |degrees: f64| match args.angle_snap {
+        Some(modulus) if modulus > 0.0 => (degrees / modulus).round() * modulus,
+        _ => degrees,
+    };
+
+    if let Some(ops) = &args.ops {
+        return ops
+            .0
+            .iter()
+            .fold(AffineTransform::identity(), |transform, op| {
+                transform.compose(&op.snap_angles(snap).to_transform(center))
+            });
+    }
+
+    let mut transform = AffineTransform::rotate(snap(args.rotation), center);
 
     if let Some(scale) = args.scale {
         transform = transform.scaled(scale, scale, center);
@@ -148,7 +447,7 @@ fn build_transform(args: &CmdlineOptions, center: Coord) -> AffineTransform {
         }
         (None, None) => {}
     }
-    match (args.skew_x, args.skew_y) {
+    match (args.skew_x.map(snap), args.skew_y.map(snap)) {
         (Some(x), Some(y)) => {
             transform = transform.skewed(x, y, center);
         }
@@ -164,20 +463,17 @@ fn build_transform(args: &CmdlineOptions, center: Coord) -> AffineTransform {
     transform
 }
 
-fn bounding_box(geometries: &[Geometry]) -> Rect {
+/// The bounding box of the geometries, or None if they're all EMPTY
+fn bounding_box(geometries: &[Geometry]) -> Option<Rect> {
     // Calculate the center of the bounding box; needed to build the AffineTransform
     let mut min_x = f64::MAX;
     let mut min_y = f64::MAX;
     let mut max_x = f64::MIN;
     let mut max_y = f64::MIN;
-    for geom in geometries.iter() {
-        let temp = geom.bounding_rect().unwrap_or_else(|| {
-            panic!(
-                "Geometry '{}' didn't have a bounding rectangle",
-                geom.to_wkt()
-            )
-        });
-
+    let mut found = false;
+    // EMPTY geometries don't have a bounding rectangle, and don't contribute to the bounds
+    for temp in geometries.iter().filter_map(|g| g.bounding_rect()) {
+        found = true;
         let min = temp.min();
         let max = temp.max();
 
@@ -186,31 +482,52 @@ fn bounding_box(geometries: &[Geometry]) -> Rect {
         max_x = max_x.max(max.x);
         max_y = max_y.max(max.y);
     }
-    Rect::new(coord! {x:min_x, y:min_y}, coord! {x:max_x, y:max_y})
+    found.then(|| Rect::new(coord! {x:min_x, y:min_y}, coord! {x:max_x, y:max_y}))
 }
 
-fn affine_transform(
-    geometries: impl Iterator<Item = Geometry> + 'static,
+/// Add the offsets relative to the whole collection's bounding box to the absolute offsets
+fn relative_offsets<'a>(
+    geometries: Box<dyn Iterator<Item = Geometry> + 'a>,
     args: &CmdlineOptions,
-) -> Box<dyn Iterator<Item = Geometry> + '_> {
-    match args.center {
+) -> (Box<dyn Iterator<Item = Geometry> + 'a>, TransformParams) {
+    let mut params = args.transform.clone();
+    if args.offset_x_frac.is_none() && args.offset_y_frac.is_none() {
+        return (geometries, params);
+    }
+
+    // Like --center=whole-collection, this has to load all of the geometries into RAM
+    let geometries: Vec<_> = geometries.collect();
+    let Some(rect) = bounding_box(&geometries) else {
+        return (Box::new(geometries.into_iter()), params);
+    };
+    if let Some(frac) = args.offset_x_frac {
+        params.offset_x = Some(params.offset_x.unwrap_or(0.0) + frac * rect.width());
+    }
+    if let Some(frac) = args.offset_y_frac {
+        params.offset_y = Some(params.offset_y.unwrap_or(0.0) + frac * rect.height());
+    }
+    (Box::new(geometries.into_iter()), params)
+}
+
+fn affine_transform<'a>(
+    geometries: impl Iterator<Item = Geometry> + 'a,
+    center: &TransformCenter,
+    params: &'a TransformParams,
+) -> Box<dyn Iterator<Item = Geometry> + 'a> {
+    match center {
         TransformCenter::Origin => {
             let center = coord! {x:0.0, y: 0.0};
-            let transform = build_transform(args, center);
+            let transform = build_transform(params, center);
             Box::new(geometries.map(move |geom| geom.affine_transform(&transform)))
         }
         TransformCenter::EachGeometry => {
             let map = geometries.map(move |geom| {
-                let center = geom
-                    .bounding_rect()
-                    .unwrap_or_else(|| {
-                        panic!(
-                            "Geometry '{}' didn't have a bounding rectangle",
-                            geom.to_wkt()
-                        )
-                    })
-                    .center();
-                let transform = build_transform(args, center);
+                // There's nothing to transform in an EMPTY geometry
+                let Some(rect) = geom.bounding_rect() else {
+                    return geom;
+                };
+                let center = rect.center();
+                let transform = build_transform(params, center);
                 geom.affine_transform(&transform)
             });
             Box::new(map)
@@ -219,9 +536,8 @@ fn affine_transform(
         // performing the transformations)
         TransformCenter::WholeCollection => {
             let geometries: Vec<_> = geometries.collect();
-            let rect = bounding_box(&geometries);
-            let center = rect.center();
-            let transform = build_transform(args, center);
+            let center = bounding_box(&geometries).map_or(Coord::zero(), |r| r.center());
+            let transform = build_transform(params, center);
 
             // Instead of applying the transformation in-place all at once _and then_ writing the
             // results, we lazily perform the transformation so that we can pipeline the
@@ -234,6 +550,99 @@ fn affine_transform(
     }
 }
 
+fn generate_random_seed_if_not_specified(seed: u64) -> u64 {
+    if seed == 0 {
+        let mut rng = rand::thread_rng();
+        rng.gen()
+    } else {
+        seed
+    }
+}
+
+fn random_transform<'a>(
+    geometries: impl Iterator<Item = Geometry> + 'a,
+    args: &CmdlineOptions,
+) -> Box<dyn Iterator<Item = Geometry> + 'a> {
+    if args.random_rotation.is_none()
+        && args.random_scale.is_empty()
+        && args.random_offset.is_none()
+    {
+        return Box::new(geometries);
+    }
+
+    let seed = generate_random_seed_if_not_specified(args.seed);
+    log::info!("Randomly transforming geometries with seed {seed}");
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let rotation = args.random_rotation.map(|r| Uniform::new_inclusive(-r, r));
+    let scale = if args.random_scale.len() == 2 {
+        let (a, b) = (args.random_scale[0], args.random_scale[1]);
+        Some(Uniform::new_inclusive(a.min(b), a.max(b)))
+    } else {
+        None
+    };
+    let offset = args.random_offset.map(|o| Uniform::new_inclusive(-o, o));
+    let angle_snap = args.transform.angle_snap;
+
+    let map = geometries.map(move |geom| {
+        let Some(center) = geom.centroid() else {
+            return geom;
+        };
+        let params = TransformParams {
+            rotation: rotation.map_or(0.0, |d| d.sample(&mut rng)),
+            scale: scale.map(|d| d.sample(&mut rng)),
+            offset_x: offset.map(|d| d.sample(&mut rng)),
+            offset_y: offset.map(|d| d.sample(&mut rng)),
+            angle_snap,
+            ..Default::default()
+        };
+        let transform = build_transform(&params, center.0);
+        geom.affine_transform(&transform)
+    });
+    Box::new(map)
+}
+
+/// Replicate the geometries across a grid of tiles, each randomly jittered and rotated as a whole
+fn tile(
+    geometries: Vec<Geometry>,
+    (cols, rows): (usize, usize),
+    args: &CmdlineOptions,
+) -> Vec<Geometry> {
+    let Some(bounds) = bounding_box(&geometries) else {
+        return geometries;
+    };
+    let dx = args.tile_x.unwrap_or(bounds.width());
+    let dy = args.tile_y.unwrap_or(bounds.height());
+
+    let jitter = args.tile_jitter.map(|j| Uniform::new_inclusive(-j, j));
+    let rotation = args.tile_rotation.map(|r| Uniform::new_inclusive(-r, r));
+    let seed = if jitter.is_some() || rotation.is_some() {
+        let seed = generate_random_seed_if_not_specified(args.seed);
+        log::info!("Randomly transforming tiles with seed {seed}");
+        seed
+    } else {
+        args.seed
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut tiled = Vec::with_capacity(cols * rows * geometries.len());
+    for j in 0..rows {
+        for i in 0..cols {
+            let params = TransformParams {
+                rotation: rotation.map_or(0.0, |d| d.sample(&mut rng)),
+                offset_x: Some(i as f64 * dx + jitter.map_or(0.0, |d| d.sample(&mut rng))),
+                offset_y: Some(j as f64 * dy + jitter.map_or(0.0, |d| d.sample(&mut rng))),
+                angle_snap: args.transform.angle_snap,
+                ..Default::default()
+            };
+            // Rotate each tile about its own center, before it's offset into place
+            let transform = build_transform(&params, bounds.center());
+            tiled.extend(geometries.iter().map(|g| g.affine_transform(&transform)));
+        }
+    }
+    tiled
+}
+
 fn from_polar(coord: Coord) -> Coord {
     let r = coord.x;
     let theta = coord.y;
@@ -249,6 +658,34 @@ fn to_polar(coord: Coord) -> Coord {
     coord! { x: r, y: theta}
 }
 
+/// Convert to or from polar coordinates about the given center
+fn polar_transform<'a>(
+    geometries: impl Iterator<Item = Geometry> + 'a,
+    center: &TransformCenter,
+    convert: fn(Coord) -> Coord,
+) -> Box<dyn Iterator<Item = Geometry> + 'a> {
+    let about = move |center: Coord| move |coord: Coord| convert(coord - center) + center;
+    match center {
+        TransformCenter::Origin => Box::new(geoms_coordwise(geometries, convert)),
+        TransformCenter::EachGeometry => {
+            let map = geometries.map(move |mut geom| {
+                // There's nothing to convert in an EMPTY geometry
+                let Some(rect) = geom.bounding_rect() else {
+                    return geom;
+                };
+                geom.map_coords_in_place(about(rect.center()));
+                geom
+            });
+            Box::new(map)
+        }
+        TransformCenter::WholeCollection => {
+            let geometries: Vec<_> = geometries.collect();
+            let center = bounding_box(&geometries).map_or(Coord::zero(), |r| r.center());
+            Box::new(geoms_coordwise(geometries.into_iter(), about(center)))
+        }
+    }
+}
+
 fn scale_range(src: &[f64; 2], dst: &[f64; 2], v: f64) -> f64 {
     (dst[1] - dst[0]) * (v - src[0]) / (src[1] - src[0]) + dst[0]
 }
@@ -275,6 +712,24 @@ fn scale_coord_range(
     coord! {x: x, y: y}
 }
 
+fn arc(bounds: &Rect, radius: f64, sweep: f64, coord: Coord) -> Coord {
+    let min = bounds.min();
+    let t = if bounds.width() > 0.0 {
+        (coord.x - min.x) / bounds.width()
+    } else {
+        0.5
+    };
+    let theta = std::f64::consts::FRAC_PI_2 + sweep * (0.5 - t);
+    from_polar(coord! {x: radius + coord.y - min.y, y: theta})
+}
+
+fn quantize(coord: Coord, spacing: f64) -> Coord {
+    coord! {
+        x: (coord.x / spacing).round() * spacing,
+        y: (coord.y / spacing).round() * spacing,
+    }
+}
+
 fn geoms_coordwise(
     geometries: impl Iterator<Item = Geometry>,
     transform: impl Fn(Coord) -> Coord + Copy,
@@ -285,6 +740,22 @@ fn geoms_coordwise(
     })
 }
 
+/// Read the geometries, parsing WKT in parallel if the whole collection gets loaded anyways
+fn read_unlabeled_geometries<R: Read + 'static>(
+    reader: R,
+    args: &CmdlineOptions,
+) -> Box<dyn Iterator<Item = Geometry>> {
+    #[cfg(feature = "parallel")]
+    if matches!(args.center, TransformCenter::WholeCollection)
+        && matches!(args.input_format, GeometryInputFormat::Wkt)
+        && args.limit.is_none()
+    {
+        return Box::new(read_wkt_geometries_parallel(reader).into_iter());
+    }
+    let limit = args.limit.unwrap_or(usize::MAX);
+    Box::new(read_geometries(reader, &args.input_format).take(limit))
+}
+
 fn main() {
     let args = CmdlineOptions::parse();
 
@@ -294,14 +765,58 @@ fn main() {
         .init()
         .expect("Failed to initialize stderrlog");
 
+    if args.labeled
+        && !(matches!(args.input_format, GeometryInputFormat::Wkt)
+            && matches!(args.output_format, GeometryFormat::Wkt))
+    {
+        eprintln!("--labeled requires WKT input and output");
+        std::process::exit(1);
+    }
+    check_non_negative("random-rotation", args.random_rotation);
+    check_non_negative("random-offset", args.random_offset);
+    check_non_negative("tile-jitter", args.tile_jitter);
+    check_non_negative("tile-rotation", args.tile_rotation);
+    if let Some(spacing) = args.quantize {
+        if spacing.is_nan() || spacing <= 0.0 {
+            eprintln!("--quantize must be positive, got {spacing}");
+            std::process::exit(1);
+        }
+    }
+    if args.random_scale.iter().any(|s| s.is_nan()) {
+        eprintln!("--random-scale must not be NaN");
+        std::process::exit(1);
+    }
+
     let reader = get_input_reader(&args.input).unwrap();
-    let writer = get_output_writer(&args.output).unwrap();
-    let geometries = read_geometries(reader, &args.input_format);
-    let mut transformed = affine_transform(geometries, &args);
+    let mut writer = get_output_writer(&args.output).unwrap();
+    let limit = args.limit.unwrap_or(usize::MAX);
+    let mut labels = Vec::new();
+    let geometries: Box<dyn Iterator<Item = Geometry>> = if args.labeled {
+        let geometries: Vec<_>;
+        (labels, geometries) = read_labeled_wkt_geometries(reader).take(limit).unzip();
+        Box::new(geometries.into_iter())
+    } else {
+        read_unlabeled_geometries(reader, &args)
+    };
+    let geometries = Progress::new(geometries, args.progress);
+    let geometries: Box<dyn Iterator<Item = Geometry>> = if args.explode {
+        Box::new(flatten_nested_geometries(geometries))
+    } else {
+        Box::new(geometries)
+    };
+    let geometries = random_transform(geometries, &args);
+    let (geometries, params) = relative_offsets(geometries, &args);
+    let mut transformed = affine_transform(geometries, &args.center, &params);
+
+    if let Some(count) = args.tile_count {
+        let geometries: Vec<_> = transformed.collect();
+        transformed = Box::new(tile(geometries, count, &args).into_iter());
+    }
 
     if args.range1.len() == 2 || args.range2.len() == 2 {
         let geometries: Vec<_> = transformed.collect();
-        let bounds = bounding_box(&geometries);
+        // If every geometry is EMPTY, there aren't any coordinates to scale
+        let bounds = bounding_box(&geometries).unwrap_or(Rect::new(Coord::zero(), Coord::zero()));
 
         let mut x_dst = None;
         let mut y_dst = None;
@@ -328,11 +843,38 @@ fn main() {
         transformed = Box::new(scaled.into_iter());
     }
 
+    if let Some((radius, sweep)) = args.arc {
+        let geometries: Vec<_> = transformed.collect();
+        let bounds = bounding_box(&geometries).unwrap_or(Rect::new(Coord::zero(), Coord::zero()));
+        let sweep = sweep.to_radians();
+        let bent: Vec<_> = geoms_coordwise(geometries.into_iter(), |coord| {
+            arc(&bounds, radius, sweep, coord)
+        })
+        .collect();
+        transformed = Box::new(bent.into_iter());
+    }
+
     if args.to_polar {
-        transformed = Box::new(geoms_coordwise(transformed, to_polar));
+        transformed = polar_transform(transformed, &args.polar_center, to_polar);
     } else if args.from_polar {
-        transformed = Box::new(geoms_coordwise(transformed, from_polar));
+        transformed = polar_transform(transformed, &args.polar_center, from_polar);
     }
 
-    write_geometries(writer, transformed, args.output_format);
+    if let Some(spacing) = args.quantize {
+        transformed = Box::new(geoms_coordwise(transformed, move |c| quantize(c, spacing)));
+    }
+
+    if let Some(orientation) = args.orient {
+        transformed = Box::new(transformed.map(move |g| normalize_winding(g, orientation)));
+    }
+
+    if let Some(crs) = &args.crs {
+        write_crs_header(&mut writer, crs, args.output_format);
+    }
+    if args.labeled {
+        // None of the transformations add or remove geometries, so the labels still line up
+        write_labeled_wkt_geometries(writer, labels.into_iter().zip(transformed));
+    } else {
+        write_geometries(writer, transformed, args.output_format);
+    }
 }
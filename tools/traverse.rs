@@ -1,12 +1,15 @@
 use std::cmp::Ordering;
+use std::io::Write;
 use std::path::PathBuf;
 
 use clap::Parser;
-use generative::graph::GeometryGraph;
+use generative::graph::{largest_component, GeometryGraph};
 use generative::io::{
-    get_input_reader, get_output_writer, read_tgf_graph, write_geometries, GeometryFormat,
+    get_input_reader, get_output_writer, read_graph, write_geometries,
+    write_labeled_wkt_geometries, GeometryFormat,
 };
-use geo::{Geometry, LineString, Point};
+use geo::{Euclidean, Geometry, Length, LineString, Point};
+use petgraph::visit::EdgeRef;
 use petgraph::{EdgeType, Undirected};
 use rand::distributions::{Distribution, Uniform};
 use rand::rngs::StdRng;
@@ -16,7 +19,8 @@ use stderrlog::ColorChoice;
 
 /// Randomly traverse the given graph.
 ///
-/// May not visit every node.
+/// May not visit every node. Use --cover-all-edges to instead decompose the graph into the fewest
+/// edge-disjoint trails that draw every edge exactly once.
 #[derive(Debug, Parser)]
 #[clap(name = "traverse", verbatim_doc_comment)]
 struct CmdlineOptions {
@@ -65,6 +69,35 @@ struct CmdlineOptions {
     /// Output untraversed nodes at the end
     #[clap(short = 'u', long, default_value_t = false)]
     untraversed: bool,
+
+    /// Instead of random traversals, cover every edge exactly once
+    ///
+    /// Each connected component is decomposed into the minimum number of edge-disjoint trails,
+    /// which is one trail per pair of odd-degree nodes (or a single closed trail if there are no
+    /// odd-degree nodes). Ignores the other traversal options.
+    #[clap(long, default_value_t = false)]
+    cover_all_edges: bool,
+
+    /// Only keep the largest connected component of the graph, discarding any stray fragments
+    #[clap(long)]
+    largest_component: bool,
+
+    /// Only output the traversals that are at least this long
+    ///
+    /// Filtered out traversals still count towards --traversals, and with --remove-after-traverse,
+    /// their edges are still removed.
+    #[clap(long)]
+    min_path_length: Option<f64>,
+
+    /// Only output the traversals that are at most this long
+    #[clap(long)]
+    max_path_length: Option<f64>,
+
+    /// Write each traversal's length before it, as a 'length<TAB>' label
+    ///
+    /// This is the labeled WKT that 'transform --labeled' reads. Requires --output-format=wkt.
+    #[clap(long)]
+    emit_lengths: bool,
 }
 
 fn generate_random_seed_if_not_specified(seed: u64) -> u64 {
@@ -147,6 +180,135 @@ where
     }
 }
 
+/// Decompose the graph into the minimum number of edge-disjoint trails that cover every edge
+///
+/// The odd-degree nodes in each connected component are paired up and joined with virtual edges,
+/// which makes every node even-degree. The Eulerian circuit of the augmented component is then cut
+/// at each virtual edge.
+fn cover_all_edges(graph: &GeometryGraph<Undirected>) -> Vec<LineString> {
+    let points: Vec<Point> = graph.node_weights().copied().collect();
+    // (source, target, is_virtual)
+    let mut edges: Vec<(usize, usize, bool)> = graph
+        .edge_references()
+        .map(|e| (e.source().index(), e.target().index(), false))
+        .collect();
+    let mut adjacency = vec![Vec::new(); points.len()];
+    for (i, (source, target, _)) in edges.iter().enumerate() {
+        adjacency[*source].push(i);
+        adjacency[*target].push(i);
+    }
+
+    // Find the connected components, and pair up the odd-degree nodes in each of them
+    let mut component_starts = Vec::new();
+    let mut visited = vec![false; points.len()];
+    for start in 0..points.len() {
+        if visited[start] || adjacency[start].is_empty() {
+            continue;
+        }
+        component_starts.push(start);
+
+        let mut odd_nodes = Vec::new();
+        let mut to_visit = vec![start];
+        visited[start] = true;
+        while let Some(node) = to_visit.pop() {
+            if adjacency[node].len() % 2 != 0 {
+                odd_nodes.push(node);
+            }
+            for edge in &adjacency[node] {
+                let (source, target, _) = edges[*edge];
+                let neighbor = if source == node { target } else { source };
+                if !visited[neighbor] {
+                    visited[neighbor] = true;
+                    to_visit.push(neighbor);
+                }
+            }
+        }
+
+        // There's always an even number of odd-degree nodes in a component
+        for pair in odd_nodes.chunks_exact(2) {
+            let virtual_edge = edges.len();
+            edges.push((pair[0], pair[1], true));
+            adjacency[pair[0]].push(virtual_edge);
+            adjacency[pair[1]].push(virtual_edge);
+        }
+    }
+
+    let mut trails: Vec<LineString> = Vec::new();
+    let mut used = vec![false; edges.len()];
+    let mut next_unused = vec![0; points.len()];
+    for start in component_starts {
+        // Hierholzer's algorithm. Each step records the node, and the edge used to reach it.
+        let mut stack: Vec<(usize, Option<usize>)> = vec![(start, None)];
+        let mut circuit = Vec::new();
+        while let Some(&(node, _)) = stack.last() {
+            let adjacent = &adjacency[node];
+            while next_unused[node] < adjacent.len() && used[adjacent[next_unused[node]]] {
+                next_unused[node] += 1;
+            }
+
+            if next_unused[node] == adjacent.len() {
+                circuit.push(stack.pop().unwrap());
+            } else {
+                let edge = adjacent[next_unused[node]];
+                used[edge] = true;
+                let (source, target, _) = edges[edge];
+                let neighbor = if source == node { target } else { source };
+                stack.push((neighbor, Some(edge)));
+            }
+        }
+        circuit.reverse();
+
+        // Cut the circuit at each of the virtual edges
+        let mut pieces: Vec<Vec<usize>> = vec![vec![start]];
+        for (node, edge) in circuit.into_iter().skip(1) {
+            let edge = edge.expect("Only the first step of the circuit has no edge");
+            if edges[edge].2 {
+                pieces.push(vec![node]);
+            } else {
+                pieces.last_mut().unwrap().push(node);
+            }
+        }
+        // The circuit is closed, so if it was cut, the last piece continues into the first
+        if pieces.len() > 1 {
+            let mut last = pieces.pop().unwrap();
+            last.extend_from_slice(&pieces[0][1..]);
+            pieces[0] = last;
+        }
+
+        let component_trails = pieces
+            .into_iter()
+            .filter(|p| p.len() > 1)
+            .map(|p| LineString::from(p.into_iter().map(|n| points[n]).collect::<Vec<_>>()));
+        trails.extend(component_trails);
+    }
+
+    trails
+}
+
+/// Write the traversals within the length limits, labeled with their lengths if asked to
+fn write_traversals<W>(
+    writer: W,
+    traversals: impl Iterator<Item = LineString>,
+    args: &CmdlineOptions,
+) where
+    W: Write,
+{
+    let min = args.min_path_length.unwrap_or(f64::NEG_INFINITY);
+    let max = args.max_path_length.unwrap_or(f64::INFINITY);
+    let traversals = traversals
+        .map(|t| (t.length::<Euclidean>(), t))
+        .filter(move |(length, _)| (min..=max).contains(length));
+
+    if args.emit_lengths {
+        let labeled =
+            traversals.map(|(length, t)| (Some(length.to_string()), Geometry::LineString(t)));
+        write_labeled_wkt_geometries(writer, labeled);
+    } else {
+        let traversals = traversals.map(|(_, t)| Geometry::LineString(t));
+        write_geometries(writer, traversals, args.output_format);
+    }
+}
+
 fn main() {
     let args = CmdlineOptions::parse();
 
@@ -156,12 +318,38 @@ fn main() {
         .init()
         .expect("Failed to initialize stderrlog");
 
+    if args.emit_lengths && !matches!(args.output_format, GeometryFormat::Wkt) {
+        eprintln!("--emit-lengths requires --output-format=wkt");
+        std::process::exit(1);
+    }
+
     let seed = generate_random_seed_if_not_specified(args.seed);
     log::info!("Seeding RNG with: {}", seed);
     let mut rng = StdRng::seed_from_u64(seed);
 
     let reader = get_input_reader(&args.input).unwrap();
-    let mut graph: GeometryGraph<Undirected> = read_tgf_graph(reader);
+    let mut graph: GeometryGraph<Undirected> = read_graph(reader);
+    if args.largest_component {
+        graph = largest_component(&graph);
+    }
+
+    if args.cover_all_edges {
+        let trails = cover_all_edges(&graph);
+        log::debug!("Covered all edges with {} trails", trails.len());
+
+        let mut writer = get_output_writer(&args.output).unwrap();
+        write_traversals(&mut writer, trails.into_iter(), &args);
+
+        // Only the isolated nodes are left untraversed
+        if args.untraversed {
+            let isolated = graph
+                .node_indices()
+                .filter(|n| graph.neighbors(*n).next().is_none())
+                .map(|n| Geometry::Point(graph[n]));
+            write_geometries(&mut writer, isolated, args.output_format);
+        }
+        return;
+    }
 
     let mut num_traversals = if args.random_traversals {
         let n = args.traversals * 2; // changes mean
@@ -197,11 +385,10 @@ fn main() {
         )
     })
     .take(num_traversals as usize)
-    .flatten()
-    .map(Geometry::LineString);
+    .flatten();
 
     let mut writer = get_output_writer(&args.output).unwrap();
-    write_geometries(&mut writer, traversals, args.output_format);
+    write_traversals(&mut writer, traversals, &args);
 
     // dump the remaining nodes
     if args.untraversed {
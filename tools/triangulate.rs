@@ -2,10 +2,16 @@ use std::path::PathBuf;
 
 use clap::{Parser, ValueEnum};
 use generative::flatten::flatten_geometries_into_points;
+use generative::graph::GeometryGraph;
+use generative::io::framed::serve;
 use generative::io::{
-    get_input_reader, get_output_writer, read_geometries, write_graph, GeometryFormat, GraphFormat,
+    get_input_reader, get_output_writer, read_geometries, read_polygons, write_graph_with_options,
+    write_wkt_geometries, GeometryInputFormat, GraphFormat, Progress, TgfOptions,
 };
-use generative::triangulation::triangulate;
+use generative::triangulation::{refine, triangulate, Triangulation};
+use geo::{Geometry, LineString, MultiPolygon};
+use petgraph::graph::NodeIndex;
+use petgraph::Undirected;
 use stderrlog::ColorChoice;
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -31,20 +37,181 @@ struct CmdlineOptions {
     output: Option<PathBuf>,
 
     /// Output geometry format.
+    ///
+    /// The TGF format writes the Delaunay graph, suitable for traverse or snap.
     #[clap(short = 'O', long, default_value_t = GraphFormat::Wkt)]
     output_format: GraphFormat,
 
+    #[clap(flatten)]
+    tgf: TgfOptions,
+
     /// Input file to read input from. Defaults to stdin.
     #[clap(short, long)]
     input: Option<PathBuf>,
 
     /// Input geometry format.
-    #[clap(short = 'I', long, default_value_t = GeometryFormat::Wkt)]
-    input_format: GeometryFormat,
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// Periodically log how many geometries have been processed to stderr
+    #[clap(long)]
+    progress: bool,
 
     /// How to triangulate the input geometries
     #[clap(short, long, default_value = "whole-collection")]
     strategy: TriangulationStrategy,
+
+    /// Keep running, and triangulate length-prefixed batches of WKB geometries
+    ///
+    /// Each batch is a little-endian u32 byte count followed by that many bytes of raw WKB
+    /// geometries. Each batch is answered with a batch of the triangulation edges as LINESTRINGs.
+    /// Ignores --input-format and --output-format.
+    #[clap(long)]
+    serve: bool,
+
+    /// Only triangulate the POINTs and MULTIPOINTs, and output the other geometries unchanged
+    ///
+    /// The other geometries are written after the triangulation. Requires --output-format=wkt.
+    #[clap(long)]
+    passthrough: bool,
+
+    /// Refine the triangulation into a quality mesh with Ruppert's algorithm
+    ///
+    /// Steiner points are added until no triangle has an angle smaller than --min-angle.
+    #[clap(long)]
+    refine: bool,
+
+    /// The smallest angle, in degrees, to allow in a refined triangulation
+    ///
+    /// Refinement is only guaranteed to finish for angles up to about 20.7 degrees.
+    #[clap(long, default_value_t = 20.0, requires = "refine")]
+    min_angle: f64,
+
+    /// Stop refining once the triangulation has this many points
+    #[clap(long, default_value_t = 100_000, requires = "refine")]
+    max_points: usize,
+
+    /// A WKT file of POLYGONs to clip the triangulation to
+    ///
+    /// Triangles whose centroids are outside of the polygons, or inside their holes, are discarded.
+    /// The triangle edges don't follow the boundary, so it's best to include the boundary's
+    /// vertices in the input points. Applied after --refine.
+    #[clap(long)]
+    clip_boundary: Option<PathBuf>,
+}
+
+/// The refinement to apply to each triangulation, if any
+#[derive(Debug, Clone, Copy)]
+struct Refinement {
+    min_angle: f64,
+    max_points: usize,
+}
+
+impl Refinement {
+    fn from_args(args: &CmdlineOptions) -> Option<Self> {
+        args.refine.then_some(Refinement {
+            min_angle: args.min_angle,
+            max_points: args.max_points,
+        })
+    }
+
+    fn apply(refinement: Option<Self>, triangulation: Triangulation) -> Triangulation {
+        match refinement {
+            Some(r) => refine(triangulation, r.min_angle, r.max_points),
+            None => triangulation,
+        }
+    }
+}
+
+/// Get the graph of the triangulation, clipped to the boundary if there is one
+fn clipped_graph(
+    triangulation: &Triangulation,
+    boundary: Option<&MultiPolygon>,
+) -> GeometryGraph<Undirected> {
+    match boundary {
+        Some(boundary) => triangulation.clipped_graph(boundary),
+        None => triangulation.graph(),
+    }
+}
+
+/// Split off the geometries that aren't POINTs or MULTIPOINTs, if passing them through
+fn split_passthrough(
+    geometries: impl Iterator<Item = Geometry>,
+    passthrough: bool,
+) -> (Vec<Geometry>, Vec<Geometry>) {
+    if passthrough {
+        geometries.partition(|g| matches!(g, Geometry::Point(_) | Geometry::MultiPoint(_)))
+    } else {
+        (geometries.collect(), Vec::new())
+    }
+}
+
+/// Triangulate the given geometries into a single graph
+///
+/// With the each-geometry strategy, each triangulation is a disconnected component of the graph, so
+/// that the whole result can be written as one TGF graph.
+fn triangulate_graph(
+    geometries: impl Iterator<Item = Geometry>,
+    strategy: &TriangulationStrategy,
+    refinement: Option<Refinement>,
+    boundary: Option<&MultiPolygon>,
+) -> GeometryGraph<Undirected> {
+    match strategy {
+        TriangulationStrategy::EachGeometry => {
+            let mut graph = GeometryGraph::default();
+            let triangulations = geometries
+                .map(|geom| flatten_geometries_into_points(std::iter::once(geom)))
+                .filter_map(triangulate)
+                .map(|triangulation| Refinement::apply(refinement, triangulation));
+            for triangulation in triangulations {
+                let component = clipped_graph(&triangulation, boundary);
+                let offset = graph.node_count();
+                for node in component.node_weights() {
+                    graph.add_node(*node);
+                }
+                for edge in component.raw_edges() {
+                    let src = NodeIndex::new(edge.source().index() + offset);
+                    let dst = NodeIndex::new(edge.target().index() + offset);
+                    graph.add_edge(src, dst, ());
+                }
+            }
+            graph
+        }
+        TriangulationStrategy::WholeCollection => {
+            triangulate(flatten_geometries_into_points(geometries))
+                .map(|triangulation| {
+                    let triangulation = Refinement::apply(refinement, triangulation);
+                    clipped_graph(&triangulation, boundary)
+                })
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Triangulate a batch of geometries, and return the triangulation edges
+fn triangulate_batch(
+    batch: Vec<Geometry>,
+    strategy: &TriangulationStrategy,
+    passthrough: bool,
+    refinement: Option<Refinement>,
+    boundary: Option<&MultiPolygon>,
+) -> Vec<Geometry> {
+    let (points, passthrough) = split_passthrough(batch.into_iter(), passthrough);
+    let graph = triangulate_graph(points.into_iter(), strategy, refinement, boundary);
+    graph
+        .raw_edges()
+        .iter()
+        .map(|edge| {
+            let src = graph[edge.source()];
+            let dst = graph[edge.target()];
+            Geometry::LineString(LineString::from(vec![src.0, dst.0]))
+        })
+        .chain(passthrough)
+        .collect()
 }
 
 fn main() {
@@ -56,26 +223,53 @@ fn main() {
         .init()
         .expect("Failed to initialize stderrlog");
 
+    if args.passthrough && !args.serve && !matches!(args.output_format, GraphFormat::Wkt) {
+        eprintln!("--passthrough requires --output-format=wkt");
+        std::process::exit(1);
+    }
+
+    let refinement = Refinement::from_args(&args);
+    let boundary = match &args.clip_boundary {
+        Some(path) => match read_polygons(path) {
+            Ok(boundary) => Some(boundary),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let boundary = boundary.as_ref();
     let reader = get_input_reader(&args.input).unwrap();
     let mut writer = get_output_writer(&args.output).unwrap();
-    let geometries = read_geometries(reader, &args.input_format); // lazily loaded
 
-    match args.strategy {
-        TriangulationStrategy::EachGeometry => {
-            let triangulations = geometries
-                .map(|geom| flatten_geometries_into_points(std::iter::once(geom)))
-                .filter_map(triangulate);
-            for triangulation in triangulations {
-                let graph = triangulation.graph();
-                write_graph(&mut writer, &graph, &args.output_format);
-            }
-        }
-        TriangulationStrategy::WholeCollection => {
-            let points = flatten_geometries_into_points(geometries);
-            if let Some(triangulation) = triangulate(points) {
-                let graph = triangulation.graph();
-                write_graph(writer, &graph, &args.output_format);
-            }
-        }
+    if args.serve {
+        serve(reader, writer, |batch| {
+            triangulate_batch(
+                batch,
+                &args.strategy,
+                args.passthrough,
+                refinement,
+                boundary,
+            )
+        })
+        .expect("Failed to serve triangulations");
+        return;
+    }
+
+    let geometries = read_geometries(reader, &args.input_format) // lazily loaded
+        .take(args.limit.unwrap_or(usize::MAX));
+
+    if args.passthrough {
+        let (points, passthrough) = split_passthrough(geometries, true);
+        let total = points.len();
+        let points = Progress::new(points.into_iter(), args.progress).total(total);
+        let graph = triangulate_graph(points, &args.strategy, refinement, boundary);
+        write_graph_with_options(&mut writer, &graph, &args.output_format, &args.tgf);
+        write_wkt_geometries(writer, passthrough);
+    } else {
+        let geometries = Progress::new(geometries, args.progress);
+        let graph = triangulate_graph(geometries, &args.strategy, refinement, boundary);
+        write_graph_with_options(writer, &graph, &args.output_format, &args.tgf);
     }
 }
@@ -3,7 +3,8 @@ use std::path::PathBuf;
 use clap::Parser;
 use generative::flatten::flatten_geometries_into_points;
 use generative::io::{
-    get_input_reader, get_output_writer, read_geometries, write_graph, GeometryFormat, GraphFormat,
+    get_input_reader, get_output_writer, read_geometries, write_graph_with_options,
+    GeometryInputFormat, GraphFormat, TgfOptions,
 };
 use generative::triangulation::triangulate;
 use stderrlog::ColorChoice;
@@ -26,13 +27,20 @@ struct CmdlineOptions {
     #[clap(short = 'O', long, default_value_t = GraphFormat::Wkt)]
     output_format: GraphFormat,
 
+    #[clap(flatten)]
+    tgf: TgfOptions,
+
     /// Input file to read input from. Defaults to stdin.
     #[clap(short, long)]
     input: Option<PathBuf>,
 
     /// Input geometry format.
-    #[clap(short = 'I', long, default_value_t = GeometryFormat::Wkt)]
-    input_format: GeometryFormat,
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
 }
 
 fn main() {
@@ -45,13 +53,14 @@ fn main() {
         .expect("Failed to initialize stderrlog");
 
     let reader = get_input_reader(&args.input).unwrap();
-    let geometries = read_geometries(reader, &args.input_format); // lazily loaded
+    let geometries = read_geometries(reader, &args.input_format) // lazily loaded
+        .take(args.limit.unwrap_or(usize::MAX));
 
     let points = flatten_geometries_into_points(geometries);
     if let Some(triangulation) = triangulate(points) {
         let urquhart = triangulation.urquhart();
 
         let writer = get_output_writer(&args.output).unwrap();
-        write_graph(writer, &urquhart, &args.output_format);
+        write_graph_with_options(writer, &urquhart, &args.output_format, &args.tgf);
     }
 }
@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+use generative::flatten::flatten_geometries_into_points;
+use generative::io::{
+    get_input_reader, get_output_writer, read_geometries, read_polygons, write_geometries,
+    GeometryFormat, GeometryInputFormat,
+};
+use generative::triangulation::triangulate;
+use geo::{
+    BooleanOps, BoundingRect, Geometry, LineString, MultiPoint, MultiPolygon, Polygon, Rect,
+};
+use stderrlog::ColorChoice;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum VoronoiFormat {
+    /// Output a POLYGON cell around each point
+    Cells,
+    /// Output each edge between two adjacent cells as a LINESTRING
+    Edges,
+}
+
+impl std::fmt::Display for VoronoiFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // important: Should match clap::ValueEnum format
+            VoronoiFormat::Cells => write!(f, "cells"),
+            VoronoiFormat::Edges => write!(f, "edges"),
+        }
+    }
+}
+
+/// Generate the Voronoi diagram of the given geometries
+///
+/// The geometries are flattened into a point cloud, and the Voronoi diagram is the dual of its
+/// Delaunay triangulation. The cells around the points on the convex hull are unbounded, so the
+/// diagram is clipped to the points' bounding box, or to the given clip boundary.
+///
+/// Examples:
+///     point-cloud --points 30 | voronoi | wkt2svg
+///     point-cloud --points 30 | voronoi --output-format edges --clip circle.wkt | wkt2svg
+#[derive(Debug, Parser)]
+#[clap(name = "voronoi", verbatim_doc_comment)]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// Output file to write result to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Whether to output the Voronoi cells or their edges, as WKT
+    #[clap(short = 'O', long, default_value_t = VoronoiFormat::Cells)]
+    output_format: VoronoiFormat,
+
+    /// Input file to read input from. Defaults to stdin.
+    #[clap(short, long)]
+    input: Option<PathBuf>,
+
+    /// Input geometry format.
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// The margin around the points' bounding box to clip the diagram to
+    ///
+    /// Given as a fraction of the larger side of the bounding box. Ignored with --clip.
+    #[clap(long, default_value_t = 0.1)]
+    margin: f64,
+
+    /// A WKT file of POLYGONs to clip the diagram to, instead of the bounding box
+    ///
+    /// Cells split by the boundary are written as MULTIPOLYGONs, and cells entirely outside of it
+    /// are skipped.
+    #[clap(long, visible_alias = "clip")]
+    clip_boundary: Option<PathBuf>,
+}
+
+/// Pad the bounding box by the given fraction of its larger side
+fn padded(rect: Rect, margin: f64) -> Rect {
+    let pad = margin * rect.width().max(rect.height());
+    let pad = geo::coord! {x: pad, y: pad};
+    Rect::new(rect.min() - pad, rect.max() + pad)
+}
+
+/// Clip the cell to the boundary, if there is one
+fn clip_cell(cell: Polygon, boundary: Option<&MultiPolygon>) -> Option<Geometry> {
+    let Some(boundary) = boundary else {
+        return Some(Geometry::Polygon(cell));
+    };
+    let mut clipped = cell.intersection(boundary);
+    match clipped.0.len() {
+        0 => None,
+        1 => clipped.0.pop().map(Geometry::Polygon),
+        _ => Some(Geometry::MultiPolygon(clipped)),
+    }
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    if args.margin.is_nan() || args.margin < 0.0 {
+        eprintln!("--margin must be non-negative");
+        std::process::exit(1);
+    }
+
+    let boundary = match &args.clip_boundary {
+        Some(path) => match read_polygons(path) {
+            Ok(boundary) => Some(boundary),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let boundary = boundary.as_ref();
+
+    let reader = get_input_reader(&args.input).unwrap();
+    let geometries = read_geometries(reader, &args.input_format) // lazily loaded
+        .take(args.limit.unwrap_or(usize::MAX));
+
+    let points: Vec<_> = flatten_geometries_into_points(geometries).collect();
+    let bounds = match boundary {
+        Some(boundary) => boundary.bounding_rect(),
+        None => MultiPoint::new(points.clone())
+            .bounding_rect()
+            .map(|rect| padded(rect, args.margin)),
+    };
+    let Some(bounds) = bounds else {
+        log::warn!("No points to build a Voronoi diagram from");
+        return;
+    };
+    let Some(triangulation) = triangulate(points.into_iter()) else {
+        return;
+    };
+
+    let diagram: Vec<Geometry> = match args.output_format {
+        VoronoiFormat::Cells => triangulation
+            .voronoi(bounds)
+            .into_iter()
+            .flatten()
+            .filter_map(|cell| clip_cell(cell, boundary))
+            .collect(),
+        VoronoiFormat::Edges => {
+            let edges = triangulation
+                .voronoi_edges(bounds)
+                .into_iter()
+                .map(LineString::from);
+            match boundary {
+                Some(boundary) => boundary
+                    .clip(&edges.collect(), false)
+                    .into_iter()
+                    .map(Geometry::LineString)
+                    .collect(),
+                None => edges.map(Geometry::LineString).collect(),
+            }
+        }
+    };
+    if diagram.is_empty() {
+        log::warn!("The Voronoi diagram is empty");
+    }
+
+    let writer = get_output_writer(&args.output).unwrap();
+    write_geometries(writer, diagram, GeometryFormat::Wkt);
+}
@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use generative::io::{get_input_reader, get_output_writer, read_geometries, GeometryInputFormat};
+use generative::raster::{parse_color, RasterRenderer};
+use image::codecs::png::PngEncoder;
+use image::Rgba;
+use stderrlog::ColorChoice;
+
+/// Rasterize the given geometries to a PNG, for quick previews
+///
+/// Lines are drawn one pixel wide, and the WKT styles used by wkt2svg are ignored. Colors are
+/// given as '#rrggbb', '#rrggbbaa', or one of a few basic color names.
+///
+/// Examples:
+///     ... | wkt2png --width 400 --antialias | display -
+///     ... | wkt2png --fill '#ff000080' --output /tmp/geometries.png
+#[derive(Debug, Parser)]
+#[clap(name = "wkt2png", verbatim_doc_comment)]
+struct CmdlineOptions {
+    /// The log level
+    #[clap(short, long, default_value_t = log::Level::Info)]
+    log_level: log::Level,
+
+    /// Input file to read input from. Defaults to stdin.
+    #[clap(short, long)]
+    input: Option<PathBuf>,
+
+    /// Input geometry format.
+    #[clap(short = 'I', long, default_value_t = GeometryInputFormat::Wkt)]
+    input_format: GeometryInputFormat,
+
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
+    /// Output file to write the PNG to. Defaults to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// The width of the image in pixels
+    #[clap(short = 'W', long, default_value_t = 800)]
+    width: u32,
+
+    /// The height of the image in pixels. Defaults to preserving the geometries' aspect ratio.
+    #[clap(short = 'H', long)]
+    height: Option<u32>,
+
+    /// The padding around the geometries in pixels
+    #[clap(long, default_value_t = 3)]
+    padding: u32,
+
+    /// The radius of the POINTs in pixels
+    #[clap(long, default_value_t = 1.0)]
+    point_radius: f64,
+
+    /// The color to draw the geometries with
+    #[clap(long, default_value = "black", value_parser = parse_color)]
+    stroke: Rgba<u8>,
+
+    /// The color to fill the polygons with. Polygons aren't filled by default.
+    #[clap(long, value_parser = parse_color)]
+    fill: Option<Rgba<u8>>,
+
+    /// The background color. Use 'none' for a transparent background.
+    #[clap(long, default_value = "white", value_parser = parse_color)]
+    background: Rgba<u8>,
+
+    /// Antialias the lines and points
+    #[clap(short, long)]
+    antialias: bool,
+}
+
+fn main() {
+    let args = CmdlineOptions::parse();
+
+    stderrlog::new()
+        .verbosity(args.log_level)
+        .color(ColorChoice::Auto)
+        .init()
+        .expect("Failed to initialize stderrlog");
+
+    if args.width == 0 || args.height == Some(0) {
+        eprintln!("--width and --height must be positive");
+        std::process::exit(1);
+    }
+
+    let reader = get_input_reader(&args.input).unwrap();
+    let geometries =
+        read_geometries(reader, &args.input_format).take(args.limit.unwrap_or(usize::MAX));
+
+    let renderer = RasterRenderer::new()
+        .width(args.width)
+        .height(args.height)
+        .padding(args.padding)
+        .point_radius(args.point_radius)
+        .stroke(args.stroke)
+        .fill(args.fill)
+        .background(args.background)
+        .antialias(args.antialias);
+    let image = renderer.render(geometries);
+
+    let writer = get_output_writer(&args.output).unwrap();
+    image
+        .write_with_encoder(PngEncoder::new(writer))
+        .expect("Failed to write PNG");
+}
@@ -1,17 +1,34 @@
+use std::io::{BufRead, Read};
 use std::path::PathBuf;
 
-use clap::{ArgGroup, Parser};
-use generative::flatten::flatten_nested_geometries;
+use clap::{ArgGroup, Parser, ValueEnum};
+#[cfg(feature = "parallel")]
+use generative::io::read_wkt_geometries_and_styles_parallel;
 use generative::io::{
-    get_input_reader, get_output_writer, read_wkt_geometries_and_styles, GeometryAndStyle, SvgStyle,
-};
-use geo::{
-    AffineOps, AffineTransform, BoundingRect, Coord, CoordsIter, Geometry, Line, LineString, Point,
-    Polygon, Rect, Triangle,
+    get_input_reader, get_output_writer, read_wkt_geometries_and_styles,
+    read_wkt_geometries_styles_and_z, GeometryAndStyle, SvgStyle,
 };
+use generative::svg::{Colormap, Gradient, SvgRenderer};
+use geo::{Coord, Euclidean, Length, Rect};
 use stderrlog::ColorChoice;
-use svg::node::element;
-use svg::Document;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ZMapping {
+    /// Map Z to the stroke color with --colormap
+    Color,
+    /// Map Z to the stroke width with --width-range
+    Width,
+}
+
+impl std::fmt::Display for ZMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            // important: Should match clap::ValueEnum format
+            ZMapping::Color => write!(f, "color"),
+            ZMapping::Width => write!(f, "width"),
+        }
+    }
+}
 
 /// Convert the given geometries to SVG
 ///
@@ -30,6 +47,10 @@ struct CmdlineOptions {
     #[clap(short, long)]
     input: Option<PathBuf>,
 
+    /// Only read the first N geometries from the input
+    #[clap(long)]
+    limit: Option<usize>,
+
     /// Output file to write result to. Defaults to stdout.
     #[clap(short, long)]
     output: Option<PathBuf>,
@@ -47,6 +68,9 @@ struct CmdlineOptions {
     viewbox: Option<Vec<f64>>,
 
     /// Use screen coordinates instead of Cartesian (y increases downwards)
+    ///
+    /// By default, the geometries are flipped about the horizontal centerline of the viewbox, so
+    /// that y increases upwards.
     #[clap(long)]
     screen_coordinates: bool,
 
@@ -54,6 +78,12 @@ struct CmdlineOptions {
     #[clap(short, long, default_value_t = false)]
     no_padding: bool,
 
+    /// Scale the stroke widths and point radii along with the geometries
+    ///
+    /// Otherwise the strokes stay the same width regardless of --scale or --viewbox.
+    #[clap(long)]
+    scale_stroke: bool,
+
     #[clap(long, default_value_t = 1.0)]
     point_radius: f64,
 
@@ -66,391 +96,277 @@ struct CmdlineOptions {
     #[clap(long)]
     stroke_dasharray: Option<String>,
 
+    /// Cycle through the given ';' separated dash patterns, one for each geometry drawn
+    ///
+    /// Example: --dash-cycle "4 2;8 4;2 2"
+    #[clap(long, value_delimiter = ';')]
+    dash_cycle: Vec<String>,
+
     #[clap(long, default_value = "none")]
     fill: String,
-}
 
-enum ScaleType {
-    Identity,
-    ScaleAndExpandViewBoxToFit(f64),
-    FitToViewBox(Rect),
-}
+    /// Define a gradient that can be used with FILL(url(#id)) or STROKE(url(#id)). May be repeated.
+    ///
+    /// Given as 'id:linear:x1,y1:x2,y2:color,color,...' or 'id:radial:cx,cy:r:color,color,...',
+    /// where the coordinates are fractions of each geometry's bounding box.
+    ///
+    /// Example: --gradient "grad1:linear:0,0:1,1:red,blue"
+    #[clap(long)]
+    gradient: Vec<Gradient>,
 
-impl From<&CmdlineOptions> for ScaleType {
-    fn from(options: &CmdlineOptions) -> Self {
-        match (options.scale, options.viewbox.as_ref()) {
-            (None, None) => Self::Identity,
-            (Some(scale), None) => Self::ScaleAndExpandViewBoxToFit(scale),
-            // The viewbox array is guaranteed to be 4 elements long by clap
-            (None, Some(v)) => {
-                let viewbox = Rect::new(Coord { x: v[0], y: v[1] }, Coord { x: v[2], y: v[3] });
-                Self::FitToViewBox(viewbox)
-            }
-            _ => unreachable!("--scale and --viewbox are mutually exclusive, as enforced by clap"),
-        }
-    }
-}
+    /// Style each geometry by its mean Z coordinate, scaled between the smallest and largest Z
+    ///
+    /// Overrides any STROKE or STROKEWIDTH styles in the input for geometries with Z coordinates.
+    /// Geometries without Z coordinates are drawn with the input styles.
+    #[clap(long)]
+    z_as: Option<ZMapping>,
 
-struct SvgOptions {
-    scale_type: ScaleType,
-    global_point_radius: f64,
-    overridden_point_radius: Option<f64>,
-    global_stroke: String,
-    overridden_stroke: Option<String>,
-    global_stroke_width: f64,
-    overridden_stroke_width: Option<f64>,
-    // This is the only global setting that may or may not be present
-    global_stroke_dasharray: Option<String>,
-    overridden_stroke_dasharray: Option<String>,
-    global_fill: String,
-    overridden_fill: Option<String>,
-}
+    /// Color each line by its length, scaled between the shortest and longest line
+    ///
+    /// Overrides any STROKE styles in the input for lines. Other geometries are drawn with the
+    /// input styles. Has to read all of the geometries before drawing any of them.
+    #[clap(long, conflicts_with = "z_as")]
+    color_by_length: bool,
 
-impl From<&CmdlineOptions> for SvgOptions {
-    fn from(options: &CmdlineOptions) -> Self {
-        Self {
-            scale_type: ScaleType::from(options),
-            global_point_radius: options.point_radius,
-            overridden_point_radius: None,
-            global_stroke: options.stroke.clone(),
-            overridden_stroke: None,
-            global_stroke_width: options.stroke_width,
-            overridden_stroke_width: None,
-            global_stroke_dasharray: options.stroke_dasharray.clone(),
-            overridden_stroke_dasharray: None,
-            global_fill: options.fill.clone(),
-            overridden_fill: None,
-        }
-    }
-}
+    /// The colormap to use for --z-as=color and --color-by-length
+    #[clap(long, default_value_t = Colormap::Viridis)]
+    colormap: Colormap,
 
-impl SvgOptions {
-    fn get_global_style(&self) -> element::Style {
-        // let style = element::Style::new("svg { stroke:black; stroke-width:2px; fill:none;}");
+    /// The (min, max) stroke widths to use for --z-as=width
+    #[clap(long, number_of_values = 2, default_values_t = [0.5, 4.0])]
+    width_range: Vec<f64>,
 
-        let style = if let Some(dasharray) = &self.global_stroke_dasharray {
-            format!("stroke-dasharray:{dasharray};")
-        } else {
-            String::new()
-        };
+    /// A file with the stroke width of each geometry, one number per line
+    ///
+    /// The widths are read in lockstep with the geometries, skipping the styles. Overrides any
+    /// STROKEWIDTH styles, and --z-as=width. Once the widths run out, the rest of the geometries
+    /// are drawn with the input styles.
+    #[clap(long)]
+    widths: Option<PathBuf>,
 
-        let style = format!(
-            "{} stroke:{}; stroke-width:{}; fill:{};",
-            style, self.global_stroke, self.global_stroke_width, self.global_fill
-        );
+    /// A file with a text label for each geometry, one label per line
+    ///
+    /// The labels are read in lockstep with the geometries, skipping the styles, like a LABEL(text)
+    /// style before each geometry. Empty lines leave their geometry unlabeled.
+    #[clap(long)]
+    labels: Option<PathBuf>,
 
-        element::Style::new(format!("svg {{{style}}}"))
-    }
-}
+    /// The font size of the labels
+    #[clap(long, default_value_t = 12.0)]
+    label_size: f64,
 
-fn add_point_to_document(point: Point, document: Document, options: &SvgOptions) -> Document {
-    let radius = if let Some(radius) = options.overridden_point_radius {
-        radius
-    } else {
-        options.global_point_radius
-    };
-    let mut node = element::Circle::new()
-        .set("cx", point.x())
-        .set("cy", point.y())
-        .set("r", radius);
-
-    // This is an unfortunate bit of copy-pasta, but the `.set()` method doesn't come from a trait,
-    // so it's more difficult to make generic than I wanted.
-    if let Some(stroke) = options.overridden_stroke.as_ref() {
-        node = node.set("stroke", stroke.clone());
-    }
-    if let Some(width) = options.overridden_stroke_width.as_ref() {
-        node = node.set("stroke-width", *width);
-    }
-    if let Some(dasharray) = options.overridden_stroke_dasharray.as_ref() {
-        node = node.set("stroke-dasharray", dasharray.clone());
-    }
-    if let Some(fill) = options.overridden_fill.as_ref() {
-        node = node.set("fill", fill.clone());
-    }
-    document.add(node)
-}
+    /// The (x, y) offset of each label from its geometry's centroid
+    #[clap(long, number_of_values = 2, default_values_t = [2.0, 2.0], allow_negative_numbers = true)]
+    label_offset: Vec<f64>,
 
-fn add_line_to_document(line: Line, document: Document, options: &SvgOptions) -> Document {
-    let mut node = element::Line::new()
-        .set("x1", line.start.x)
-        .set("y1", line.start.y)
-        .set("x2", line.end.x)
-        .set("y2", line.end.y);
+    /// Round the coordinates in the SVG to this many decimal places, for smaller files
+    ///
+    /// The coordinates are rounded after scaling, so a precision of 1 or 2 is plenty for plotting
+    /// at the default scale.
+    #[clap(long)]
+    svg_precision: Option<usize>,
 
-    if let Some(stroke) = options.overridden_stroke.as_ref() {
-        node = node.set("stroke", stroke.clone());
-    }
-    if let Some(width) = options.overridden_stroke_width.as_ref() {
-        node = node.set("stroke-width", *width);
-    }
-    if let Some(dasharray) = options.overridden_stroke_dasharray.as_ref() {
-        node = node.set("stroke-dasharray", dasharray.clone());
-    }
-    if let Some(fill) = options.overridden_fill.as_ref() {
-        node = node.set("fill", fill.clone());
-    }
-    document.add(node)
-}
+    /// Group the geometries into a <g> for each geometry type, for editing the SVG by hand
+    ///
+    /// The groups have the classes "polygons", "lines", and "points", and are drawn in that order.
+    #[clap(long)]
+    group_by_type: bool,
 
-fn add_linestring_to_document(
-    linestring: LineString,
-    document: Document,
-    options: &SvgOptions,
-) -> Document {
-    let points: Vec<(f64, f64)> = linestring
-        .into_inner()
-        .into_iter()
-        .map(|c| c.into())
-        .collect();
-    let mut node = element::Polyline::new().set("points", points);
-
-    if let Some(stroke) = options.overridden_stroke.as_ref() {
-        node = node.set("stroke", stroke.clone());
-    }
-    if let Some(width) = options.overridden_stroke_width.as_ref() {
-        node = node.set("stroke-width", *width);
-    }
-    if let Some(dasharray) = options.overridden_stroke_dasharray.as_ref() {
-        node = node.set("stroke-dasharray", dasharray.clone());
-    }
-    if let Some(fill) = options.overridden_fill.as_ref() {
-        node = node.set("fill", fill.clone());
-    }
-    document.add(node)
-}
+    /// Add a <title> to the SVG
+    #[clap(long)]
+    title: Option<String>,
 
-fn add_ring_to_path_data(mut data: element::path::Data, ring: &LineString) -> element::path::Data {
-    if ring.coords_count() < 3 {
-        return data;
-    }
+    /// Add a <desc> to the SVG, like the command line or random seed used to generate it
+    #[clap(long)]
+    desc: Option<String>,
 
-    // Handle both closed and open rings
-    let end = if ring.0.first() == ring.0.last() {
-        ring.0.len() - 1
-    } else {
-        ring.0.len()
-    };
-    // Orientation doesn't matter in SVG path data.
-    let start: (f64, f64) = ring.0[0].into();
-    data = data.move_to(start);
-    for coord in &ring.0[1..end] {
-        let point = (coord.x, coord.y);
-        data = data.line_to(point);
-    }
-    data.close()
+    /// List the style directives that can be given on their own line in the input, and exit
+    ///
+    /// Each style applies to all of the geometries after it, until it's overridden.
+    #[clap(long)]
+    list_styles: bool,
 }
 
-fn add_polygon_to_document(polygon: Polygon, document: Document, options: &SvgOptions) -> Document {
-    let mut data = element::path::Data::new();
-    data = add_ring_to_path_data(data, polygon.exterior());
-    for interior in polygon.interiors() {
-        data = add_ring_to_path_data(data, interior);
-    }
-    let mut node = element::Path::new()
-        .set("fill-rule", "evenodd")
-        .set("d", data);
+impl From<&CmdlineOptions> for SvgRenderer {
+    fn from(options: &CmdlineOptions) -> Self {
+        let mut renderer = SvgRenderer::new()
+            .screen_coordinates(options.screen_coordinates)
+            .padding(!options.no_padding)
+            .scale_stroke(options.scale_stroke)
+            .point_radius(options.point_radius)
+            .stroke(options.stroke.clone())
+            .stroke_width(options.stroke_width)
+            .stroke_dasharray(options.stroke_dasharray.clone())
+            .dash_cycle(options.dash_cycle.clone())
+            .gradients(options.gradient.clone())
+            .fill(options.fill.clone())
+            .comment(Some(format!(
+                "generated by generative wkt2svg {}",
+                env!("CARGO_PKG_VERSION")
+            )))
+            .title(options.title.clone())
+            .description(options.desc.clone())
+            .group_by_type(options.group_by_type)
+            .precision(options.svg_precision)
+            .label_size(options.label_size)
+            .label_offset(Coord {
+                x: options.label_offset[0],
+                y: options.label_offset[1],
+            });
 
-    if let Some(stroke) = options.overridden_stroke.as_ref() {
-        node = node.set("stroke", stroke.clone());
-    }
-    if let Some(width) = options.overridden_stroke_width.as_ref() {
-        node = node.set("stroke-width", *width);
-    }
-    if let Some(dasharray) = options.overridden_stroke_dasharray.as_ref() {
-        node = node.set("stroke-dasharray", dasharray.clone());
-    }
-    if let Some(fill) = options.overridden_fill.as_ref() {
-        node = node.set("fill", fill.clone());
+        match (options.scale, options.viewbox.as_ref()) {
+            (None, None) => {}
+            (Some(scale), None) => renderer = renderer.scale(scale),
+            // The viewbox array is guaranteed to be 4 elements long by clap
+            (None, Some(v)) => {
+                let viewbox = Rect::new(Coord { x: v[0], y: v[1] }, Coord { x: v[2], y: v[3] });
+                renderer = renderer.viewbox(viewbox);
+            }
+            _ => unreachable!("--scale and --viewbox are mutually exclusive, as enforced by clap"),
+        }
+        renderer
     }
-    document.add(node)
 }
 
-fn add_rect_to_document(rect: Rect, document: Document, options: &SvgOptions) -> Document {
-    let mut node = element::Rectangle::new()
-        .set("x", rect.min().x)
-        .set("y", rect.max().y) // (x, y) is upper left corner
-        .set("width", rect.width())
-        .set("height", rect.height());
-
-    if let Some(stroke) = options.overridden_stroke.as_ref() {
-        node = node.set("stroke", stroke.clone());
-    }
-    if let Some(width) = options.overridden_stroke_width.as_ref() {
-        node = node.set("stroke-width", *width);
-    }
-    if let Some(dasharray) = options.overridden_stroke_dasharray.as_ref() {
-        node = node.set("stroke-dasharray", dasharray.clone());
-    }
-    if let Some(fill) = options.overridden_fill.as_ref() {
-        node = node.set("fill", fill.clone());
+/// The length of the geometry, if it's a line
+fn line_length(geometry: &GeometryAndStyle) -> Option<f64> {
+    match geometry {
+        GeometryAndStyle::Line(l) => Some(l.length::<Euclidean>()),
+        GeometryAndStyle::LineString(l) => Some(l.length::<Euclidean>()),
+        GeometryAndStyle::MultiLineString(l) => Some(l.length::<Euclidean>()),
+        _ => None,
     }
-    document.add(node)
 }
 
-fn add_triangle_to_document(
-    triangle: Triangle,
-    document: Document,
-    options: &SvgOptions,
-) -> Document {
-    let points: Vec<(f64, f64)> = triangle.to_array().into_iter().map(|c| c.into()).collect();
-    let mut node = element::Polygon::new().set("points", points);
-
-    if let Some(stroke) = options.overridden_stroke.as_ref() {
-        node = node.set("stroke", stroke.clone());
-    }
-    if let Some(width) = options.overridden_stroke_width.as_ref() {
-        node = node.set("stroke-width", *width);
-    }
-    if let Some(dasharray) = options.overridden_stroke_dasharray.as_ref() {
-        node = node.set("stroke-dasharray", dasharray.clone());
-    }
-    if let Some(fill) = options.overridden_fill.as_ref() {
-        node = node.set("fill", fill.clone());
-    }
-    document.add(node)
-}
+/// Insert a STROKE or STROKEWIDTH style before each geometry, mapped from a value like its mean Z
+/// coordinate or its length
+fn map_values_to_styles(
+    geometries: Vec<(GeometryAndStyle, Option<f64>)>,
+    mapping: ZMapping,
+    args: &CmdlineOptions,
+) -> Vec<GeometryAndStyle> {
+    let (min, max) = geometries
+        .iter()
+        .filter_map(|(_, value)| *value)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    let range = max - min;
+
+    // Restore the style from the input for any geometries without a value
+    let mut input_style = match mapping {
+        ZMapping::Color => SvgStyle::Stroke(args.stroke.clone()),
+        ZMapping::Width => SvgStyle::StrokeWidth(args.stroke_width),
+    };
 
-fn to_svg(
-    geometry: GeometryAndStyle,
-    transform: &Option<AffineTransform>,
-    document: Document,
-    options: &mut SvgOptions,
-) -> Document {
-    match geometry {
-        GeometryAndStyle::Style(style) => {
-            match style {
-                SvgStyle::PointRadius(r) => {
-                    if r == options.global_point_radius {
-                        options.overridden_point_radius = None;
-                    } else {
-                        options.overridden_point_radius = Some(r);
-                    }
-                }
-                SvgStyle::Stroke(s) => {
-                    if s == options.global_stroke {
-                        options.overridden_stroke = None;
-                    } else {
-                        options.overridden_stroke = Some(s);
+    let mut styled = Vec::with_capacity(2 * geometries.len());
+    for (geometry, z) in geometries {
+        match (&geometry, z) {
+            (GeometryAndStyle::Style(style), _) => match (mapping, style) {
+                (ZMapping::Color, SvgStyle::Stroke(_))
+                | (ZMapping::Width, SvgStyle::StrokeWidth(_)) => input_style = style.clone(),
+                _ => {}
+            },
+            (_, Some(value)) => {
+                let t = if range > 0.0 {
+                    (value - min) / range
+                } else {
+                    0.5
+                };
+                let style = match mapping {
+                    ZMapping::Color => SvgStyle::Stroke(args.colormap.color(t)),
+                    ZMapping::Width => {
+                        let (low, high) = (args.width_range[0], args.width_range[1]);
+                        SvgStyle::StrokeWidth(low + t * (high - low))
                     }
-                }
-                SvgStyle::StrokeWidth(w) => {
-                    if w == options.global_stroke_width {
-                        options.overridden_stroke_width = None;
-                    } else {
-                        options.overridden_stroke_width = Some(w);
-                    }
-                }
-                SvgStyle::StrokeDasharray(d) => {
-                    if Some(&d) == options.global_stroke_dasharray.as_ref()
-                        || d.is_empty()
-                        || d == "NONE"
-                    {
-                        options.overridden_stroke_dasharray = None;
-                    } else {
-                        options.overridden_stroke_dasharray = Some(d);
-                    }
-                }
-                SvgStyle::Fill(f) => {
-                    if f == options.global_fill {
-                        options.overridden_fill = None;
-                    } else {
-                        options.overridden_fill = Some(f);
-                    }
-                }
-            }
-            document
-        }
-        _ => {
-            let geometry: Geometry = geometry.into();
-            let transformed_geometry = if let Some(transform) = transform {
-                geometry.affine_transform(transform)
-            } else {
-                geometry
-            };
-
-            match transformed_geometry {
-                Geometry::Point(p) => add_point_to_document(p, document, options),
-                Geometry::Line(l) => add_line_to_document(l, document, options),
-                Geometry::LineString(l) => add_linestring_to_document(l, document, options),
-                Geometry::Polygon(p) => add_polygon_to_document(p, document, options),
-                Geometry::Rect(r) => add_rect_to_document(r, document, options),
-                Geometry::Triangle(t) => add_triangle_to_document(t, document, options),
-                _ => unreachable!("MULTI-geometries get flattened before conversion to SVG"),
+                };
+                styled.push(GeometryAndStyle::Style(style));
             }
+            (_, None) => styled.push(GeometryAndStyle::Style(input_style.clone())),
         }
+        styled.push(geometry);
     }
+    styled
 }
 
-fn expand_to_fit(bbox1: Option<Rect>, bbox2: Option<Rect>) -> Option<Rect> {
-    match (bbox1, bbox2) {
-        (None, None) => None,
-        (Some(b), None) | (None, Some(b)) => Some(b),
-        (Some(bbox1), Some(bbox2)) => {
-            let min1 = bbox1.min();
-            let min2 = bbox2.min();
-            let min = Coord {
-                x: f64::min(min1.x, min2.x),
-                y: f64::min(min1.y, min2.y),
-            };
-
-            let max1 = bbox1.max();
-            let max2 = bbox2.max();
-            let max = Coord {
-                x: f64::max(max1.x, max2.x),
-                y: f64::max(max1.y, max2.y),
-            };
-
-            Some(Rect::new(min, max))
+/// Insert a STROKEWIDTH style before each geometry, read from the next line of the widths
+fn apply_widths(
+    geometries: Vec<GeometryAndStyle>,
+    widths: impl BufRead,
+    args: &CmdlineOptions,
+) -> Vec<GeometryAndStyle> {
+    let mut widths = widths.lines().enumerate();
+    // Restore the width from the input after the widths run out, or for lines that aren't widths
+    let mut input_width = args.stroke_width;
+    let mut exhausted = false;
+
+    let mut styled = Vec::with_capacity(2 * geometries.len());
+    for geometry in geometries {
+        if let GeometryAndStyle::Style(style) = &geometry {
+            if let SvgStyle::StrokeWidth(width) = style {
+                input_width = *width;
+            }
+            styled.push(geometry);
+            continue;
         }
-    }
-}
 
-fn bounding_box<'g>(geometries: impl Iterator<Item = &'g GeometryAndStyle>) -> Option<Rect> {
-    let mut bbox = None;
-    for geometry in geometries {
-        let temp = match geometry {
-            GeometryAndStyle::Point(p) => Some(p.bounding_rect()),
-            GeometryAndStyle::Line(l) => Some(l.bounding_rect()),
-            GeometryAndStyle::LineString(l) => l.bounding_rect(),
-            GeometryAndStyle::Polygon(p) => p.bounding_rect(),
-            GeometryAndStyle::MultiPoint(m) => m.bounding_rect(),
-            GeometryAndStyle::MultiLineString(m) => m.bounding_rect(),
-            GeometryAndStyle::MultiPolygon(m) => m.bounding_rect(),
-            GeometryAndStyle::GeometryCollection(g) => g.bounding_rect(),
-            GeometryAndStyle::Rect(r) => Some(r.bounding_rect()),
-            GeometryAndStyle::Triangle(t) => Some(t.bounding_rect()),
-            GeometryAndStyle::Style(_) => None,
+        let width = match widths.next() {
+            Some((_, Ok(line))) => match line.trim().parse::<f64>() {
+                Ok(width) => width,
+                Err(e) => {
+                    log::warn!("Failed to parse width '{line}': {e}");
+                    input_width
+                }
+            },
+            Some((i, Err(e))) => {
+                log::warn!("Failed to read width on line {}: {e}", i + 1);
+                input_width
+            }
+            _ => {
+                if !exhausted {
+                    log::warn!("Ran out of --widths, using the input stroke widths for the rest");
+                    exhausted = true;
+                }
+                input_width
+            }
         };
-        bbox = expand_to_fit(bbox, temp);
+        styled.push(GeometryAndStyle::Style(SvgStyle::StrokeWidth(width)));
+        styled.push(geometry);
     }
-    bbox
+    styled
 }
 
-fn calculate_transform(
-    bounding_box: &Rect,
-    options: &SvgOptions,
-) -> (Option<AffineTransform>, Rect) {
-    match options.scale_type {
-        ScaleType::Identity => (None, *bounding_box),
-        ScaleType::ScaleAndExpandViewBoxToFit(scale) => {
-            let center = bounding_box.center();
-            let transform = AffineTransform::scale(scale, scale, center);
-            let viewbox = bounding_box.affine_transform(&transform);
-            (Some(transform), viewbox)
-        }
-        ScaleType::FitToViewBox(viewbox) => {
-            let offset = viewbox.center() - bounding_box.center();
-            let x_scale = viewbox.width() / bounding_box.width();
-            let y_scale = viewbox.height() / bounding_box.height();
-            let center = bounding_box.center();
-
-            let transform =
-                AffineTransform::translate(offset.x, offset.y).scaled(x_scale, y_scale, center);
+/// Insert a LABEL style before each geometry, read from the next line of the labels
+fn apply_labels(geometries: Vec<GeometryAndStyle>, labels: impl BufRead) -> Vec<GeometryAndStyle> {
+    let mut labels = labels.lines().enumerate();
 
-            (Some(transform), viewbox)
+    let mut labeled = Vec::with_capacity(2 * geometries.len());
+    for geometry in geometries {
+        if !matches!(geometry, GeometryAndStyle::Style(_)) {
+            match labels.next() {
+                Some((_, Ok(label))) if !label.trim().is_empty() => {
+                    labeled.push(GeometryAndStyle::Style(SvgStyle::Label(label)));
+                }
+                Some((i, Err(e))) => log::warn!("Failed to read label on line {}: {e}", i + 1),
+                _ => {}
+            }
         }
+        labeled.push(geometry);
     }
+    labeled
+}
+
+/// Read the geometries and styles, parsing them in parallel if the whole input gets read anyways
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+fn read_geometries_and_styles<R: Read + 'static>(
+    reader: R,
+    limit: Option<usize>,
+) -> Box<dyn Iterator<Item = GeometryAndStyle>> {
+    #[cfg(feature = "parallel")]
+    if limit.is_none() {
+        return Box::new(read_wkt_geometries_and_styles_parallel(reader).into_iter());
+    }
+    Box::new(read_wkt_geometries_and_styles(reader))
 }
 
 fn main() {
@@ -462,73 +378,69 @@ fn main() {
         .init()
         .expect("Failed to initialize stderrlog");
 
-    let reader = get_input_reader(&args.input).unwrap();
-    // Can't lazily convert to SVG because we have to know the whole collection's bounding box to
-    // know how to scale.
-    let geometries = read_wkt_geometries_and_styles(reader);
-
-    // Flatten any MULTI or GEOMETRYCOLLECTION geometries
-    let geometries: Vec<_> = geometries.collect();
-    // [g, g, g, s, g, s, s, g] => [g, g, g, s], [g, s], [s], [g]
-    let geometries_ending_with_styles =
-        geometries.split_inclusive(|x| matches!(x, GeometryAndStyle::Style(_)));
-    let flattened = geometries_ending_with_styles.map(|slice| {
-        if matches!(slice.last(), Some(GeometryAndStyle::Style(_))) {
-            let (style, geometries) = slice.split_last().unwrap();
-            // Have to convert to geo_types to flatten
-            let flattened = flatten_nested_geometries(geometries.iter().map(|g| g.clone().into()));
-            // But then need to convert back to add the style back in
-            let flattened = flattened.map(|g| g.into());
-            let mut flattened: Vec<GeometryAndStyle> = flattened.collect();
-            flattened.push(style.clone());
-            flattened
-        } else {
-            let flattened = flatten_nested_geometries(slice.iter().map(|g| g.clone().into()));
-            let flattened = flattened.map(|g| g.into());
-            flattened.collect()
+    if args.list_styles {
+        for style in SvgStyle::examples() {
+            println!("{style}\t{}", style.description());
         }
-    });
-    let geometries: Vec<_> = flattened.flatten().collect();
-
-    if geometries.is_empty() {
         return;
     }
-    let bbox = bounding_box(geometries.iter());
-    if bbox.is_none() {
-        log::error!("Failed to calculate geometry bounding box");
+
+    let reader = get_input_reader(&args.input).unwrap();
+    // Count only the geometries towards the limit, not the styles
+    let limit = args.limit.unwrap_or(usize::MAX);
+    let mut count = 0;
+    let mut within_limit = |g: &GeometryAndStyle| {
+        if !matches!(g, GeometryAndStyle::Style(_)) {
+            count += 1;
+        }
+        count <= limit
+    };
+    let geometries: Vec<_> = match args.z_as {
+        None if args.color_by_length => {
+            let geometries = read_geometries_and_styles(reader, args.limit)
+                .take_while(|g| within_limit(g))
+                .map(|g| {
+                    let length = line_length(&g);
+                    (g, length)
+                })
+                .collect();
+            map_values_to_styles(geometries, ZMapping::Color, &args)
+        }
+        None => read_geometries_and_styles(reader, args.limit)
+            .take_while(|g| within_limit(g))
+            .collect(),
+        Some(mapping) => {
+            let geometries = read_wkt_geometries_styles_and_z(reader)
+                .take_while(|(g, _)| within_limit(g))
+                .collect();
+            map_values_to_styles(geometries, mapping, &args)
+        }
+    };
+    if geometries.is_empty() {
         return;
     }
-    let bbox = bbox.unwrap();
-    let mut options = SvgOptions::from(&args);
-
-    let (transform, mut viewbox) = calculate_transform(&bbox, &options);
-    if !args.no_padding {
-        const PADDING: Coord = Coord { x: 3.0, y: 3.0 };
-        let min = viewbox.min();
-        let new_min = min - PADDING;
-        viewbox.set_min(new_min);
-
-        let max = viewbox.max();
-        let new_max = max + PADDING;
-        viewbox.set_max(new_max);
-    }
-    let min = viewbox.min();
-    let viewbox = (min.x, min.y, viewbox.width(), viewbox.height());
-    log::debug!(
-        "Transforming geometries with: {:?} to fit into viewBox {:?}",
-        transform,
-        viewbox
-    );
-
-    let mut document = Document::new().set("viewBox", viewbox);
-    if !args.screen_coordinates {
-        document = document.set("transform", "scale(1,-1)");
-    }
-    let style = options.get_global_style();
-    document = document.add(style);
-    for geometry in geometries {
-        document = to_svg(geometry, &transform, document, &mut options);
-    }
+    let geometries = match &args.widths {
+        Some(path) => match get_input_reader(&Some(path.clone())) {
+            Ok(widths) => apply_widths(geometries, widths, &args),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        },
+        None => geometries,
+    };
+    let geometries = match &args.labels {
+        Some(path) => match get_input_reader(&Some(path.clone())) {
+            Ok(labels) => apply_labels(geometries, labels),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        },
+        None => geometries,
+    };
+
+    let document = SvgRenderer::from(&args).render(geometries);
 
     let writer = get_output_writer(&args.output).unwrap();
     svg::write(writer, &document).unwrap();